@@ -0,0 +1,3 @@
+//! C library exporting the functions of `bls_signatures_rs::ffi` (see
+//! `include/bls_signatures.h`), built as a dynamic and a static library.
+pub use bls_signatures_rs::ffi::*;
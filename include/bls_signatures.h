@@ -0,0 +1,59 @@
+/* C declarations for the `ffi` feature of bls-signatures-rs. */
+#ifndef BLS_SIGNATURES_H
+#define BLS_SIGNATURES_H
+
+#include <stddef.h>
+#include <stdint.h>
+
+#define BLS_BN256_PUBLIC_KEY_LENGTH 65
+#define BLS_BN256_SIGNATURE_LENGTH 33
+
+typedef enum {
+    BLS_OK = 0,
+    BLS_NULL_POINTER = 1,
+    BLS_BUFFER_TOO_SMALL = 2,
+    BLS_INVALID_INPUT = 3,
+    BLS_VERIFICATION_FAILED = 4,
+    BLS_INTERNAL_ERROR = 5,
+} BlsStatus;
+
+/* Opaque bn256 engine handle, which can be shared between threads until it is released. */
+typedef struct Bn256 Bn256;
+
+Bn256 *bls_bn256_new(void);
+void bls_bn256_free(Bn256 *handle);
+
+BlsStatus bls_bn256_derive_public_key(Bn256 *handle,
+                                      const uint8_t *secret_key, size_t secret_key_length,
+                                      uint8_t *out, size_t out_length);
+
+BlsStatus bls_bn256_sign(Bn256 *handle,
+                         const uint8_t *secret_key, size_t secret_key_length,
+                         const uint8_t *message, size_t message_length,
+                         uint8_t *out, size_t out_length);
+
+BlsStatus bls_bn256_verify(Bn256 *handle,
+                           const uint8_t *signature, size_t signature_length,
+                           const uint8_t *message, size_t message_length,
+                           const uint8_t *public_key, size_t public_key_length);
+
+/* `public_keys` holds `count` concatenated compressed public keys. */
+BlsStatus bls_bn256_aggregate_public_keys(Bn256 *handle,
+                                          const uint8_t *public_keys, size_t count,
+                                          uint8_t *out, size_t out_length);
+
+/* `signatures` holds `count` concatenated compressed signatures. */
+BlsStatus bls_bn256_aggregate_signatures(Bn256 *handle,
+                                         const uint8_t *signatures, size_t count,
+                                         uint8_t *out, size_t out_length);
+
+/* `public_keys` holds `count` concatenated compressed public keys, while `messages` and
+ * `message_lengths` hold the `count` messages signed by each of them. */
+BlsStatus bls_bn256_aggregate_verify(Bn256 *handle,
+                                     const uint8_t *public_keys,
+                                     const uint8_t *const *messages,
+                                     const size_t *message_lengths,
+                                     size_t count,
+                                     const uint8_t *signature, size_t signature_length);
+
+#endif /* BLS_SIGNATURES_H */
@@ -0,0 +1,274 @@
+//! Incremental aggregation of signatures and public keys.
+//!
+//! `SignatureAggregator` keeps the running sums of the signatures (in G1) and of the public keys
+//! (in G2) added so far, so gossip-style systems can aggregate items as they arrive instead of
+//! buffering all of them until calling `aggregate_signatures` and `aggregate_public_keys` once.
+//! Every item is validated when added, so an invalid one is rejected without altering the sums.
+//!
+//! `AggregatePublicKey` maintains the aggregated key of a long-lived set of signers, such as a
+//! validator set, adding and removing (subtracting) keys as the set changes instead of summing all
+//! of them again on every change.
+use alloc::vec::Vec;
+use bn::{Group, G1, G2};
+
+use super::error::Error;
+use super::{g1_from_compressed, g1_to_compressed, PublicKey};
+
+/// Running aggregate of signatures and public keys.
+#[derive(Clone, Debug)]
+pub struct SignatureAggregator {
+    /// Sum of the signatures added
+    signature: G1,
+    /// Sum of the public keys added
+    public_key: G2,
+    /// Number of signatures added
+    signatures: usize,
+    /// Number of public keys added
+    public_keys: usize,
+}
+
+impl Default for SignatureAggregator {
+    fn default() -> Self {
+        SignatureAggregator::new()
+    }
+}
+
+impl SignatureAggregator {
+    /// Function to create an empty aggregator.
+    pub fn new() -> Self {
+        SignatureAggregator {
+            signature: G1::zero(),
+            public_key: G2::zero(),
+            signatures: 0,
+            public_keys: 0,
+        }
+    }
+
+    /// Function to add a signature to the aggregate.
+    ///
+    /// # Arguments
+    ///
+    /// * `signature` - The signature bytes (a point in G1 in compressed format)
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error` if the signature is not valid
+    pub fn add_signature(&mut self, signature: &[u8]) -> Result<(), Error> {
+        self.signature = self.signature + g1_from_compressed(signature)?;
+        self.signatures += 1;
+
+        Ok(())
+    }
+
+    /// Function to add a public key to the aggregate.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key` - The public key bytes (a point in G2 in compressed format)
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error` if the public key is not valid
+    pub fn add_public_key(&mut self, public_key: &[u8]) -> Result<(), Error> {
+        self.public_key = self.public_key + PublicKey::from_compressed(public_key)?.0;
+        self.public_keys += 1;
+
+        Ok(())
+    }
+
+    /// Function to get the number of signatures added.
+    pub fn signatures(&self) -> usize {
+        self.signatures
+    }
+
+    /// Function to get the number of public keys added.
+    pub fn public_keys(&self) -> usize {
+        self.public_keys
+    }
+
+    /// Function to get the aggregated signature and public key.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the compressed aggregated signature and public key; otherwise
+    ///   `Error::InvalidLength` if no signature has been added. If no public key has been added,
+    ///   the public key is the point at infinity (all zeros).
+    pub fn finalize(&self) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        if self.signatures == 0 {
+            return Err(Error::InvalidLength);
+        }
+
+        Ok((
+            g1_to_compressed(self.signature)?,
+            PublicKey(self.public_key).to_compressed()?,
+        ))
+    }
+}
+
+/// Aggregated public key of a set of signers that can change over time.
+#[derive(Clone, Debug)]
+pub struct AggregatePublicKey {
+    /// Sum of the public keys in the set
+    point: G2,
+    /// Number of public keys in the set
+    len: usize,
+}
+
+impl Default for AggregatePublicKey {
+    fn default() -> Self {
+        AggregatePublicKey::new()
+    }
+}
+
+impl AggregatePublicKey {
+    /// Function to create the aggregated public key of an empty set.
+    pub fn new() -> Self {
+        AggregatePublicKey {
+            point: G2::zero(),
+            len: 0,
+        }
+    }
+
+    /// Function to create the aggregated public key of a set of signers.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_keys` - The public key bytes of every signer
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `AggregatePublicKey`; otherwise `Error` if a public key is not valid
+    pub fn from_public_keys(public_keys: &[&[u8]]) -> Result<Self, Error> {
+        let mut aggregate = AggregatePublicKey::new();
+        for public_key in public_keys {
+            aggregate.add(public_key)?;
+        }
+
+        Ok(aggregate)
+    }
+
+    /// Function to add a public key to the set.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key` - The public key bytes (a point in G2 in compressed format)
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error` if the public key is not valid
+    pub fn add(&mut self, public_key: &[u8]) -> Result<(), Error> {
+        self.point = self.point + PublicKey::from_compressed(public_key)?.0;
+        self.len += 1;
+
+        Ok(())
+    }
+
+    /// Function to remove a public key from the set by subtracting it from the aggregate.
+    ///
+    /// The key is not checked to be part of the set, as the individual keys are not stored, so
+    /// only keys previously added should be removed.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key` - The public key bytes (a point in G2 in compressed format)
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error::InvalidLength` if the set is empty, or `Error`
+    ///   if the public key is not valid
+    pub fn remove(&mut self, public_key: &[u8]) -> Result<(), Error> {
+        if self.len == 0 {
+            return Err(Error::InvalidLength);
+        }
+        self.point = self.point - PublicKey::from_compressed(public_key)?.0;
+        self.len -= 1;
+
+        Ok(())
+    }
+
+    /// Function to get the number of public keys in the set.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Function to check whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Function to serialize the aggregated public key in compressed format (all zeros for the
+    /// empty set).
+    pub fn to_compressed(&self) -> Result<Vec<u8>, Error> {
+        PublicKey(self.point).to_compressed()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::Bn256;
+    use crate::MultiSignature;
+
+    /// Test that aggregating incrementally matches aggregating at once
+    #[test]
+    fn test_signature_aggregator() {
+        let bn256 = Bn256::default();
+        let secret_keys = [[1u8; 32], [2u8; 32], [3u8; 32]];
+        let public_keys: Vec<Vec<u8>> = secret_keys
+            .iter()
+            .map(|secret_key| bn256.derive_public_key(secret_key).unwrap())
+            .collect();
+        let signatures: Vec<Vec<u8>> = secret_keys
+            .iter()
+            .map(|secret_key| bn256.sign(secret_key, b"sample").unwrap())
+            .collect();
+
+        let mut aggregator = SignatureAggregator::new();
+        assert!(aggregator.finalize().is_err());
+        for (public_key, signature) in public_keys.iter().zip(&signatures) {
+            aggregator.add_signature(signature).unwrap();
+            aggregator.add_public_key(public_key).unwrap();
+        }
+        assert!(aggregator.add_signature(&public_keys[0]).is_err());
+        assert_eq!(aggregator.signatures(), 3);
+        assert_eq!(aggregator.public_keys(), 3);
+
+        let (signature, public_key) = aggregator.finalize().unwrap();
+        let public_keys: Vec<&[u8]> = public_keys.iter().map(Vec::as_slice).collect();
+        let signatures: Vec<&[u8]> = signatures.iter().map(Vec::as_slice).collect();
+        assert_eq!(signature, bn256.aggregate_signatures(&signatures).unwrap());
+        assert_eq!(
+            public_key,
+            bn256.aggregate_public_keys(&public_keys).unwrap()
+        );
+        assert!(bn256.verify(&signature, b"sample", &public_key).is_ok());
+    }
+
+    /// Test adding and removing public keys from an aggregated public key
+    #[test]
+    fn test_aggregate_public_key() {
+        let bn256 = Bn256::default();
+        let public_keys: Vec<Vec<u8>> = [[1u8; 32], [2u8; 32], [3u8; 32]]
+            .iter()
+            .map(|secret_key| bn256.derive_public_key(secret_key).unwrap())
+            .collect();
+
+        let mut aggregate =
+            AggregatePublicKey::from_public_keys(&[&public_keys[0], &public_keys[1]]).unwrap();
+        aggregate.add(&public_keys[2]).unwrap();
+        aggregate.remove(&public_keys[0]).unwrap();
+        assert_eq!(aggregate.len(), 2);
+        assert_eq!(
+            aggregate.to_compressed().unwrap(),
+            bn256
+                .aggregate_public_keys(&[&public_keys[1], &public_keys[2]])
+                .unwrap()
+        );
+
+        aggregate.remove(&public_keys[1]).unwrap();
+        aggregate.remove(&public_keys[2]).unwrap();
+        assert!(aggregate.is_empty());
+        assert_eq!(aggregate.to_compressed().unwrap(), vec![0u8; 65]);
+        assert!(aggregate.remove(&public_keys[0]).is_err());
+    }
+}
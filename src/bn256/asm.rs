@@ -0,0 +1,369 @@
+//! Accountable-subgroup multisignatures (ASM) from [Compact Multi-Signatures for Smaller Blockchains](https://eprint.iacr.org/2018/483) (section 5).
+//!
+//! A fixed group of `n` signers agrees on an aggregated public key `apk = a_1 * PK_1 + ... + a_n * PK_n`,
+//! where `a_i = H(PK_i, {PK_1, ..., PK_n})` are the coefficients used by
+//! `Bn256::aggregate_public_keys_delinearized`. In a one-time setup, every member `i` collects a
+//! share `mu_ji = (a_j * sk_j) * H2(apk, i)` from every member `j`, whose sum is the membership key
+//! `mk_i`. Afterwards, any subset `S` of the group can sign a message:
+//!
+//! - every signer computes `s_i = sk_i * H0(apk, PK, m) + mk_i`, where `PK` is the sum of the public keys in `S`
+//! - the signatures are summed into a single point in G1
+//! - the multisignature is checked together with a bitmap identifying exactly the members of `S`:
+//!   `e(H0(apk, PK, m), PK) * e(H2(apk, i_1) + ... + H2(apk, i_k), apk) = e(Signature, G2::one)`
+//!
+//! Bit `i` of the bitmap (least significant bit first within each byte) is set if member `i` signed.
+use alloc::vec::Vec;
+use bn::{Fr, Group, G1, G2};
+
+use super::error::Error;
+use super::hash_to_curve::hash_to_curve;
+use super::{
+    core, delinearization_coefficients, g1_from_compressed, g1_to_compressed, PrivateKey, PublicKey,
+};
+
+/// Domain separation tag used for hashing messages signed by a subgroup (`H0`).
+const ASM_SIGNATURE_DST: &[u8] = b"BLS_ASM_SIG_BN254G1_XMD:SHA-256_SVDW_RO_";
+
+/// Domain separation tag used for hashing the index of a member (`H2`).
+const ASM_MEMBERSHIP_DST: &[u8] = b"BLS_ASM_MEMBERSHIP_BN254G1_XMD:SHA-256_SVDW_RO_";
+
+/// A fixed group of signers able to produce accountable-subgroup multisignatures.
+pub struct SignerGroup {
+    /// Public key of every member
+    public_keys: Vec<G2>,
+    /// Coefficient of every member in the aggregated public key
+    coefficients: Vec<Fr>,
+    /// Aggregated public key of the group
+    aggregated_key: G2,
+    /// Compressed aggregated public key of the group
+    encoded_key: Vec<u8>,
+}
+
+impl SignerGroup {
+    /// Function to create a group of signers, in the given order, from their public keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_keys` - The public key bytes of every member
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `SignerGroup`; otherwise `Error`
+    pub fn new(public_keys: &[&[u8]]) -> Result<Self, Error> {
+        let coefficients = delinearization_coefficients(public_keys)?;
+        let public_keys = public_keys
+            .iter()
+            .map(|&public_key| PublicKey::from_compressed(public_key).map(|pk| pk.0))
+            .collect::<Result<Vec<G2>, Error>>()?;
+        let weighted: Vec<G2> = public_keys
+            .iter()
+            .zip(&coefficients)
+            .map(|(&public_key, &coefficient)| public_key * coefficient)
+            .collect();
+        let aggregated_key = core::aggregate_public_keys(&weighted);
+        let encoded_key = PublicKey(aggregated_key).to_compressed()?;
+
+        Ok(SignerGroup {
+            public_keys,
+            coefficients,
+            aggregated_key,
+            encoded_key,
+        })
+    }
+
+    /// Function to get the number of members of the group.
+    pub fn len(&self) -> usize {
+        self.public_keys.len()
+    }
+
+    /// Function to check whether the group has no members (never true for a created group).
+    pub fn is_empty(&self) -> bool {
+        self.public_keys.is_empty()
+    }
+
+    /// Function to get the aggregated public key of the group.
+    ///
+    /// # Returns
+    ///
+    /// * A vector of bytes with the compressed aggregated public key
+    pub fn aggregated_public_key(&self) -> Vec<u8> {
+        self.encoded_key.clone()
+    }
+
+    /// Function to compute the share of the membership key of `member` contributed by `signer`.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_key` - The secret key of the contributing member
+    /// * `signer`     - The index of the contributing member
+    /// * `member`     - The index of the member receiving the share
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the share (a point in G1)
+    pub fn membership_key_share(
+        &self,
+        secret_key: &[u8],
+        signer: usize,
+        member: usize,
+    ) -> Result<Vec<u8>, Error> {
+        let coefficient = *self
+            .coefficients
+            .get(signer)
+            .ok_or(Error::IndexOutOfBounds)?;
+        let private_key = PrivateKey::from_slice(secret_key)?;
+        let share = core::core_sign(private_key.0 * coefficient, self.hash_member(member)?);
+
+        g1_to_compressed(share)
+    }
+
+    /// Function to verify the share of the membership key of `member` contributed by `signer`.
+    ///
+    /// # Arguments
+    ///
+    /// * `share`  - The share bytes
+    /// * `signer` - The index of the contributing member
+    /// * `member` - The index of the member receiving the share
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    pub fn verify_membership_key_share(
+        &self,
+        share: &[u8],
+        signer: usize,
+        member: usize,
+    ) -> Result<(), Error> {
+        let public_key = *self
+            .public_keys
+            .get(signer)
+            .ok_or(Error::IndexOutOfBounds)?;
+        let weighted_key = public_key * self.coefficients[signer];
+
+        core::core_verify(
+            weighted_key,
+            self.hash_member(member)?,
+            g1_from_compressed(share)?,
+        )
+    }
+
+    /// Function to combine the shares received from every member into the membership key of `member`.
+    ///
+    /// # Arguments
+    ///
+    /// * `shares` - The share bytes contributed by every member of the group
+    /// * `member` - The index of the member receiving the shares
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the membership key (a point in G1)
+    pub fn combine_membership_key(
+        &self,
+        shares: &[&[u8]],
+        member: usize,
+    ) -> Result<Vec<u8>, Error> {
+        if shares.len() != self.len() {
+            return Err(Error::InvalidLength);
+        }
+        let shares = shares
+            .iter()
+            .map(|&share| g1_from_compressed(share))
+            .collect::<Result<Vec<G1>, Error>>()?;
+        let membership_key = core::aggregate_signatures(&shares);
+        core::core_verify(
+            self.aggregated_key,
+            self.hash_member(member)?,
+            membership_key,
+        )?;
+
+        g1_to_compressed(membership_key)
+    }
+
+    /// Function to sign a message as part of the subgroup of signers given by `signers`.
+    ///
+    /// The signatures of all the signers must be combined with `MultiSignature::aggregate_signatures`.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_key`     - The secret key of the signer
+    /// * `membership_key` - The membership key of the signer
+    /// * `member`         - The index of the signer
+    /// * `signers`        - The bitmap of the subgroup of signers
+    /// * `message`        - The message bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the signature
+    pub fn sign<M: AsRef<[u8]>>(
+        &self,
+        secret_key: &[u8],
+        membership_key: &[u8],
+        member: usize,
+        signers: &[u8],
+        message: M,
+    ) -> Result<Vec<u8>, Error> {
+        let message = message.as_ref();
+        let subgroup_key = self.subgroup_public_key(signers)?;
+        if !is_signer(signers, member) {
+            return Err(Error::InvalidSignerBitmap);
+        }
+        let private_key = PrivateKey::from_slice(secret_key)?;
+        let hash_point = self.hash_message(subgroup_key, message)?;
+        let signature =
+            core::core_sign(private_key.0, hash_point) + g1_from_compressed(membership_key)?;
+
+        g1_to_compressed(signature)
+    }
+
+    /// Function to verify a multisignature of the subgroup of signers given by `signers`.
+    ///
+    /// # Arguments
+    ///
+    /// * `signature` - The aggregated signature bytes
+    /// * `signers`   - The bitmap of the subgroup of signers
+    /// * `message`   - The message bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    pub fn verify<M: AsRef<[u8]>>(
+        &self,
+        signature: &[u8],
+        signers: &[u8],
+        message: M,
+    ) -> Result<(), Error> {
+        let message = message.as_ref();
+        let subgroup_key = self.subgroup_public_key(signers)?;
+        let hash_point = self.hash_message(subgroup_key, message)?;
+        let mut membership_point = G1::zero();
+        for member in (0..self.len()).filter(|&member| is_signer(signers, member)) {
+            membership_point = membership_point + self.hash_member(member)?;
+        }
+
+        core::core_aggregate_verify(
+            &[subgroup_key, self.aggregated_key],
+            &[hash_point, membership_point],
+            g1_from_compressed(signature)?,
+        )
+    }
+
+    /// Function to sum the public keys of the subgroup of signers, validating the bitmap.
+    fn subgroup_public_key(&self, signers: &[u8]) -> Result<G2, Error> {
+        // One bit per member, rounded up to whole bytes
+        if signers.len() * 8 < self.len() || signers.len() * 8 >= self.len() + 8 {
+            return Err(Error::InvalidSignerBitmap);
+        }
+        // Bits past the last member must not be set
+        if (self.len()..signers.len() * 8).any(|member| is_signer(signers, member)) {
+            return Err(Error::InvalidSignerBitmap);
+        }
+        let public_keys: Vec<G2> = self
+            .public_keys
+            .iter()
+            .enumerate()
+            .filter(|&(member, _)| is_signer(signers, member))
+            .map(|(_, &public_key)| public_key)
+            .collect();
+        if public_keys.is_empty() {
+            return Err(Error::InvalidSignerBitmap);
+        }
+
+        Ok(core::aggregate_public_keys(&public_keys))
+    }
+
+    /// Function to hash the index of a member to G1 (`H2(apk, i)`).
+    fn hash_member(&self, member: usize) -> Result<G1, Error> {
+        if member >= self.len() {
+            return Err(Error::IndexOutOfBounds);
+        }
+        let input = [&self.encoded_key[..], &(member as u64).to_be_bytes()].concat();
+
+        hash_to_curve(&input, ASM_MEMBERSHIP_DST)
+    }
+
+    /// Function to hash a message signed by a subgroup to G1 (`H0(apk, PK, m)`).
+    fn hash_message(&self, subgroup_key: G2, message: &[u8]) -> Result<G1, Error> {
+        let subgroup_key = PublicKey(subgroup_key).to_compressed()?;
+        let input = [&self.encoded_key[..], &subgroup_key[..], message].concat();
+
+        hash_to_curve(&input, ASM_SIGNATURE_DST)
+    }
+}
+
+/// Function to check whether the bit of `member` is set in the bitmap `signers`.
+fn is_signer(signers: &[u8], member: usize) -> bool {
+    member / 8 < signers.len() && signers[member / 8] & (1 << (member % 8)) != 0
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::Bn256;
+    use crate::MultiSignature;
+
+    /// Test signing and verifying with a subgroup of a group of three signers
+    #[test]
+    fn test_accountable_subgroup_multisignature() {
+        let secret_keys = [
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap(),
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap(),
+            hex::decode("0c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f672")
+                .unwrap(),
+        ];
+        let bn256 = Bn256::default();
+        let public_keys: Vec<Vec<u8>> = secret_keys
+            .iter()
+            .map(|secret_key| bn256.derive_public_key(secret_key).unwrap())
+            .collect();
+        let public_keys: Vec<&[u8]> = public_keys.iter().map(|pk| &pk[..]).collect();
+        let group = SignerGroup::new(&public_keys).unwrap();
+
+        // Setup: every member receives a share from every other member
+        let membership_keys: Vec<Vec<u8>> = (0..group.len())
+            .map(|member| {
+                let shares: Vec<Vec<u8>> = secret_keys
+                    .iter()
+                    .enumerate()
+                    .map(|(signer, secret_key)| {
+                        let share = group
+                            .membership_key_share(secret_key, signer, member)
+                            .unwrap();
+                        assert!(group
+                            .verify_membership_key_share(&share, signer, member)
+                            .is_ok());
+                        share
+                    })
+                    .collect();
+                let shares: Vec<&[u8]> = shares.iter().map(|share| &share[..]).collect();
+                group.combine_membership_key(&shares, member).unwrap()
+            })
+            .collect();
+
+        // Members 0 and 2 sign
+        let msg: &[u8] = b"sample";
+        let signers = [0b101];
+        let sign_0 = group
+            .sign(&secret_keys[0], &membership_keys[0], 0, &signers, msg)
+            .unwrap();
+        let sign_2 = group
+            .sign(&secret_keys[2], &membership_keys[2], 2, &signers, msg)
+            .unwrap();
+        let signature = bn256.aggregate_signatures(&[&sign_0, &sign_2]).unwrap();
+        assert!(group.verify(&signature, &signers, msg).is_ok());
+
+        // The bitmap must identify exactly who signed
+        assert!(group.verify(&signature, &[0b111], msg).is_err());
+        assert!(group.verify(&signature, &[0b001], msg).is_err());
+        assert!(group.verify(&signature, &signers, b"other").is_err());
+        match group.verify(&signature, &[0b1101], msg) {
+            Err(Error::InvalidSignerBitmap) => {}
+            _ => panic!("Expected InvalidSignerBitmap error"),
+        }
+        // A member outside the bitmap cannot sign
+        assert!(group
+            .sign(&secret_keys[1], &membership_keys[1], 1, &signers, msg)
+            .is_err());
+    }
+}
@@ -0,0 +1,278 @@
+//! Chained randomness beacons produced by a committee holding a threshold BLS key.
+//!
+//! The beacon of round `r` is the threshold signature of `SHA-256(previous_signature || r)` (see
+//! `drand::round_message`), where `previous_signature` is the signature of round `r - 1`, or a
+//! public genesis seed for the first round. As BLS signatures are unique, the committee cannot
+//! bias the randomness `SHA-256(signature)` of a round, and fewer than `t` members cannot predict
+//! it. Every round is produced as follows:
+//!
+//! 1. every member signs the round with `sign_round` and broadcasts its signature share
+//! 2. anybody checks the shares with `verify_round_share` and combines `t` valid ones with
+//!    `combine_round` into the `Beacon` of the round
+//!
+//! Clients holding only the group public key verify a single beacon with `Beacon::verify`, or catch
+//! up on a range of consecutive rounds with `verify_chain`, which checks all of them at the cost of
+//! two pairings. Beacons use the same encoding as `drand`, so they can also be checked with
+//! `drand::verify_beacon`.
+use alloc::vec::Vec;
+use bn::{Fr, Group, G1};
+use sha2::Sha256;
+
+use super::ciphersuite::BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL;
+use super::drand::{randomness, round_message};
+use super::error::Error;
+use super::hash_to_field::hash_to_field;
+use super::threshold::{combine, share_public_key_point, KeyShare, SignatureShare};
+use super::{core, drand, g1_from_compressed, g1_to_compressed, PublicKey};
+
+/// Domain separation tag used for deriving the coefficients of `verify_chain`.
+const CATCH_UP_DST: &[u8] = b"BLS_BEACON_CATCH_UP_BN254_XMD:SHA-256_";
+
+/// The beacon of a round.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Beacon {
+    /// Round number
+    pub round: u64,
+    /// Signature of the previous round (or genesis seed for the first round)
+    pub previous_signature: Vec<u8>,
+    /// Threshold signature of the round
+    pub signature: Vec<u8>,
+}
+
+impl Beacon {
+    /// Function to get the message signed in the round.
+    pub fn message(&self) -> Vec<u8> {
+        round_message(self.round, Some(&self.previous_signature))
+    }
+
+    /// Function to get the randomness of the round (the signature is not verified).
+    pub fn randomness(&self) -> Vec<u8> {
+        randomness(&self.signature)
+    }
+
+    /// Function to verify the beacon under the group public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key` - The group public key bytes
+    ///
+    /// # Returns
+    ///
+    /// * If the beacon is valid, a vector of 32 bytes with the randomness; otherwise `Error`
+    pub fn verify(&self, public_key: &[u8]) -> Result<Vec<u8>, Error> {
+        drand::verify_beacon(
+            public_key,
+            self.round,
+            Some(&self.previous_signature),
+            &self.signature,
+        )
+    }
+}
+
+/// Function to sign a round with the key share of a member.
+///
+/// # Arguments
+///
+/// * `share`              - The key share of the member
+/// * `round`              - The round number
+/// * `previous_signature` - The signature of the previous round (or genesis seed)
+///
+/// # Returns
+///
+/// * If successful, the `SignatureShare` of the member
+pub fn sign_round(
+    share: &KeyShare,
+    round: u64,
+    previous_signature: &[u8],
+) -> Result<SignatureShare, Error> {
+    let secret = Fr::from_slice(&share.secret_key)?;
+    let hash_point = hash_round(round, previous_signature)?;
+
+    Ok(SignatureShare {
+        index: share.index,
+        signature: g1_to_compressed(core::core_sign(secret, hash_point))?,
+    })
+}
+
+/// Function to verify the signature share of a round against the verification vector.
+///
+/// # Arguments
+///
+/// * `share`               - The signature share
+/// * `round`               - The round number
+/// * `previous_signature`  - The signature of the previous round (or genesis seed)
+/// * `verification_vector` - The verification vector of the committee
+///
+/// # Returns
+///
+/// * If the share is valid, `Ok(())`; otherwise `Error`
+pub fn verify_round_share(
+    share: &SignatureShare,
+    round: u64,
+    previous_signature: &[u8],
+    verification_vector: &[&[u8]],
+) -> Result<(), Error> {
+    let public_key = share_public_key_point(verification_vector, share.index)?;
+    let hash_point = hash_round(round, previous_signature)?;
+
+    core::core_verify(
+        public_key,
+        hash_point,
+        g1_from_compressed(&share.signature)?,
+    )
+}
+
+/// Function to combine the signature shares of a round into its beacon.
+///
+/// The shares should have been checked with `verify_round_share`. The combined signature is
+/// verified before returning the beacon.
+///
+/// # Arguments
+///
+/// * `round`              - The round number
+/// * `previous_signature` - The signature of the previous round (or genesis seed)
+/// * `shares`             - At least `threshold` signature shares of the round
+/// * `public_key`         - The group public key bytes
+///
+/// # Returns
+///
+/// * If successful, the `Beacon` of the round; otherwise `Error`
+pub fn combine_round(
+    round: u64,
+    previous_signature: &[u8],
+    shares: &[SignatureShare],
+    public_key: &[u8],
+) -> Result<Beacon, Error> {
+    let beacon = Beacon {
+        round,
+        previous_signature: previous_signature.to_vec(),
+        signature: combine(shares)?,
+    };
+    beacon.verify(public_key)?;
+
+    Ok(beacon)
+}
+
+/// Function to verify a range of consecutive beacons following a trusted signature.
+///
+/// Every beacon must link to the signature of the previous one, and all the signatures are checked
+/// at once with pseudo-random coefficients derived from the whole range:
+/// `e(r_1 * H(m_1) + ... + r_n * H(m_n), PK) = e(r_1 * S_1 + ... + r_n * S_n, G2::one)`.
+///
+/// # Arguments
+///
+/// * `public_key`         - The group public key bytes
+/// * `previous_signature` - The trusted signature preceding the first beacon (or genesis seed)
+/// * `beacons`            - The beacons of consecutive rounds
+///
+/// # Returns
+///
+/// * If all the beacons are valid, the randomness of every round; otherwise `Error`
+pub fn verify_chain(
+    public_key: &[u8],
+    previous_signature: &[u8],
+    beacons: &[Beacon],
+) -> Result<Vec<Vec<u8>>, Error> {
+    if beacons.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+    let PublicKey(public_key_point) = PublicKey::from_compressed(public_key)?;
+
+    let mut previous = previous_signature;
+    let mut hash_points = Vec::with_capacity(beacons.len());
+    let mut signatures = Vec::with_capacity(beacons.len());
+    let mut transcript = public_key.to_vec();
+    for (i, beacon) in beacons.iter().enumerate() {
+        if beacon.previous_signature != previous
+            || (i > 0 && beacon.round != beacons[i - 1].round.wrapping_add(1))
+        {
+            return Err(Error::BrokenChain);
+        }
+        hash_points.push(hash_round(beacon.round, previous)?);
+        signatures.push(g1_from_compressed(&beacon.signature)?);
+        transcript.extend_from_slice(&beacon.round.to_be_bytes());
+        transcript.extend_from_slice(&beacon.signature);
+        previous = &beacon.signature;
+    }
+
+    let coefficients = hash_to_field::<Fr, Sha256>(&transcript, CATCH_UP_DST, beacons.len())?;
+    let mut hash_point = G1::zero();
+    let mut signature = G1::zero();
+    for ((&hash, &sig), &coefficient) in hash_points.iter().zip(&signatures).zip(&coefficients) {
+        hash_point = hash_point + hash * coefficient;
+        signature = signature + sig * coefficient;
+    }
+    core::core_verify(public_key_point, hash_point, signature)?;
+
+    Ok(beacons.iter().map(Beacon::randomness).collect())
+}
+
+/// Function to hash the message of a round to G1.
+fn hash_round(round: u64, previous_signature: &[u8]) -> Result<G1, Error> {
+    BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL
+        .hash_to_curve(&round_message(round, Some(previous_signature)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::threshold::split;
+
+    /// Test producing a chain of beacons with a 2-of-3 committee and catching up on it
+    #[test]
+    fn test_beacon_chain() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let (shares, verification_vector) = split(&secret_key, b"seed", 2, 3).unwrap();
+        let verification_vector: Vec<&[u8]> = verification_vector.iter().map(|v| &v[..]).collect();
+        let public_key = verification_vector[0];
+        let genesis: &[u8] = b"genesis seed";
+
+        let mut beacons: Vec<Beacon> = Vec::new();
+        for round in 1..=3 {
+            let previous = beacons
+                .last()
+                .map_or(genesis, |beacon| &beacon.signature[..]);
+            let round_shares: Vec<SignatureShare> = [&shares[0], &shares[2]]
+                .iter()
+                .map(|&share| sign_round(share, round, previous).unwrap())
+                .collect();
+            for share in &round_shares {
+                assert!(verify_round_share(share, round, previous, &verification_vector).is_ok());
+            }
+            assert!(verify_round_share(
+                &round_shares[0],
+                round + 1,
+                previous,
+                &verification_vector
+            )
+            .is_err());
+            let beacon = combine_round(round, previous, &round_shares, public_key).unwrap();
+            assert_eq!(beacon.verify(public_key).unwrap(), beacon.randomness());
+            beacons.push(beacon);
+        }
+        // Not enough shares
+        let share = sign_round(&shares[1], 4, &beacons[2].signature).unwrap();
+        assert!(combine_round(4, &beacons[2].signature, &[share], public_key).is_err());
+
+        let randomness = verify_chain(public_key, genesis, &beacons).unwrap();
+        assert_eq!(randomness.len(), 3);
+        assert_eq!(randomness[2], beacons[2].randomness());
+        assert!(verify_chain(public_key, &beacons[0].signature, &beacons[1..]).is_ok());
+
+        // Missing round
+        match verify_chain(
+            public_key,
+            genesis,
+            &[beacons[0].clone(), beacons[2].clone()],
+        ) {
+            Err(Error::BrokenChain) => {}
+            _ => panic!("Expected BrokenChain error"),
+        }
+        // Invalid signature with a valid link
+        let mut forged = beacons.clone();
+        forged[2].signature = beacons[1].signature.clone();
+        assert!(verify_chain(public_key, genesis, &forged).is_err());
+    }
+}
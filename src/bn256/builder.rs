@@ -0,0 +1,242 @@
+//! Builder of configured bn256 engines.
+//!
+//! `Bn256Builder` gathers the settings of an application in one place: the hash function, the
+//! domain separation tag, the signature scheme and the validation strictness. `build` checks them
+//! and returns the engine, whose settings cannot be changed afterwards as every operation takes
+//! `&self`, so it can be shared across the application:
+//!
+//! ```
+//! use bls_signatures_rs::bn256::{Bn256, IdentityPolicy, Scheme};
+//!
+//! let bn256 = Bn256::builder()
+//!     .hash::<sha3::Keccak256>()
+//!     .scheme(Scheme::ProofOfPossession)
+//!     .identity_policy(IdentityPolicy::Reject)
+//!     .build()
+//!     .unwrap();
+//! ```
+use alloc::vec::Vec;
+use core::marker::PhantomData;
+use digest::generic_array::typenum::U32;
+use digest::Digest;
+use sha2::Sha256;
+
+use super::error::Error;
+use super::{Bn256, IdentityPolicy, Scheme};
+
+/// Builder of a `Bn256` engine (see `Bn256::builder`).
+pub struct Bn256Builder<D = Sha256> {
+    /// Engine with the settings configured so far
+    engine: Bn256<D>,
+}
+
+impl Bn256 {
+    /// Function to start configuring an engine, with the defaults of `Bn256::new` (SHA-256, try
+    /// and increment with a 1-byte counter, `Scheme::Basic` and `IdentityPolicy::Reject`).
+    ///
+    /// # Returns
+    ///
+    /// * A `Bn256Builder` instance.
+    pub fn builder() -> Bn256Builder {
+        Bn256Builder::default()
+    }
+}
+
+impl<D: Digest<OutputSize = U32>> Default for Bn256Builder<D> {
+    fn default() -> Self {
+        Bn256Builder {
+            engine: Bn256::new(),
+        }
+    }
+}
+
+impl<D: Digest<OutputSize = U32>> Bn256Builder<D> {
+    /// Function to select the hash function `H` used for hashing messages to G1 with try and
+    /// increment, e.g. `sha3::Keccak256` for signatures verified by Solidity contracts.
+    ///
+    /// # Returns
+    ///
+    /// * The `Bn256Builder` instance with the updated setting.
+    pub fn hash<H: Digest<OutputSize = U32>>(self) -> Bn256Builder<H> {
+        let engine = self.engine;
+        Bn256Builder {
+            engine: Bn256 {
+                digest: PhantomData,
+                scheme: engine.scheme,
+                public_key_binding: engine.public_key_binding,
+                allow_duplicate_messages: engine.allow_duplicate_messages,
+                counter_width: engine.counter_width,
+                max_attempts: engine.max_attempts,
+                identity_policy: engine.identity_policy,
+                dst: engine.dst,
+            },
+        }
+    }
+
+    /// Function to hash messages to G1 with `hash_to_curve::hash_to_curve` and the given domain
+    /// separation tag instead of try and increment, as `Bn256::sign_with_dst` does.
+    ///
+    /// Signatures are then only valid for this DST, so different applications cannot replay each
+    /// other's signatures. Streaming (see the `streaming` module) is not available with a DST.
+    ///
+    /// # Arguments
+    ///
+    /// * `dst` - The domain separation tag (not empty).
+    ///
+    /// # Returns
+    ///
+    /// * The `Bn256Builder` instance with the updated setting.
+    pub fn dst(mut self, dst: &[u8]) -> Self {
+        self.engine.dst = Some(dst.to_vec());
+
+        self
+    }
+
+    /// Function to select the signature scheme (see `Bn256::with_scheme`).
+    ///
+    /// # Arguments
+    ///
+    /// * `scheme` - The signature scheme.
+    ///
+    /// # Returns
+    ///
+    /// * The `Bn256Builder` instance with the updated setting.
+    pub fn scheme(mut self, scheme: Scheme) -> Self {
+        self.engine = self.engine.with_scheme(scheme);
+
+        self
+    }
+
+    /// Function to enable or disable binding the public key into the message hashing (see
+    /// `Bn256::with_public_key_binding`).
+    ///
+    /// # Arguments
+    ///
+    /// * `enabled` - Whether the public key should be bound into the hash.
+    ///
+    /// # Returns
+    ///
+    /// * The `Bn256Builder` instance with the updated setting.
+    pub fn public_key_binding(mut self, enabled: bool) -> Self {
+        self.engine = self.engine.with_public_key_binding(enabled);
+
+        self
+    }
+
+    /// Function to allow repeated messages in aggregate verification with `Scheme::Basic` (see
+    /// `Bn256::with_duplicate_messages`).
+    ///
+    /// # Arguments
+    ///
+    /// * `allowed` - Whether aggregate verification accepts repeated messages.
+    ///
+    /// # Returns
+    ///
+    /// * The `Bn256Builder` instance with the updated setting.
+    pub fn duplicate_messages(mut self, allowed: bool) -> Self {
+        self.engine = self.engine.with_duplicate_messages(allowed);
+
+        self
+    }
+
+    /// Function to configure the try and increment counter (see `Bn256::with_try_and_increment`).
+    ///
+    /// # Arguments
+    ///
+    /// * `counter_width` - The number of bytes of the counter (between 1 and 8).
+    /// * `max_attempts`  - The maximum number of attempts.
+    ///
+    /// # Returns
+    ///
+    /// * The `Bn256Builder` instance with the updated setting.
+    pub fn try_and_increment(mut self, counter_width: usize, max_attempts: u64) -> Self {
+        self.engine = self
+            .engine
+            .with_try_and_increment(counter_width, max_attempts);
+
+        self
+    }
+
+    /// Function to select whether verification accepts the point at infinity as public key or
+    /// signature (see `Bn256::with_identity_policy`).
+    ///
+    /// # Arguments
+    ///
+    /// * `policy` - The policy applied to the public keys and signatures being verified.
+    ///
+    /// # Returns
+    ///
+    /// * The `Bn256Builder` instance with the updated setting.
+    pub fn identity_policy(mut self, policy: IdentityPolicy) -> Self {
+        self.engine = self.engine.with_identity_policy(policy);
+
+        self
+    }
+
+    /// Function to check the settings and create the engine.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the configured `Bn256`; otherwise `Error::InvalidLength` if the try and
+    ///   increment counter is not between 1 and 8 bytes, or `Error::InvalidDstLength` if the DST
+    ///   is empty
+    pub fn build(self) -> Result<Bn256<D>, Error> {
+        if self.engine.counter_width == 0 || self.engine.counter_width > 8 {
+            return Err(Error::InvalidLength);
+        }
+        if self.engine.dst.as_ref().is_some_and(Vec::is_empty) {
+            return Err(Error::InvalidDstLength);
+        }
+
+        Ok(self.engine)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MultiSignature;
+
+    /// Test the engines created with the builder
+    #[test]
+    fn test_builder() {
+        let secret_key = [1u8; 32];
+
+        // The defaults match `Bn256::new`
+        let bn256 = Bn256::builder().build().unwrap();
+        assert_eq!(
+            bn256.sign(&secret_key, b"sample").unwrap(),
+            Bn256::default().sign(&secret_key, b"sample").unwrap()
+        );
+
+        let bn256 = Bn256::builder()
+            .hash::<sha3::Keccak256>()
+            .scheme(Scheme::MessageAugmentation)
+            .build()
+            .unwrap();
+        let expected = Bn256::<sha3::Keccak256>::new().with_scheme(Scheme::MessageAugmentation);
+        assert_eq!(
+            bn256.sign(&secret_key, b"sample").unwrap(),
+            expected.sign(&secret_key, b"sample").unwrap()
+        );
+
+        // A DST replaces try and increment by hash to curve
+        let dst = b"BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_TESTAPP_";
+        let bn256 = Bn256::builder().dst(dst).build().unwrap();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+        assert_eq!(
+            signature,
+            Bn256::default()
+                .sign_with_dst(&secret_key, b"sample", dst)
+                .unwrap()
+        );
+        assert!(bn256.verify(&signature, b"sample", &public_key).is_ok());
+        assert!(Bn256::default()
+            .verify(&signature, b"sample", &public_key)
+            .is_err());
+
+        assert!(Bn256::builder().dst(b"").build().is_err());
+        assert!(Bn256::builder().try_and_increment(9, 255).build().is_err());
+    }
+}
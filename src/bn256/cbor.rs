@@ -0,0 +1,405 @@
+//! Deterministic [CBOR](https://tools.ietf.org/html/rfc8949) encodings of keys, signatures and
+//! aggregate proofs (`cbor` feature).
+//!
+//! Keys and signatures are encoded as CBOR byte strings holding the bytes taken by the functions
+//! of this crate: 32 big endian bytes for secret keys and the compressed format for public keys
+//! and signatures. An `AggregateProof` (an aggregated signature together with the public keys and
+//! messages of its signers) is encoded as the array `[signature, [[public_key, message], ...]]`.
+//!
+//! Encodings follow the core deterministic encoding requirements (section 4.2 of RFC 8949):
+//! lengths are definite and in their shortest form. Decoding only accepts such encodings, with no
+//! trailing bytes, so every value has exactly one encoding.
+use alloc::vec::Vec;
+use zeroize::Zeroizing;
+
+use super::error::Error;
+use super::signature_set::SignatureSet;
+use super::{PrivateKey, PublicKey, Signature};
+
+/// Major type of byte strings.
+const BYTE_STRING: u8 = 2;
+
+/// Major type of arrays.
+const ARRAY: u8 = 4;
+
+/// An aggregated signature together with the public keys and messages of its signers.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AggregateProof {
+    /// The aggregated signature (a point in G1 in compressed format)
+    pub signature: Vec<u8>,
+    /// The public keys of the signers (points in G2 in compressed format)
+    pub public_keys: Vec<Vec<u8>>,
+    /// The message signed by every signer (in the same order)
+    pub messages: Vec<Vec<u8>>,
+}
+
+impl AggregateProof {
+    /// Function to create an `AggregateProof` from an aggregated signature and its signature set.
+    ///
+    /// # Arguments
+    ///
+    /// * `set`       - The public keys and messages of the signers
+    /// * `signature` - The aggregated signature bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `AggregateProof` with the points in canonical form; otherwise `Error`
+    pub fn new(set: &SignatureSet, signature: &[u8]) -> Result<Self, Error> {
+        Ok(AggregateProof {
+            signature: Signature::from_compressed(signature)?.to_compressed()?,
+            public_keys: set
+                .public_keys()
+                .iter()
+                .map(|public_key| PublicKey::from_compressed(public_key)?.to_compressed())
+                .collect::<Result<_, _>>()?,
+            messages: set
+                .messages()
+                .iter()
+                .map(|message| message.to_vec())
+                .collect(),
+        })
+    }
+
+    /// Function to get the `SignatureSet` of the proof, to be verified with
+    /// `Bn256::aggregate_verify_set`.
+    pub fn signature_set(&self) -> SignatureSet<'_> {
+        let mut set = SignatureSet::new();
+        for (public_key, message) in self.public_keys.iter().zip(&self.messages) {
+            set.push(public_key, message);
+        }
+
+        set
+    }
+
+    /// Function to encode the proof in CBOR.
+    pub fn to_cbor(&self) -> Vec<u8> {
+        let mut encoded = Vec::new();
+        write_header(&mut encoded, ARRAY, 2);
+        write_byte_string(&mut encoded, &self.signature);
+        write_header(&mut encoded, ARRAY, self.public_keys.len());
+        for (public_key, message) in self.public_keys.iter().zip(&self.messages) {
+            write_header(&mut encoded, ARRAY, 2);
+            write_byte_string(&mut encoded, public_key);
+            write_byte_string(&mut encoded, message);
+        }
+
+        encoded
+    }
+
+    /// Function to decode a proof from CBOR, validating its points.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The CBOR encoding of the proof
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `AggregateProof`; otherwise `Error`
+    pub fn from_cbor(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader(bytes);
+        if reader.read_header(ARRAY)? != 2 {
+            return Err(Error::InvalidCbor);
+        }
+        let signature = reader.read_byte_string()?.to_vec();
+        Signature::from_compressed(&signature)?;
+        let count = reader.read_header(ARRAY)?;
+        // Every signer takes at least 3 bytes, which bounds the allocations below
+        if count > reader.0.len() / 3 {
+            return Err(Error::InvalidCbor);
+        }
+        let mut public_keys = Vec::with_capacity(count);
+        let mut messages = Vec::with_capacity(count);
+        for _ in 0..count {
+            if reader.read_header(ARRAY)? != 2 {
+                return Err(Error::InvalidCbor);
+            }
+            let public_key = reader.read_byte_string()?;
+            PublicKey::from_compressed(public_key)?;
+            public_keys.push(public_key.to_vec());
+            messages.push(reader.read_byte_string()?.to_vec());
+        }
+        reader.finish()?;
+
+        Ok(AggregateProof {
+            signature,
+            public_keys,
+            messages,
+        })
+    }
+}
+
+/// Function to encode a secret key in CBOR.
+///
+/// # Arguments
+///
+/// * `secret_key` - The secret key bytes
+///
+/// # Returns
+///
+/// * If successful, the CBOR encoding of the secret key; otherwise `Error`
+pub fn secret_key_to_cbor(secret_key: &[u8]) -> Result<Vec<u8>, Error> {
+    let secret_key = Zeroizing::new(PrivateKey::from_slice(secret_key)?.to_bytes()?);
+
+    Ok(byte_string(&secret_key))
+}
+
+/// Function to decode a secret key from CBOR, validating it.
+///
+/// # Arguments
+///
+/// * `bytes` - The CBOR encoding of the secret key
+///
+/// # Returns
+///
+/// * If successful, the secret key bytes; otherwise `Error`
+pub fn secret_key_from_cbor(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let secret_key = read_single_byte_string(bytes)?;
+
+    PrivateKey::from_slice(secret_key)?.to_bytes()
+}
+
+/// Function to encode a public key in CBOR.
+///
+/// # Arguments
+///
+/// * `public_key` - The public key bytes (a point in G2 in compressed format)
+///
+/// # Returns
+///
+/// * If successful, the CBOR encoding of the public key; otherwise `Error`
+pub fn public_key_to_cbor(public_key: &[u8]) -> Result<Vec<u8>, Error> {
+    Ok(byte_string(
+        &PublicKey::from_compressed(public_key)?.to_compressed()?,
+    ))
+}
+
+/// Function to decode a public key from CBOR, validating it.
+///
+/// # Arguments
+///
+/// * `bytes` - The CBOR encoding of the public key
+///
+/// # Returns
+///
+/// * If successful, the public key bytes (a point in G2 in compressed format); otherwise `Error`
+pub fn public_key_from_cbor(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let public_key = read_single_byte_string(bytes)?;
+    PublicKey::from_compressed(public_key)?;
+
+    Ok(public_key.to_vec())
+}
+
+/// Function to encode a signature in CBOR.
+///
+/// # Arguments
+///
+/// * `signature` - The signature bytes (a point in G1 in compressed format)
+///
+/// # Returns
+///
+/// * If successful, the CBOR encoding of the signature; otherwise `Error`
+pub fn signature_to_cbor(signature: &[u8]) -> Result<Vec<u8>, Error> {
+    Ok(byte_string(
+        &Signature::from_compressed(signature)?.to_compressed()?,
+    ))
+}
+
+/// Function to decode a signature from CBOR, validating it.
+///
+/// # Arguments
+///
+/// * `bytes` - The CBOR encoding of the signature
+///
+/// # Returns
+///
+/// * If successful, the signature bytes (a point in G1 in compressed format); otherwise `Error`
+pub fn signature_from_cbor(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let signature = read_single_byte_string(bytes)?;
+    Signature::from_compressed(signature)?;
+
+    Ok(signature.to_vec())
+}
+
+/// Function to write the header of a data item with the shortest encoding of its argument.
+fn write_header(encoded: &mut Vec<u8>, major: u8, argument: usize) {
+    let major = major << 5;
+    let argument = argument as u64;
+    if argument < 24 {
+        encoded.push(major | argument as u8);
+    } else if argument <= 0xff {
+        encoded.extend_from_slice(&[major | 24, argument as u8]);
+    } else if argument <= 0xffff {
+        encoded.push(major | 25);
+        encoded.extend_from_slice(&(argument as u16).to_be_bytes());
+    } else if argument <= 0xffff_ffff {
+        encoded.push(major | 26);
+        encoded.extend_from_slice(&(argument as u32).to_be_bytes());
+    } else {
+        encoded.push(major | 27);
+        encoded.extend_from_slice(&argument.to_be_bytes());
+    }
+}
+
+/// Function to write a byte string.
+fn write_byte_string(encoded: &mut Vec<u8>, bytes: &[u8]) {
+    write_header(encoded, BYTE_STRING, bytes.len());
+    encoded.extend_from_slice(bytes);
+}
+
+/// Function to encode a byte string.
+fn byte_string(bytes: &[u8]) -> Vec<u8> {
+    let mut encoded = Vec::with_capacity(bytes.len() + 2);
+    write_byte_string(&mut encoded, bytes);
+
+    encoded
+}
+
+/// Function to decode an encoding made of a single byte string.
+fn read_single_byte_string(bytes: &[u8]) -> Result<&[u8], Error> {
+    let mut reader = Reader(bytes);
+    let byte_string = reader.read_byte_string()?;
+    reader.finish()?;
+
+    Ok(byte_string)
+}
+
+/// Reader of deterministically encoded CBOR.
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    /// Function to read `length` bytes.
+    fn read(&mut self, length: usize) -> Result<&'a [u8], Error> {
+        if self.0.len() < length {
+            return Err(Error::InvalidCbor);
+        }
+        let (bytes, rest) = self.0.split_at(length);
+        self.0 = rest;
+
+        Ok(bytes)
+    }
+
+    /// Function to read the header of a data item of major type `major`, returning its argument.
+    ///
+    /// Indefinite lengths and arguments not encoded in their shortest form are rejected.
+    fn read_header(&mut self, major: u8) -> Result<usize, Error> {
+        let initial = self.read(1)?[0];
+        if initial >> 5 != major {
+            return Err(Error::InvalidCbor);
+        }
+        let (argument, minimum) = match initial & 0x1f {
+            info @ 0..=23 => (u64::from(info), 0),
+            24 => (u64::from(self.read(1)?[0]), 24),
+            25 => {
+                let mut bytes = [0u8; 2];
+                bytes.copy_from_slice(self.read(2)?);
+                (u64::from(u16::from_be_bytes(bytes)), 0x100)
+            }
+            26 => {
+                let mut bytes = [0u8; 4];
+                bytes.copy_from_slice(self.read(4)?);
+                (u64::from(u32::from_be_bytes(bytes)), 0x1_0000)
+            }
+            27 => {
+                let mut bytes = [0u8; 8];
+                bytes.copy_from_slice(self.read(8)?);
+                (u64::from_be_bytes(bytes), 0x1_0000_0000)
+            }
+            _ => return Err(Error::InvalidCbor),
+        };
+        if argument < minimum || argument > self.0.len() as u64 {
+            return Err(Error::InvalidCbor);
+        }
+
+        Ok(argument as usize)
+    }
+
+    /// Function to read a byte string.
+    fn read_byte_string(&mut self) -> Result<&'a [u8], Error> {
+        let length = self.read_header(BYTE_STRING)?;
+        self.read(length)
+    }
+
+    /// Function to check that all the bytes have been read.
+    fn finish(&self) -> Result<(), Error> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(Error::InvalidCbor)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::Bn256;
+    use crate::MultiSignature;
+
+    /// Test encoding keys and signatures as CBOR byte strings
+    #[test]
+    fn test_cbor() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+
+        let encoded = secret_key_to_cbor(&secret_key).unwrap();
+        assert_eq!(encoded, [&[0x58, 0x20][..], &secret_key].concat());
+        assert_eq!(secret_key_from_cbor(&encoded).unwrap(), secret_key);
+        let encoded = public_key_to_cbor(&public_key).unwrap();
+        assert_eq!(encoded, [&[0x58, 0x41][..], &public_key].concat());
+        assert_eq!(public_key_from_cbor(&encoded).unwrap(), public_key);
+        let encoded = signature_to_cbor(&signature).unwrap();
+        assert_eq!(encoded, [&[0x58, 0x21][..], &signature].concat());
+        assert_eq!(signature_from_cbor(&encoded).unwrap(), signature);
+
+        // Non-shortest length, indefinite length, trailing bytes and wrong type
+        let mut long = vec![0x59, 0x00, 0x21];
+        long.extend_from_slice(&signature);
+        assert!(signature_from_cbor(&long).is_err());
+        assert!(
+            signature_from_cbor(&[&[0x5f, 0x58, 0x21][..], &signature, &[0xff]].concat()).is_err()
+        );
+        assert!(signature_from_cbor(&[&encoded[..], &[0x00]].concat()).is_err());
+        assert!(signature_from_cbor(&[&[0x78, 0x21][..], &signature].concat()).is_err());
+        assert!(public_key_from_cbor(&encoded).is_err());
+    }
+
+    /// Test encoding aggregate proofs and verifying them after decoding
+    #[test]
+    fn test_aggregate_proof_cbor() {
+        let secret_key1 =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let secret_key2 =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let public_key1 = bn256.derive_public_key(&secret_key1).unwrap();
+        let public_key2 = bn256.derive_public_key(&secret_key2).unwrap();
+        let signature1 = bn256.sign(&secret_key1, b"first").unwrap();
+        let signature2 = bn256.sign(&secret_key2, b"second").unwrap();
+        let signature = bn256
+            .aggregate_signatures(&[&signature1, &signature2])
+            .unwrap();
+
+        let mut set = SignatureSet::new();
+        set.push(&public_key1, b"first");
+        set.push(&public_key2, b"second");
+        let proof = AggregateProof::new(&set, &signature).unwrap();
+        let encoded = proof.to_cbor();
+        assert_eq!(&encoded[..3], &[0x82, 0x58, 0x21]);
+        assert_eq!(encoded[3 + 33], 0x82);
+
+        let decoded = AggregateProof::from_cbor(&encoded).unwrap();
+        assert_eq!(decoded, proof);
+        assert!(bn256
+            .aggregate_verify_set(&decoded.signature_set(), &decoded.signature)
+            .is_ok());
+
+        assert!(AggregateProof::from_cbor(&encoded[..encoded.len() - 1]).is_err());
+        assert!(AggregateProof::from_cbor(&[&encoded[..], &[0x00]].concat()).is_err());
+    }
+}
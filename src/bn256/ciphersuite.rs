@@ -0,0 +1,84 @@
+//! Ciphersuites following the naming of the [BLS IRTF draft](https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature) (section 4).
+//!
+//! A ciphersuite fixes every parameter required for interoperability: the hash to curve suite
+//! (`BN254G1_XMD:SHA-256_SVDW_RO_`), the signature scheme and the domain separation tag, which is
+//! the ciphersuite identifier itself:
+//!
+//! - `BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_NUL_` for the basic scheme
+//! - `BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_AUG_` for the message augmentation scheme
+//! - `BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_POP_` for the proof of possession scheme
+//!
+//! The `BLS_SIG_BN254G1_XMD:KECCAK-256_SVDW_RO_NUL_` ciphersuite of the drand `evmnet` network,
+//! which expands messages with Keccak-256, is provided as well.
+//!
+//! Custom ciphersuites (e.g. using the Fouque–Tibouchi map) can be defined by creating a
+//! `Ciphersuite` with a different identifier.
+use bn::G1;
+
+use super::error::Error;
+use super::hash_to_curve::{hash_to_curve_with_hash, HashFunction, MapToCurve};
+use super::Scheme;
+
+/// Parameters of a BLS ciphersuite.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Ciphersuite {
+    /// Ciphersuite identifier, used as domain separation tag for hashing messages.
+    pub id: &'static [u8],
+    /// Signature scheme.
+    pub scheme: Scheme,
+    /// Map from field elements to points in G1.
+    pub map: MapToCurve,
+    /// Hash function used for expanding messages.
+    pub hash: HashFunction,
+}
+
+impl Ciphersuite {
+    /// Function to hash a message to G1 with the map, the hash function and the identifier of the
+    /// ciphersuite as DST.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the hashed point in `G1`
+    pub fn hash_to_curve(&self, message: &[u8]) -> Result<G1, Error> {
+        hash_to_curve_with_hash(message, self.id, self.map, self.hash)
+    }
+}
+
+/// Basic scheme with the `BN254G1_XMD:SHA-256_SVDW_RO_` hash to curve suite.
+pub const BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL: Ciphersuite = Ciphersuite {
+    id: b"BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_NUL_",
+    scheme: Scheme::Basic,
+    map: MapToCurve::ShallueVanDeWoestijne,
+    hash: HashFunction::Sha256,
+};
+
+/// Message augmentation scheme with the `BN254G1_XMD:SHA-256_SVDW_RO_` hash to curve suite.
+pub const BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_AUG: Ciphersuite = Ciphersuite {
+    id: b"BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_AUG_",
+    scheme: Scheme::MessageAugmentation,
+    map: MapToCurve::ShallueVanDeWoestijne,
+    hash: HashFunction::Sha256,
+};
+
+/// Proof of possession scheme with the `BN254G1_XMD:SHA-256_SVDW_RO_` hash to curve suite.
+///
+/// Proofs of possession use the `POP_DST` domain separation tag.
+pub const BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_POP: Ciphersuite = Ciphersuite {
+    id: b"BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_POP_",
+    scheme: Scheme::ProofOfPossession,
+    map: MapToCurve::ShallueVanDeWoestijne,
+    hash: HashFunction::Sha256,
+};
+
+/// Basic scheme with the `BN254G1_XMD:KECCAK-256_SVDW_RO_` hash to curve suite, as used by the
+/// drand `evmnet` network.
+pub const BLS_SIG_BN254G1_XMD_KECCAK_256_SVDW_RO_NUL: Ciphersuite = Ciphersuite {
+    id: b"BLS_SIG_BN254G1_XMD:KECCAK-256_SVDW_RO_NUL_",
+    scheme: Scheme::Basic,
+    map: MapToCurve::ShallueVanDeWoestijne,
+    hash: HashFunction::Keccak256,
+};
@@ -0,0 +1,256 @@
+//! Core operations from the [BLS IRTF draft](https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature) (section 2).
+//!
+//! These functions operate directly over scalars and curve points, leaving the encoding of keys
+//! and signatures, the hashing of messages and the rules of each scheme to the callers. The
+//! `Bn256` engine builds the `Basic`, `MessageAugmentation` and `ProofOfPossession` schemes on top
+//! of them, and they can be used to build custom schemes as well:
+//!
+//! - `sk_to_pk`: derives the public key in G2
+//! - `key_validate`: checks a public key is not the identity and belongs to the subgroup of G2
+//! - `core_sign`: signs a message already hashed to G1
+//! - `core_verify`: checks a signature with the pairing equation `e(H(m), PK) = e(Signature, G2::one)`
+//! - `aggregate_signatures` / `aggregate_public_keys`: sum points in G1 / G2
+//! - `core_aggregate_verify`: checks an aggregated signature of `n` hashed messages with `n + 1` pairings
+//! - `core_batch_verify`: checks `n` independent signatures with `n + 1` pairings using coefficients
+use alloc::vec::Vec;
+use bn::{pairing_batch, Fr, Group, Gt, G1, G2};
+
+use super::error::Error;
+use super::hash_to_g2::is_in_subgroup_g2;
+
+/// Function to derive the public key (point in G2) of a secret key (`SkToPk`).
+///
+/// # Arguments
+///
+/// * `secret_key` - The secret key scalar.
+///
+/// # Returns
+///
+/// * The public key as a point in `G2`.
+pub fn sk_to_pk(secret_key: Fr) -> G2 {
+    G2::one() * secret_key
+}
+
+/// Function to validate a public key (`KeyValidate`).
+///
+/// The identity must be rejected, as the signature of any message for it is the identity too.
+///
+/// # Arguments
+///
+/// * `public_key` - The public key as a point in `G2`.
+///
+/// # Returns
+///
+/// * If successful, `Ok(())`; otherwise `Error`
+pub fn key_validate(public_key: G2) -> Result<(), Error> {
+    if public_key.is_zero() {
+        return Err(Error::IdentityPublicKey);
+    }
+    if !is_in_subgroup_g2(public_key) {
+        return Err(Error::InvalidGroupPoint);
+    }
+
+    Ok(())
+}
+
+/// Function to sign a message already hashed to G1 (`CoreSign`).
+///
+/// # Arguments
+///
+/// * `secret_key` - The secret key scalar.
+/// * `hash_point` - The message hashed to G1.
+///
+/// # Returns
+///
+/// * The signature as a point in `G1`.
+pub fn core_sign(secret_key: Fr, hash_point: G1) -> G1 {
+    hash_point * secret_key
+}
+
+/// Function to verify a signature of a message already hashed to G1 (`CoreVerify`).
+///
+/// # Arguments
+///
+/// * `public_key` - The public key as a point in `G2`.
+/// * `hash_point` - The message hashed to G1.
+/// * `signature`  - The signature as a point in `G1`.
+///
+/// # Returns
+///
+/// * If successful, `Ok(())`; otherwise `Error`
+pub fn core_verify(public_key: G2, hash_point: G1, signature: G1) -> Result<(), Error> {
+    core_aggregate_verify(&[public_key], &[hash_point], signature)
+}
+
+/// Function to aggregate signatures (sum of points in G1).
+///
+/// # Arguments
+///
+/// * `signatures` - The signatures to be aggregated.
+///
+/// # Returns
+///
+/// * The aggregated signature as a point in `G1`.
+pub fn aggregate_signatures(signatures: &[G1]) -> G1 {
+    signatures
+        .iter()
+        .fold(G1::zero(), |acc, &signature| acc + signature)
+}
+
+/// Function to aggregate public keys (sum of points in G2).
+///
+/// # Arguments
+///
+/// * `public_keys` - The public keys to be aggregated.
+///
+/// # Returns
+///
+/// * The aggregated public key as a point in `G2`.
+pub fn aggregate_public_keys(public_keys: &[G2]) -> G2 {
+    public_keys
+        .iter()
+        .fold(G2::zero(), |acc, &public_key| acc + public_key)
+}
+
+/// Function to verify an aggregated signature of several messages already hashed to G1
+/// (`CoreAggregateVerify`).
+///
+/// The check is performed as a single batch of `n + 1` pairings:
+/// `e(H(m_1), PK_1) * ... * e(H(m_n), PK_n) * e(-Signature, G2::one) = 1`.
+///
+/// # Arguments
+///
+/// * `public_keys` - The public key of every signer as points in `G2`.
+/// * `hash_points` - The message signed by every signer hashed to G1 (in the same order).
+/// * `signature`   - The aggregated signature as a point in `G1`.
+///
+/// # Returns
+///
+/// * If successful, `Ok(())`; otherwise `Error`
+pub fn core_aggregate_verify(
+    public_keys: &[G2],
+    hash_points: &[G1],
+    signature: G1,
+) -> Result<(), Error> {
+    if public_keys.is_empty() || public_keys.len() != hash_points.len() {
+        return Err(Error::InvalidLength);
+    }
+
+    let mut vals: Vec<(G1, G2)> = hash_points
+        .iter()
+        .cloned()
+        .zip(public_keys.iter().cloned())
+        .collect();
+    // Last pairing input: e(-Signature,G2::one())
+    vals.push((signature, -G2::one()));
+    // Pairing batch with one negated point
+    if pairing_batch(&vals) == Gt::one() {
+        Ok(())
+    } else {
+        Err(Error::VerificationFailed)
+    }
+}
+
+/// Function to verify several independent signatures at once, each of them scaled by a coefficient.
+///
+/// The check is performed as a single batch of `n + 1` pairings:
+/// `e(r_1 * H(m_1), PK_1) * ... * e(r_n * H(m_n), PK_n) * e(-(r_1 * S_1 + ... + r_n * S_n), G2::one) = 1`.
+/// The coefficients `r_i` must be unpredictable for whoever chose the signatures, otherwise
+/// invalid signatures cancelling each other could pass the check.
+///
+/// # Arguments
+///
+/// * `public_keys`  - The public key of every signature as points in `G2`.
+/// * `hash_points`  - The message of every signature hashed to G1.
+/// * `signatures`   - The signatures as points in `G1`.
+/// * `coefficients` - The coefficient of every signature.
+///
+/// # Returns
+///
+/// * If successful, `Ok(())`; otherwise `Error`
+pub fn core_batch_verify(
+    public_keys: &[G2],
+    hash_points: &[G1],
+    signatures: &[G1],
+    coefficients: &[Fr],
+) -> Result<(), Error> {
+    let n = public_keys.len();
+    if n == 0 || hash_points.len() != n || signatures.len() != n || coefficients.len() != n {
+        return Err(Error::InvalidLength);
+    }
+
+    let mut vals = Vec::with_capacity(n + 1);
+    let mut combined_signature = G1::zero();
+    for i in 0..n {
+        vals.push((hash_points[i] * coefficients[i], public_keys[i]));
+        combined_signature = combined_signature + signatures[i] * coefficients[i];
+    }
+    // Last pairing input: e(-Signature,G2::one())
+    vals.push((combined_signature, -G2::one()));
+    // Pairing batch with one negated point
+    if pairing_batch(&vals) == Gt::one() {
+        Ok(())
+    } else {
+        Err(Error::VerificationFailed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Test the core operations with points hashed to G1 by the caller
+    #[test]
+    fn test_core_sign_verify() {
+        let sk1 = Fr::from_str("12345").unwrap();
+        let sk2 = Fr::from_str("67890").unwrap();
+        let h1 = G1::one() * Fr::from_str("3").unwrap();
+        let h2 = G1::one() * Fr::from_str("5").unwrap();
+
+        let signature1 = core_sign(sk1, h1);
+        let signature2 = core_sign(sk2, h2);
+        assert!(core_verify(sk_to_pk(sk1), h1, signature1).is_ok());
+        assert!(core_verify(sk_to_pk(sk1), h2, signature1).is_err());
+
+        let aggregated = aggregate_signatures(&[signature1, signature2]);
+        let public_keys = [sk_to_pk(sk1), sk_to_pk(sk2)];
+        assert!(core_aggregate_verify(&public_keys, &[h1, h2], aggregated).is_ok());
+        assert!(core_aggregate_verify(&public_keys, &[h2, h1], aggregated).is_err());
+        assert!(core_aggregate_verify(&public_keys, &[h1], aggregated).is_err());
+
+        // Same message: the aggregated public key verifies the aggregated signature
+        let aggregated = aggregate_signatures(&[signature1, core_sign(sk2, h1)]);
+        let aggregated_key = aggregate_public_keys(&public_keys);
+        assert!(core_verify(aggregated_key, h1, aggregated).is_ok());
+    }
+
+    /// Test public key validation
+    #[test]
+    fn test_key_validate() {
+        assert!(key_validate(sk_to_pk(Fr::from_str("12345").unwrap())).is_ok());
+        match key_validate(G2::zero()) {
+            Err(Error::IdentityPublicKey) => {}
+            _ => panic!("Expected IdentityPublicKey error"),
+        }
+    }
+
+    /// Test batch verification of independent signatures
+    #[test]
+    fn test_core_batch_verify() {
+        let sk1 = Fr::from_str("12345").unwrap();
+        let sk2 = Fr::from_str("67890").unwrap();
+        let h1 = G1::one() * Fr::from_str("3").unwrap();
+        let h2 = G1::one() * Fr::from_str("5").unwrap();
+        let public_keys = [sk_to_pk(sk1), sk_to_pk(sk2)];
+        let coefficients = [Fr::from_str("7").unwrap(), Fr::from_str("11").unwrap()];
+
+        let signatures = [core_sign(sk1, h1), core_sign(sk2, h2)];
+        assert!(core_batch_verify(&public_keys, &[h1, h2], &signatures, &coefficients).is_ok());
+
+        // Invalid signatures cancelling each other are detected with distinct coefficients
+        let delta = G1::one();
+        let forged = [signatures[0] + delta, signatures[1] - delta];
+        assert!(core_aggregate_verify(&public_keys, &[h1, h2], forged[0] + forged[1]).is_ok());
+        assert!(core_batch_verify(&public_keys, &[h1, h2], &forged, &coefficients).is_err());
+    }
+}
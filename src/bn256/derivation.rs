@@ -0,0 +1,217 @@
+//! Hierarchical derivation of secret keys by path ([EIP-2333](https://eips.ethereum.org/EIPS/eip-2333)
+//! and [EIP-2334](https://eips.ethereum.org/EIPS/eip-2334)).
+//!
+//! The master secret key is `KeyGen(seed)` (see `keygen::key_gen`), and the child `index` of a
+//! parent secret key is derived through a Lamport public key, so that a child key does not reveal
+//! its parent:
+//!
+//! 1. `lamport_0 = HKDF-Expand(HKDF-Extract(I2OSP(index, 4), I2OSP(parent, 32)), "", 255 * 32)`
+//! 2. `lamport_1`, likewise with the bits of the parent key flipped
+//! 3. `lamport_PK = SHA-256(SHA-256(lamport_0[0]) || ... || SHA-256(lamport_1[254]))`
+//! 4. `child = KeyGen(lamport_PK)`
+//!
+//! Keys are reduced modulo the order of bn256, so they differ from the BLS12-381 keys derived from
+//! the same seed. Paths follow EIP-2334: `m/12381/3600/account/0` for withdrawal keys and
+//! `m/12381/3600/account/0/0` for signing keys.
+use ::core::fmt;
+use ::core::str::FromStr;
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+use zeroize::Zeroizing;
+
+use super::error::Error;
+use super::keygen::{hkdf_expand, hkdf_extract, key_gen};
+
+/// Purpose of the EIP-2334 paths.
+pub const PURPOSE: u32 = 12381;
+
+/// Coin type of the EIP-2334 paths.
+pub const COIN_TYPE: u32 = 3600;
+
+/// Number of chunks of a Lamport secret key.
+const LAMPORT_CHUNKS: usize = 255;
+
+/// A derivation path, the indices of the children walked from the master key.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DerivationPath(pub Vec<u32>);
+
+impl DerivationPath {
+    /// Function to get the EIP-2334 path of the withdrawal key of an account,
+    /// `m/12381/3600/account/0`.
+    pub fn withdrawal(account: u32) -> Self {
+        DerivationPath(vec![PURPOSE, COIN_TYPE, account, 0])
+    }
+
+    /// Function to get the EIP-2334 path of the signing key of an account,
+    /// `m/12381/3600/account/0/0`.
+    pub fn signing(account: u32) -> Self {
+        DerivationPath(vec![PURPOSE, COIN_TYPE, account, 0, 0])
+    }
+
+    /// Function to get the indices of the path.
+    pub fn indices(&self) -> &[u32] {
+        &self.0
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = Error;
+
+    /// Function to parse a path such as `m/12381/3600/0/0/0`, where every index is a decimal
+    /// number below `2^32`.
+    fn from_str(path: &str) -> Result<Self, Error> {
+        let mut components = path.split('/');
+        if components.next() != Some("m") {
+            return Err(Error::InvalidDerivationPath);
+        }
+
+        components
+            .map(|component| {
+                if component.is_empty() || !component.bytes().all(|b| b.is_ascii_digit()) {
+                    return Err(Error::InvalidDerivationPath);
+                }
+                component
+                    .parse::<u32>()
+                    .map_err(|_| Error::InvalidDerivationPath)
+            })
+            .collect::<Result<Vec<u32>, Error>>()
+            .map(DerivationPath)
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.0 {
+            write!(f, "/{}", index)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Function to derive the master secret key of a seed (`derive_master_SK`).
+///
+/// # Arguments
+///
+/// * `seed` - The secret seed, at least 32 bytes
+///
+/// # Returns
+///
+/// * If successful, a vector of 32 big endian bytes with the master secret key; otherwise `Error`
+pub fn derive_master_secret_key(seed: &[u8]) -> Result<Vec<u8>, Error> {
+    key_gen(seed, b"")
+}
+
+/// Function to derive a child secret key (`derive_child_SK`).
+///
+/// # Arguments
+///
+/// * `parent_secret_key` - The parent secret key bytes (32 bytes)
+/// * `index`             - The index of the child
+///
+/// # Returns
+///
+/// * If successful, a vector of 32 big endian bytes with the child secret key; otherwise `Error`
+pub fn derive_child_secret_key(parent_secret_key: &[u8], index: u32) -> Result<Vec<u8>, Error> {
+    if parent_secret_key.len() != 32 {
+        return Err(Error::InvalidLength);
+    }
+    let salt = index.to_be_bytes();
+    let flipped = Zeroizing::new(parent_secret_key.iter().map(|b| !b).collect::<Vec<u8>>());
+
+    let mut hasher = Sha256::new();
+    for ikm in [parent_secret_key, &flipped[..]].iter() {
+        let prk = Zeroizing::new(hkdf_extract(&salt, ikm));
+        let lamport_secret_key = Zeroizing::new(hkdf_expand(&prk, b"", LAMPORT_CHUNKS * 32));
+        for chunk in lamport_secret_key.chunks(32) {
+            hasher.input(Sha256::digest(chunk));
+        }
+    }
+
+    key_gen(&hasher.result(), b"")
+}
+
+/// Function to derive the secret key at a path from a seed.
+///
+/// # Arguments
+///
+/// * `seed` - The secret seed, at least 32 bytes
+/// * `path` - The derivation path
+///
+/// # Returns
+///
+/// * If successful, a vector of 32 big endian bytes with the secret key; otherwise `Error`
+pub fn derive_secret_key(seed: &[u8], path: &DerivationPath) -> Result<Vec<u8>, Error> {
+    let mut secret_key = Zeroizing::new(derive_master_secret_key(seed)?);
+    for &index in path.indices() {
+        secret_key = Zeroizing::new(derive_child_secret_key(&secret_key, index)?);
+    }
+
+    Ok(secret_key.to_vec())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::ToString;
+
+    /// Test parsing and printing derivation paths
+    #[test]
+    fn test_derivation_path() {
+        let path: DerivationPath = "m/12381/3600/0/0/0".parse().unwrap();
+        assert_eq!(path, DerivationPath::signing(0));
+        assert_eq!(path.to_string(), "m/12381/3600/0/0/0");
+        assert_eq!(
+            "m/12381/3600/7/0".parse::<DerivationPath>().unwrap(),
+            DerivationPath::withdrawal(7)
+        );
+        assert!("m".parse::<DerivationPath>().unwrap().indices().is_empty());
+
+        for invalid in [
+            "",
+            "12381/3600",
+            "m/",
+            "m//0",
+            "m/+1",
+            "m/0'",
+            "m/4294967296",
+            "M/0",
+        ]
+        .iter()
+        {
+            match invalid.parse::<DerivationPath>() {
+                Err(Error::InvalidDerivationPath) => {}
+                _ => panic!("Expected InvalidDerivationPath error"),
+            }
+        }
+    }
+
+    /// Test deriving keys from the seed of the EIP-2333 test case 0
+    #[test]
+    fn test_derive_secret_key() {
+        let seed = hex::decode("c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04").unwrap();
+        let master_secret_key = derive_master_secret_key(&seed).unwrap();
+        assert_eq!(
+            hex::encode(&master_secret_key),
+            "254fb2238bbfc89722432066d0e795cb2250d3df84dcd7901dc7f4328bedf2d8"
+        );
+        assert_eq!(
+            hex::encode(derive_child_secret_key(&master_secret_key, 0).unwrap()),
+            "0dd7b16c16e902c678d929aef36a1eb4d3f80bd6da589af6bb48396e80724547"
+        );
+
+        assert_eq!(
+            hex::encode(derive_secret_key(&seed, &DerivationPath::signing(0)).unwrap()),
+            "1ed417a2e54a0fef4ae8da6e5e21850388b3931ee893a4c7d0ea14220aae6841"
+        );
+        assert_eq!(
+            hex::encode(derive_secret_key(&seed, &DerivationPath::withdrawal(0)).unwrap()),
+            "264ec182b3acb360ea8a96ed9bebe837aa3e615bf40b0e178cf8f0fa538c8078"
+        );
+        assert_eq!(
+            derive_secret_key(&seed, &DerivationPath::default()).unwrap(),
+            master_secret_key
+        );
+    }
+}
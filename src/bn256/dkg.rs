@@ -0,0 +1,777 @@
+//! Distributed key generation for threshold BLS keys, following the Joint-Feldman protocol of
+//! [Pedersen](https://link.springer.com/chapter/10.1007/3-540-46416-6_47).
+//!
+//! Every participant acts as the dealer of its own secret, which is shared among all of them with
+//! `threshold::split`. The group secret key is the sum of the secrets of all dealers, so it is
+//! never known by anybody, and no trusted dealer is required. The protocol consists of:
+//!
+//! 1. **Deal**: every participant calls `Participant::deal` with a fresh random secret, broadcasts
+//!    the verification vector and sends every share privately to its recipient.
+//! 2. **Verify deals**: every participant checks the shares it receives with
+//!    `Participant::receive_deal`. For every invalid or missing share, it broadcasts the
+//!    `Complaint` returned by `Participant::complain`.
+//! 3. **Complaints**: every participant records the complaints broadcast by the others with
+//!    `Participant::process_complaint`. Every accused dealer answers each complaint by
+//!    broadcasting the share of the accuser with `Deal::justify`, which is checked by everybody
+//!    with `Participant::process_justification`. Dealers revealing an invalid share are
+//!    disqualified, as are dealers with unanswered complaints.
+//! 4. **Aggregate**: `Participant::qualified` returns the dealers neither disqualified nor with
+//!    pending complaints, and `Participant::finalize` sums the shares and verification vectors of
+//!    those dealers.
+//!
+//! As every complaint and justification is broadcast, all honest participants obtain the same set
+//! of qualified dealers. Observers can derive the group public key and the public key of every
+//! participant from the published verification vectors with `group_verification_vector`,
+//! `group_public_key` and `member_public_keys`. The resulting `KeyShare` and verification vector can be used with
+//! `Bn256::sign_share`, `Bn256::verify_share` and `threshold::combine`. This module does not
+//! transport any message, so it can be used over any communication channel.
+use alloc::vec::Vec;
+use bn::{Fr, Group, G2};
+use byteorder::{BigEndian, ByteOrder};
+use zeroize::Zeroizing;
+
+use super::error::Error;
+use super::threshold::{
+    scalar_to_bytes, share_public_key, split, verify_key_share, KeyShare, ThresholdParameters,
+    KEY_SHARE_LENGTH, PUBLIC_KEY_LENGTH,
+};
+use super::{g2_from_compressed, PrivateKey, PublicKey};
+
+/// The public and private outputs of a dealer.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Deal {
+    /// Index of the dealer
+    pub dealer: u64,
+    /// Compressed points committing to the polynomial of the dealer (to be broadcast)
+    pub verification_vector: Vec<Vec<u8>>,
+    /// Share of every participant (to be sent privately to each recipient)
+    pub shares: Vec<KeyShare>,
+}
+
+/// A complaint of a participant about the share received from a dealer (to be broadcast).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Complaint {
+    /// Index of the participant complaining
+    pub accuser: u64,
+    /// Index of the dealer accused
+    pub dealer: u64,
+}
+
+/// The answer of a dealer to a complaint, revealing the share of the accuser (to be broadcast).
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Justification {
+    /// Index of the dealer answering the complaint
+    pub dealer: u64,
+    /// Share of the participant complaining
+    pub share: KeyShare,
+}
+
+impl Deal {
+    /// Function to serialize the deal as the big endian dealer index (8 bytes), the number of
+    /// commitments (4 bytes) followed by the compressed commitments (65 bytes each) and the number
+    /// of shares (4 bytes) followed by the serialized shares (40 bytes each).
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the serialized deal
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = Vec::with_capacity(
+            16 + self.verification_vector.len() * PUBLIC_KEY_LENGTH
+                + self.shares.len() * KEY_SHARE_LENGTH,
+        );
+        bytes.extend_from_slice(&self.dealer.to_be_bytes());
+        bytes.extend_from_slice(&length_to_bytes(self.verification_vector.len())?);
+        for commitment in &self.verification_vector {
+            if commitment.len() != PUBLIC_KEY_LENGTH {
+                return Err(Error::InvalidLength);
+            }
+            bytes.extend_from_slice(commitment);
+        }
+        bytes.extend_from_slice(&length_to_bytes(self.shares.len())?);
+        for share in &self.shares {
+            bytes.extend_from_slice(&share.to_bytes()?);
+        }
+
+        Ok(bytes)
+    }
+
+    /// Function to deserialize a deal encoded with `to_bytes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized deal
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `Deal`; otherwise `Error`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < 12 {
+            return Err(Error::InvalidLength);
+        }
+        let dealer = BigEndian::read_u64(&bytes[0..8]);
+        // The counts are untrusted, so the lengths are computed without overflowing (e.g. on 32-bit
+        // targets)
+        let commitments = BigEndian::read_u32(&bytes[8..12]) as usize;
+        let shares_offset = commitments
+            .checked_mul(PUBLIC_KEY_LENGTH)
+            .and_then(|length| length.checked_add(12))
+            .ok_or(Error::InvalidLength)?;
+        if bytes.len().saturating_sub(shares_offset) < 4 {
+            return Err(Error::InvalidLength);
+        }
+        let shares = BigEndian::read_u32(&bytes[shares_offset..shares_offset + 4]) as usize;
+        let length = shares
+            .checked_mul(KEY_SHARE_LENGTH)
+            .and_then(|length| length.checked_add(shares_offset + 4))
+            .ok_or(Error::InvalidLength)?;
+        if bytes.len() != length {
+            return Err(Error::InvalidLength);
+        }
+
+        Ok(Deal {
+            dealer,
+            verification_vector: bytes[12..shares_offset]
+                .chunks(PUBLIC_KEY_LENGTH)
+                .map(|commitment| commitment.to_vec())
+                .collect(),
+            shares: bytes[shares_offset + 4..]
+                .chunks(KEY_SHARE_LENGTH)
+                .map(KeyShare::from_bytes)
+                .collect::<Result<_, Error>>()?,
+        })
+    }
+
+    /// Function to answer a complaint about this deal.
+    ///
+    /// # Arguments
+    ///
+    /// * `complaint` - The complaint received
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `Justification` revealing the share of the accuser
+    pub fn justify(&self, complaint: &Complaint) -> Result<Justification, Error> {
+        if complaint.dealer != self.dealer {
+            return Err(Error::IndexOutOfBounds);
+        }
+        let share = self
+            .shares
+            .iter()
+            .find(|share| share.index == complaint.accuser)
+            .ok_or(Error::IndexOutOfBounds)?;
+
+        Ok(Justification {
+            dealer: self.dealer,
+            share: share.clone(),
+        })
+    }
+}
+
+impl Complaint {
+    /// Function to serialize the complaint as the big endian accuser and dealer indices (8 bytes
+    /// each).
+    ///
+    /// # Returns
+    ///
+    /// * A vector of 16 bytes
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [self.accuser.to_be_bytes(), self.dealer.to_be_bytes()].concat()
+    }
+
+    /// Function to deserialize a complaint encoded with `to_bytes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized complaint
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `Complaint`; otherwise `Error`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != 16 {
+            return Err(Error::InvalidLength);
+        }
+
+        Ok(Complaint {
+            accuser: BigEndian::read_u64(&bytes[0..8]),
+            dealer: BigEndian::read_u64(&bytes[8..16]),
+        })
+    }
+}
+
+impl Justification {
+    /// Function to serialize the justification as the big endian dealer index (8 bytes) followed
+    /// by the serialized share (40 bytes).
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of 48 bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        Ok([&self.dealer.to_be_bytes()[..], &self.share.to_bytes()?].concat())
+    }
+
+    /// Function to deserialize a justification encoded with `to_bytes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized justification
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `Justification`; otherwise `Error`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != 8 + KEY_SHARE_LENGTH {
+            return Err(Error::InvalidLength);
+        }
+
+        Ok(Justification {
+            dealer: BigEndian::read_u64(&bytes[0..8]),
+            share: KeyShare::from_bytes(&bytes[8..])?,
+        })
+    }
+}
+
+/// Function to encode the number of elements of a list as a big endian 32-bit integer.
+fn length_to_bytes(length: usize) -> Result<[u8; 4], Error> {
+    if length > u32::MAX as usize {
+        return Err(Error::InvalidLength);
+    }
+
+    Ok((length as u32).to_be_bytes())
+}
+
+/// The deal of a dealer as seen by a participant.
+struct DealerState {
+    /// Index of the dealer
+    dealer: u64,
+    /// Compressed points committing to the polynomial of the dealer
+    verification_vector: Vec<Vec<u8>>,
+    /// Share of the participant, if a valid one has been received
+    share: Option<Fr>,
+}
+
+/// The state of a participant of the distributed key generation.
+pub struct Participant {
+    /// Index of the participant (starting at `1`)
+    index: u64,
+    /// Number of shares required to sign
+    threshold: usize,
+    /// Number of participants
+    participants: usize,
+    /// Deals received so far
+    dealers: Vec<DealerState>,
+    /// Complaints not answered yet
+    complaints: Vec<Complaint>,
+    /// Dealers disqualified for revealing invalid shares
+    disqualified: Vec<u64>,
+}
+
+impl Participant {
+    /// Function to create the state of a participant.
+    ///
+    /// # Arguments
+    ///
+    /// * `index`        - The index of the participant (between 1 and `participants`)
+    /// * `threshold`    - The number of shares required to sign (between 1 and `participants`)
+    /// * `participants` - The number of participants
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `Participant`; otherwise `Error`
+    pub fn new(index: u64, threshold: usize, participants: usize) -> Result<Self, Error> {
+        ThresholdParameters::new(threshold, participants)?.check_index(index)?;
+
+        Ok(Participant {
+            index,
+            threshold,
+            participants,
+            dealers: Vec::new(),
+            complaints: Vec::new(),
+            disqualified: Vec::new(),
+        })
+    }
+
+    /// Function to share a secret among all the participants.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret` - Bytes chosen uniformly at random by the dealer (at most 64), reduced modulo the
+    ///   group order as in `PrivateKey::from_slice_reduced`. At least 48 bytes are recommended,
+    ///   so that the reduced secret is close to uniform
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `Deal` of the participant
+    pub fn deal(&self, secret: &[u8]) -> Result<Deal, Error> {
+        let secret_key = Zeroizing::new(PrivateKey::from_slice_reduced(secret)?.to_bytes()?);
+        // The secret is fresh randomness of its own, so it also seeds the sharing polynomial
+        let (shares, verification_vector) =
+            split(&secret_key, secret, self.threshold, self.participants)?;
+
+        Ok(Deal {
+            dealer: self.index,
+            verification_vector,
+            shares,
+        })
+    }
+
+    /// Function to store the verification vector broadcast by a dealer whose share has not been
+    /// received (a complaint should be broadcast for it).
+    ///
+    /// # Arguments
+    ///
+    /// * `dealer`              - The index of the dealer
+    /// * `verification_vector` - The verification vector broadcast by the dealer
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    pub fn receive_verification_vector(
+        &mut self,
+        dealer: u64,
+        verification_vector: &[&[u8]],
+    ) -> Result<(), Error> {
+        if dealer == 0 || dealer > self.participants as u64 {
+            return Err(Error::IndexOutOfBounds);
+        }
+        if self.dealers.iter().any(|state| state.dealer == dealer) {
+            return Err(Error::DuplicateShareIndex);
+        }
+        if verification_vector.len() != self.threshold {
+            return Err(Error::InvalidLength);
+        }
+
+        self.dealers.push(DealerState {
+            dealer,
+            verification_vector: verification_vector.iter().map(|v| v.to_vec()).collect(),
+            share: None,
+        });
+
+        Ok(())
+    }
+
+    /// Function to verify and store the share received from a dealer.
+    ///
+    /// # Arguments
+    ///
+    /// * `dealer`              - The index of the dealer
+    /// * `verification_vector` - The verification vector broadcast by the dealer
+    /// * `share`               - The share sent by the dealer to this participant
+    ///
+    /// # Returns
+    ///
+    /// * If the share is valid, `Ok(())`; otherwise `Error` (a complaint should be broadcast)
+    pub fn receive_deal(
+        &mut self,
+        dealer: u64,
+        verification_vector: &[&[u8]],
+        share: &KeyShare,
+    ) -> Result<(), Error> {
+        self.receive_verification_vector(dealer, verification_vector)?;
+        if share.index != self.index {
+            return Err(Error::InvalidShare);
+        }
+        verify_key_share(share, verification_vector)?;
+        let secret = Fr::from_slice(&share.secret_key)?;
+        self.dealer_state(dealer)?.share = Some(secret);
+
+        Ok(())
+    }
+
+    /// Function to complain about the share received from a dealer, either invalid or missing.
+    ///
+    /// # Arguments
+    ///
+    /// * `dealer` - The index of the dealer
+    ///
+    /// # Returns
+    ///
+    /// * The `Complaint` to be broadcast
+    pub fn complain(&mut self, dealer: u64) -> Complaint {
+        let complaint = Complaint {
+            accuser: self.index,
+            dealer,
+        };
+        self.process_complaint(&complaint);
+
+        complaint
+    }
+
+    /// Function to record a complaint broadcast by a participant.
+    ///
+    /// # Arguments
+    ///
+    /// * `complaint` - The complaint received
+    pub fn process_complaint(&mut self, complaint: &Complaint) {
+        if !self.complaints.contains(complaint) {
+            self.complaints.push(*complaint);
+        }
+    }
+
+    /// Function to check the answer of a dealer to a complaint.
+    ///
+    /// If the revealed share is valid, the complaint is resolved (and the share is adopted if this
+    /// participant complained). Otherwise, the dealer is disqualified.
+    ///
+    /// # Arguments
+    ///
+    /// * `justification` - The justification broadcast by the dealer
+    ///
+    /// # Returns
+    ///
+    /// * If the revealed share is valid, `Ok(())`; otherwise `Error`
+    pub fn process_justification(&mut self, justification: &Justification) -> Result<(), Error> {
+        let complaint = Complaint {
+            accuser: justification.share.index,
+            dealer: justification.dealer,
+        };
+        let position = self
+            .complaints
+            .iter()
+            .position(|pending| *pending == complaint)
+            .ok_or(Error::IndexOutOfBounds)?;
+        self.complaints.remove(position);
+
+        let index = self.index;
+        let state = self.dealer_state(justification.dealer)?;
+        let verification_vector: Vec<&[u8]> =
+            state.verification_vector.iter().map(|v| &v[..]).collect();
+        match verify_key_share(&justification.share, &verification_vector) {
+            Ok(()) => {
+                if complaint.accuser == index {
+                    state.share = Some(Fr::from_slice(&justification.share.secret_key)?);
+                }
+                Ok(())
+            }
+            Err(error) => {
+                self.disqualified.push(justification.dealer);
+                Err(error)
+            }
+        }
+    }
+
+    /// Function to get the dealers whose deals are included in the group key: those that have
+    /// broadcast a verification vector, have not been disqualified and have answered every
+    /// complaint.
+    ///
+    /// # Returns
+    ///
+    /// * The sorted indices of the qualified dealers
+    pub fn qualified(&self) -> Vec<u64> {
+        let mut qualified: Vec<u64> = self
+            .dealers
+            .iter()
+            .map(|state| state.dealer)
+            .filter(|dealer| !self.disqualified.contains(dealer))
+            .filter(|&dealer| self.complaints.iter().all(|c| c.dealer != dealer))
+            .collect();
+        qualified.sort_unstable();
+
+        qualified
+    }
+
+    /// Function to compute the key share of the participant and the group verification vector.
+    ///
+    /// All the participants must use the same set of qualified dealers (see `qualified`), whose
+    /// valid shares must have been received.
+    ///
+    /// # Arguments
+    ///
+    /// * `qualified` - The indices of the dealers not disqualified
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the key share of the participant and the group verification vector,
+    ///   whose first entry is the group public key
+    pub fn finalize(&self, qualified: &[u64]) -> Result<(KeyShare, Vec<Vec<u8>>), Error> {
+        if qualified.is_empty() {
+            return Err(Error::InvalidLength);
+        }
+        let mut sorted = qualified.to_vec();
+        sorted.sort_unstable();
+        if sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(Error::DuplicateShareIndex);
+        }
+
+        let mut secret = Fr::zero();
+        let mut verification_vectors = Vec::with_capacity(qualified.len());
+        for &dealer in qualified {
+            if self.disqualified.contains(&dealer) {
+                return Err(Error::DisqualifiedDealer);
+            }
+            let state = self
+                .dealers
+                .iter()
+                .find(|state| state.dealer == dealer)
+                .ok_or(Error::MissingDeal)?;
+            secret = secret + state.share.ok_or(Error::MissingDeal)?;
+            verification_vectors.push(
+                state
+                    .verification_vector
+                    .iter()
+                    .map(|v| &v[..])
+                    .collect::<Vec<&[u8]>>(),
+            );
+        }
+        let verification_vectors: Vec<&[&[u8]]> =
+            verification_vectors.iter().map(|v| &v[..]).collect();
+        let verification_vector = group_verification_vector(&verification_vectors)?;
+
+        Ok((
+            KeyShare {
+                index: self.index,
+                secret_key: scalar_to_bytes(secret)?,
+            },
+            verification_vector,
+        ))
+    }
+
+    /// Function to get the state of the deal of a dealer.
+    fn dealer_state(&mut self, dealer: u64) -> Result<&mut DealerState, Error> {
+        self.dealers
+            .iter_mut()
+            .find(|state| state.dealer == dealer)
+            .ok_or(Error::MissingDeal)
+    }
+}
+
+/// Function to compute the group verification vector from the verification vectors published by
+/// the qualified dealers, so that anybody can follow the key generation without taking part in it.
+///
+/// # Arguments
+///
+/// * `verification_vectors` - The verification vector of every qualified dealer
+///
+/// # Returns
+///
+/// * If successful, the group verification vector, whose first entry is the group public key
+pub fn group_verification_vector(verification_vectors: &[&[&[u8]]]) -> Result<Vec<Vec<u8>>, Error> {
+    let threshold = verification_vectors
+        .first()
+        .map(|verification_vector| verification_vector.len())
+        .ok_or(Error::InvalidLength)?;
+    if threshold == 0 || verification_vectors.iter().any(|v| v.len() != threshold) {
+        return Err(Error::InvalidLength);
+    }
+
+    let mut sums = vec![G2::zero(); threshold];
+    for verification_vector in verification_vectors {
+        for (sum, &commitment) in sums.iter_mut().zip(verification_vector.iter()) {
+            *sum = *sum + g2_from_compressed(commitment)?;
+        }
+    }
+
+    sums.into_iter()
+        .map(|commitment| PublicKey(commitment).to_compressed())
+        .collect()
+}
+
+/// Function to compute the group public key from the verification vectors published by the
+/// qualified dealers.
+///
+/// # Arguments
+///
+/// * `verification_vectors` - The verification vector of every qualified dealer
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the compressed group public key
+pub fn group_public_key(verification_vectors: &[&[&[u8]]]) -> Result<Vec<u8>, Error> {
+    let mut verification_vector = group_verification_vector(verification_vectors)?;
+
+    Ok(verification_vector.swap_remove(0))
+}
+
+/// Function to compute the public key of the share of every participant from the group
+/// verification vector, e.g. for checking signature shares with `MultiSignature::verify`.
+///
+/// # Arguments
+///
+/// * `verification_vector` - The group verification vector
+/// * `participants`        - The number of participants
+///
+/// # Returns
+///
+/// * If successful, the compressed public keys of the participants `1..=participants`
+pub fn member_public_keys(
+    verification_vector: &[&[u8]],
+    participants: usize,
+) -> Result<Vec<Vec<u8>>, Error> {
+    (1..=participants as u64)
+        .map(|index| share_public_key(verification_vector, index))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::threshold::combine;
+    use crate::bn256::Bn256;
+    use crate::MultiSignature;
+
+    /// Function to run the dealing phase among 3 participants with a 2-of-3 threshold, where
+    /// dealer 3 sends an invalid share to participant 1.
+    fn deal_with_faulty_dealer() -> (Vec<Participant>, Vec<Deal>, Complaint) {
+        let secrets = [
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap(),
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap(),
+            hex::decode("0c9afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f672")
+                .unwrap(),
+        ];
+        let mut participants: Vec<Participant> = (1..=3)
+            .map(|index| Participant::new(index, 2, 3).unwrap())
+            .collect();
+        let deals: Vec<Deal> = participants
+            .iter()
+            .zip(&secrets)
+            .map(|(participant, secret)| participant.deal(secret).unwrap())
+            .collect();
+
+        let mut complaints = Vec::new();
+        for deal in &deals {
+            let verification_vector: Vec<&[u8]> =
+                deal.verification_vector.iter().map(|v| &v[..]).collect();
+            for (participant, share) in participants.iter_mut().zip(&deal.shares) {
+                let mut share = share.clone();
+                if deal.dealer == 3 && participant.index == 1 {
+                    share.secret_key = deals[1].shares[0].secret_key.clone();
+                }
+                match participant.receive_deal(deal.dealer, &verification_vector, &share) {
+                    Ok(()) => {}
+                    Err(Error::InvalidShare) => complaints.push(participant.complain(deal.dealer)),
+                    Err(_) => panic!("Expected InvalidShare error"),
+                }
+            }
+        }
+        assert_eq!(complaints.len(), 1);
+        for participant in participants.iter_mut() {
+            participant.process_complaint(&complaints[0]);
+            assert_eq!(participant.qualified(), [1, 2]);
+        }
+
+        (participants, deals, complaints[0])
+    }
+
+    /// Test a 2-of-3 key generation where a complaint is answered with a valid share
+    #[test]
+    fn test_distributed_key_generation() {
+        let (mut participants, deals, complaint) = deal_with_faulty_dealer();
+
+        // Dealer 3 reveals the right share of participant 1
+        let justification = deals[2].justify(&complaint).unwrap();
+        for participant in participants.iter_mut() {
+            assert!(participant.process_justification(&justification).is_ok());
+            assert_eq!(participant.qualified(), [1, 2, 3]);
+        }
+        let outputs: Vec<(KeyShare, Vec<Vec<u8>>)> = participants
+            .iter()
+            .map(|participant| participant.finalize(&participant.qualified()).unwrap())
+            .collect();
+        assert!(outputs.iter().all(|output| output.1 == outputs[0].1));
+
+        // An observer derives the same keys from the published verification vectors
+        let published: Vec<Vec<&[u8]>> = deals
+            .iter()
+            .map(|deal| deal.verification_vector.iter().map(|v| &v[..]).collect())
+            .collect();
+        let published: Vec<&[&[u8]]> = published.iter().map(|v| &v[..]).collect();
+        assert_eq!(group_verification_vector(&published).unwrap(), outputs[0].1);
+        assert_eq!(group_public_key(&published).unwrap(), outputs[0].1[0]);
+        let verification_vector: Vec<&[u8]> = outputs[0].1.iter().map(|v| &v[..]).collect();
+        let bn256 = Bn256::default();
+        let member_keys = member_public_keys(&verification_vector, 3).unwrap();
+        for (member_key, output) in member_keys.iter().zip(&outputs) {
+            assert_eq!(
+                *member_key,
+                bn256.derive_public_key(&output.0.secret_key).unwrap()
+            );
+        }
+
+        let msg: &[u8] = b"sample";
+        let signature_shares: Vec<_> = [&outputs[0].0, &outputs[2].0]
+            .iter()
+            .map(|share| bn256.sign_share(share, msg).unwrap())
+            .collect();
+        for share in &signature_shares {
+            assert!(bn256
+                .verify_share(share.index, &share.signature, msg, &verification_vector)
+                .is_ok());
+        }
+        let signature = combine(&signature_shares).unwrap();
+        assert!(bn256
+            .verify(&signature, msg, verification_vector[0])
+            .is_ok());
+    }
+
+    /// Test dealing secrets which are not canonical scalars
+    #[test]
+    fn test_deal_reduced_secret() {
+        let participant = Participant::new(1, 2, 3).unwrap();
+        let bn256 = Bn256::default();
+
+        // Secrets greater than the group order and 48-byte secrets are reduced
+        for secret in [vec![0xff; 32], vec![0xab; 48]].iter() {
+            let deal = participant.deal(secret).unwrap();
+            let secret_key = PrivateKey::from_slice_reduced(secret)
+                .unwrap()
+                .to_bytes()
+                .unwrap();
+            assert_eq!(
+                deal.verification_vector[0],
+                bn256.derive_public_key(&secret_key).unwrap()
+            );
+        }
+        assert!(participant.deal(&[0u8; 32]).is_err());
+        assert!(participant.deal(&[1u8; 65]).is_err());
+    }
+
+    /// Test disqualifying a dealer answering a complaint with an invalid share
+    #[test]
+    fn test_disqualify_dealer() {
+        let (mut participants, deals, complaint) = deal_with_faulty_dealer();
+
+        let mut justification = deals[2].justify(&complaint).unwrap();
+        justification.share.secret_key = deals[2].shares[1].secret_key.clone();
+        for participant in participants.iter_mut() {
+            assert!(participant.process_justification(&justification).is_err());
+            assert_eq!(participant.qualified(), [1, 2]);
+        }
+        match participants[1].finalize(&[1, 2, 3]) {
+            Err(Error::DisqualifiedDealer) => {}
+            _ => panic!("Expected DisqualifiedDealer error"),
+        }
+        let outputs: Vec<(KeyShare, Vec<Vec<u8>>)> = participants
+            .iter()
+            .map(|participant| participant.finalize(&[1, 2]).unwrap())
+            .collect();
+        assert!(outputs.iter().all(|output| output.1 == outputs[0].1));
+    }
+
+    /// Test serializing the messages of the key generation
+    #[test]
+    fn test_message_serialization() {
+        let (_, deals, complaint) = deal_with_faulty_dealer();
+
+        let encoded = deals[2].to_bytes().unwrap();
+        assert_eq!(encoded.len(), 8 + 4 + 2 * 65 + 4 + 3 * 40);
+        assert_eq!(Deal::from_bytes(&encoded).unwrap(), deals[2]);
+        assert!(Deal::from_bytes(&encoded[..encoded.len() - 1]).is_err());
+        // Counts of items beyond the input are rejected without overflowing
+        let mut oversized = encoded.clone();
+        oversized[8..12].copy_from_slice(&u32::MAX.to_be_bytes());
+        assert!(Deal::from_bytes(&oversized).is_err());
+
+        let encoded = complaint.to_bytes();
+        assert_eq!(Complaint::from_bytes(&encoded).unwrap(), complaint);
+
+        let justification = deals[2].justify(&complaint).unwrap();
+        let encoded = justification.to_bytes().unwrap();
+        assert_eq!(Justification::from_bytes(&encoded).unwrap(), justification);
+        assert!(Justification::from_bytes(&encoded[1..]).is_err());
+    }
+}
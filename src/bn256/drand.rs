@@ -0,0 +1,371 @@
+//! Verification of [drand](https://drand.love) randomness beacons.
+//!
+//! Every round `r` of a drand network publishes a BLS signature under the distributed public key
+//! of the network:
+//!
+//! - in chained mode, the signed message is `SHA-256(previous_signature || r)`, where the round is
+//!   encoded as 8 big endian bytes, so every beacon depends on the previous one
+//! - in unchained mode, the signed message is `SHA-256(r)`
+//!
+//! The randomness of the round is `SHA-256(signature)` over the signature bytes as published.
+//! drand publishes BN254 beacons in the layout of go-ethereum and kyber (see `geth`): 64-byte
+//! signatures and 128-byte public keys. These encodings are accepted by `verify_beacon` together
+//! with the compressed ones of this crate (33 and 65 bytes), and the randomness is computed over
+//! the signature bytes passed, so published beacons must be passed unconverted. Messages are
+//! hashed to G1 with the `BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_NUL_` ciphersuite, public keys being
+//! points in G2.
+//!
+//! The `evmnet` network, meant to be verified by EVM contracts, signs `Keccak-256(r)` in unchained
+//! mode and hashes it to G1 with the `BLS_SIG_BN254G1_XMD:KECCAK-256_SVDW_RO_NUL_` ciphersuite
+//! (see `EVMNET`).
+//!
+//! Other deployments can describe their messages with a `RoundMessageFormat`, which selects the
+//! chaining rule, an optional prefix prepended to the hashed message, the digest of the message
+//! and the ciphersuite, so the same verification code serves all of them.
+use alloc::vec::Vec;
+use digest::Digest;
+use sha2::Sha256;
+use sha3::Keccak256;
+
+use super::ciphersuite::{
+    Ciphersuite, BLS_SIG_BN254G1_XMD_KECCAK_256_SVDW_RO_NUL,
+    BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL,
+};
+use super::error::Error;
+use super::geth;
+use super::hash_to_curve::HashFunction;
+use super::Bn256;
+
+/// Rule linking the message of a round to the previous beacon.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Chaining {
+    /// The message includes the signature of the previous round.
+    Chained,
+    /// The message only depends on the round number.
+    Unchained,
+}
+
+/// Format of the messages signed in every round: `H(prefix || previous_signature || r)` in
+/// chained mode and `H(prefix || r)` in unchained mode, hashed to G1 with a ciphersuite.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RoundMessageFormat {
+    /// Chaining rule.
+    pub chaining: Chaining,
+    /// Bytes prepended to the hashed message (empty for drand).
+    pub prefix: &'static [u8],
+    /// Digest `H` of the message.
+    pub digest: HashFunction,
+    /// Ciphersuite used for hashing the message to G1.
+    pub ciphersuite: Ciphersuite,
+}
+
+/// Messages of drand networks in chained mode.
+pub const CHAINED: RoundMessageFormat = RoundMessageFormat {
+    chaining: Chaining::Chained,
+    prefix: b"",
+    digest: HashFunction::Sha256,
+    ciphersuite: BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL,
+};
+
+/// Messages of drand networks in unchained mode.
+pub const UNCHAINED: RoundMessageFormat = RoundMessageFormat {
+    chaining: Chaining::Unchained,
+    prefix: b"",
+    digest: HashFunction::Sha256,
+    ciphersuite: BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL,
+};
+
+/// Messages of the drand `evmnet` network: `Keccak-256(r)` in unchained mode, hashed to G1 with
+/// the `BLS_SIG_BN254G1_XMD:KECCAK-256_SVDW_RO_NUL_` ciphersuite.
+pub const EVMNET: RoundMessageFormat = RoundMessageFormat {
+    chaining: Chaining::Unchained,
+    prefix: b"",
+    digest: HashFunction::Keccak256,
+    ciphersuite: BLS_SIG_BN254G1_XMD_KECCAK_256_SVDW_RO_NUL,
+};
+
+impl RoundMessageFormat {
+    /// Function to build the message signed in a round.
+    ///
+    /// # Arguments
+    ///
+    /// * `round`              - The round number
+    /// * `previous_signature` - The signature of the previous round, required in chained mode and
+    ///   ignored in unchained mode
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of 32 bytes with the message of the round; otherwise
+    ///   `Error::BrokenChain` if the previous signature is missing in chained mode
+    pub fn message(&self, round: u64, previous_signature: Option<&[u8]>) -> Result<Vec<u8>, Error> {
+        let previous_signature = match self.chaining {
+            Chaining::Chained => Some(previous_signature.ok_or(Error::BrokenChain)?),
+            Chaining::Unchained => None,
+        };
+
+        Ok(match self.digest {
+            HashFunction::Sha256 => hash_message::<Sha256>(self.prefix, round, previous_signature),
+            HashFunction::Keccak256 => {
+                hash_message::<Keccak256>(self.prefix, round, previous_signature)
+            }
+        })
+    }
+
+    /// Function to verify the beacon of a round and extract its randomness.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key`         - The distributed public key of the network, marshaled (128 bytes) or
+    ///   compressed (65 bytes)
+    /// * `round`              - The round number
+    /// * `previous_signature` - The signature of the previous round as published, required in
+    ///   chained mode
+    /// * `signature`          - The signature bytes of the beacon, marshaled (64 bytes) or
+    ///   compressed (33 bytes)
+    ///
+    /// # Returns
+    ///
+    /// * If the beacon is valid, a vector of 32 bytes with the randomness; otherwise `Error`
+    pub fn verify_beacon(
+        &self,
+        public_key: &[u8],
+        round: u64,
+        previous_signature: Option<&[u8]>,
+        signature: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let message = self.message(round, previous_signature)?;
+        Bn256::default().verify_with_ciphersuite(
+            &self.ciphersuite,
+            &decode_signature(signature)?,
+            &message,
+            &decode_public_key(public_key)?,
+        )?;
+
+        Ok(randomness(signature))
+    }
+}
+
+/// Function to build the message signed in a round.
+///
+/// # Arguments
+///
+/// * `round`              - The round number
+/// * `previous_signature` - The signature of the previous round in chained mode, or `None` in
+///   unchained mode
+///
+/// # Returns
+///
+/// * A vector of 32 bytes with the message of the round
+pub fn round_message(round: u64, previous_signature: Option<&[u8]>) -> Vec<u8> {
+    hash_message::<Sha256>(b"", round, previous_signature)
+}
+
+/// Function to extract the randomness of a beacon from its signature.
+///
+/// # Arguments
+///
+/// * `signature` - The signature bytes of the beacon as published (64 bytes for drand BN254
+///   networks)
+///
+/// # Returns
+///
+/// * A vector of 32 bytes with the randomness
+pub fn randomness(signature: &[u8]) -> Vec<u8> {
+    Sha256::digest(signature).to_vec()
+}
+
+/// Function to verify the beacon of a round and extract its randomness.
+///
+/// The mode is chosen from the previous signature, see `RoundMessageFormat::verify_beacon` for
+/// other formats.
+///
+/// # Arguments
+///
+/// * `public_key`         - The distributed public key of the network, marshaled (128 bytes) or
+///   compressed (65 bytes)
+/// * `round`              - The round number
+/// * `previous_signature` - The signature of the previous round as published in chained mode, or
+///   `None` in unchained mode
+/// * `signature`          - The signature bytes of the beacon, marshaled (64 bytes) or compressed
+///   (33 bytes)
+///
+/// # Returns
+///
+/// * If the beacon is valid, a vector of 32 bytes with the randomness; otherwise `Error`
+pub fn verify_beacon(
+    public_key: &[u8],
+    round: u64,
+    previous_signature: Option<&[u8]>,
+    signature: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let format = if previous_signature.is_some() {
+        CHAINED
+    } else {
+        UNCHAINED
+    };
+
+    format.verify_beacon(public_key, round, previous_signature, signature)
+}
+
+/// Function to decode a signature marshaled as drand publishes it, or compressed.
+fn decode_signature(signature: &[u8]) -> Result<Vec<u8>, Error> {
+    match signature.len() {
+        geth::G1_LENGTH => geth::unmarshal_signature(signature),
+        _ => Ok(signature.to_vec()),
+    }
+}
+
+/// Function to decode a public key marshaled as drand publishes it, or compressed.
+fn decode_public_key(public_key: &[u8]) -> Result<Vec<u8>, Error> {
+    match public_key.len() {
+        geth::G2_LENGTH => geth::unmarshal_public_key(public_key),
+        _ => Ok(public_key.to_vec()),
+    }
+}
+
+/// Function to hash the prefix, the previous signature (if any) and the round into a message with
+/// the digest `D`.
+fn hash_message<D: Digest>(
+    prefix: &[u8],
+    round: u64,
+    previous_signature: Option<&[u8]>,
+) -> Vec<u8> {
+    let mut hasher = D::new();
+    hasher.input(prefix);
+    if let Some(previous_signature) = previous_signature {
+        hasher.input(previous_signature);
+    }
+    hasher.input(round.to_be_bytes());
+
+    hasher.result().to_vec()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::MultiSignature;
+
+    /// Test verifying chained and unchained beacons
+    #[test]
+    fn test_verify_beacon() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let suite = &BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL;
+
+        let first = bn256
+            .sign_with_ciphersuite(suite, &secret_key, round_message(1, None))
+            .unwrap();
+        let second = bn256
+            .sign_with_ciphersuite(suite, &secret_key, round_message(2, Some(&first)))
+            .unwrap();
+        assert_eq!(
+            verify_beacon(&public_key, 1, None, &first).unwrap(),
+            randomness(&first)
+        );
+        let beacon = verify_beacon(&public_key, 2, Some(&first), &second).unwrap();
+        assert_eq!(beacon.len(), 32);
+        assert!(verify_beacon(&public_key, 2, None, &second).is_err());
+        assert!(verify_beacon(&public_key, 3, Some(&first), &second).is_err());
+        assert!(verify_beacon(&public_key, 2, Some(&second), &first).is_err());
+    }
+
+    /// Test building messages with custom formats
+    #[test]
+    fn test_round_message_format() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let previous: &[u8] = b"previous signature";
+
+        assert_eq!(
+            CHAINED.message(5, Some(previous)).unwrap(),
+            round_message(5, Some(previous))
+        );
+        assert_eq!(
+            UNCHAINED.message(5, Some(previous)).unwrap(),
+            round_message(5, None)
+        );
+        match CHAINED.message(5, None) {
+            Err(Error::BrokenChain) => {}
+            _ => panic!("Expected BrokenChain error"),
+        }
+
+        let custom = RoundMessageFormat {
+            prefix: b"my-beacon",
+            ..UNCHAINED
+        };
+        let message = custom.message(5, None).unwrap();
+        assert!(message != round_message(5, None));
+        let signature = bn256
+            .sign_with_ciphersuite(
+                &BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL,
+                &secret_key,
+                &message,
+            )
+            .unwrap();
+        assert!(custom
+            .verify_beacon(&public_key, 5, None, &signature)
+            .is_ok());
+        assert!(UNCHAINED
+            .verify_beacon(&public_key, 5, None, &signature)
+            .is_err());
+    }
+
+    /// Test the messages and the ciphersuite of the `evmnet` format
+    #[test]
+    fn test_evmnet_format() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+
+        // `Keccak-256` of the round encoded as 8 big endian bytes
+        let message = EVMNET.message(1, None).unwrap();
+        assert_eq!(message, Keccak256::digest(&1u64.to_be_bytes()).to_vec());
+        assert!(message != round_message(1, None));
+
+        let signature = bn256
+            .sign_with_ciphersuite(&EVMNET.ciphersuite, &secret_key, &message)
+            .unwrap();
+        assert_eq!(
+            EVMNET
+                .verify_beacon(&public_key, 1, None, &signature)
+                .unwrap(),
+            randomness(&signature)
+        );
+        assert!(EVMNET
+            .verify_beacon(&public_key, 2, None, &signature)
+            .is_err());
+
+        // Beacons as published by drand: the randomness is computed over the marshaled signature
+        let published_public_key = geth::marshal_public_key(&public_key).unwrap();
+        let published_signature = geth::marshal_signature(&signature).unwrap();
+        assert_eq!(published_signature.len(), 64);
+        let beacon = EVMNET
+            .verify_beacon(&published_public_key, 1, None, &published_signature)
+            .unwrap();
+        assert_eq!(beacon, Sha256::digest(&published_signature).to_vec());
+        assert!(beacon != randomness(&signature));
+        assert!(EVMNET
+            .verify_beacon(&published_public_key, 2, None, &published_signature)
+            .is_err());
+
+        // The same message under the SHA-256 ciphersuite is a different signature
+        let sha_signature = bn256
+            .sign_with_ciphersuite(
+                &BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL,
+                &secret_key,
+                &message,
+            )
+            .unwrap();
+        assert!(EVMNET
+            .verify_beacon(&public_key, 1, None, &sha_signature)
+            .is_err());
+    }
+}
@@ -0,0 +1,561 @@
+//! Text encodings of keys and signatures, and conversions from and to bytes.
+//!
+//! `PrivateKey`, `PublicKey` and `Signature` implement `TryFrom<&[u8]>` with the encodings taken
+//! by the functions of this crate (32 big endian bytes for private keys and the compressed format
+//! for public keys and signatures), and `Vec<u8>` implements `TryFrom` of references to them.
+//! They hold points rather than bytes, so they do not implement `AsRef<[u8]>`. The same encodings
+//! are used by their `ByteEncoding` implementations, required by the `Bls` trait, which also
+//! requires private keys to be created from nonzero scalars and public keys and signatures to be
+//! converted into their points in G2 and G1.
+//!
+//! Public keys and signatures implement `Display`, `LowerHex` and `FromStr` with the hexadecimal
+//! encoding of their compressed format, and private keys implement `LowerHex` and `FromStr` with
+//! the hexadecimal encoding of their 32 big endian bytes. Strings are parsed with or without the
+//! `0x` prefix, which is emitted by `Display` and by `LowerHex` with the `#` flag (`{:#x}`).
+//!
+//! With the `base64` feature, public keys and signatures can also be encoded in base64, either
+//! with the standard alphabet and padding or with the URL-safe alphabet without padding (see
+//! `Base64Variant`). Decoding is strict: only the canonical encoding of a key or signature of the
+//! expected length is accepted.
+use ::core::convert::TryFrom;
+use ::core::fmt;
+use ::core::str::FromStr;
+use alloc::string::String;
+use alloc::vec::Vec;
+use bn::{Fr, G1, G2};
+use zeroize::Zeroizing;
+
+use super::error::Error;
+use super::{HashedMessage, PrivateKey, PublicKey, Signature};
+use crate::ByteEncoding;
+
+/// Lowercase hexadecimal digits.
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Function to encode bytes as lowercase hexadecimal.
+pub(super) fn to_hex(bytes: &[u8]) -> String {
+    let mut hex = String::with_capacity(2 * bytes.len());
+    for byte in bytes {
+        hex.push(HEX_DIGITS[(byte >> 4) as usize] as char);
+        hex.push(HEX_DIGITS[(byte & 0x0f) as usize] as char);
+    }
+
+    hex
+}
+
+/// Function to decode hexadecimal, either lowercase or uppercase.
+///
+/// # Arguments
+///
+/// * `hex` - The hexadecimal string, without prefix
+///
+/// # Returns
+///
+/// * If successful, the decoded bytes; otherwise `Error::InvalidEncoding`
+pub(super) fn from_hex(hex: &str) -> Result<Vec<u8>, Error> {
+    let pairs = hex.as_bytes().chunks_exact(2);
+    if !pairs.remainder().is_empty() {
+        return Err(Error::InvalidEncoding);
+    }
+    pairs
+        .map(|pair| Ok((hex_digit(pair[0])? << 4) | hex_digit(pair[1])?))
+        .collect()
+}
+
+/// Function to decode a hexadecimal digit.
+fn hex_digit(digit: u8) -> Result<u8, Error> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(Error::InvalidEncoding),
+    }
+}
+
+/// Function to remove the optional `0x` prefix of a hexadecimal string.
+fn strip_prefix(hex: &str) -> &str {
+    hex.strip_prefix("0x")
+        .or_else(|| hex.strip_prefix("0X"))
+        .unwrap_or(hex)
+}
+
+/// Function to write bytes as lowercase hexadecimal, prefixed with `0x` if `prefix` is set.
+fn write_hex(formatter: &mut fmt::Formatter, bytes: &[u8], prefix: bool) -> fmt::Result {
+    if prefix {
+        formatter.write_str("0x")?;
+    }
+    formatter.write_str(&Zeroizing::new(to_hex(bytes)))
+}
+
+/// Formats the private key as hexadecimal, with the `0x` prefix for `{:#x}`.
+///
+/// `PrivateKey` does not implement `Display` so that secret keys are not written to logs by
+/// accident.
+impl fmt::LowerHex for PrivateKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let bytes = Zeroizing::new(self.to_bytes().map_err(|_| fmt::Error)?);
+        write_hex(formatter, &bytes, formatter.alternate())
+    }
+}
+
+/// Formats the private key without its scalar, so that secret keys in `Debug` output of other
+/// types are not written to logs.
+impl fmt::Debug for PrivateKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("PrivateKey(<redacted>)")
+    }
+}
+
+/// Parses a private key from hexadecimal, with or without the `0x` prefix.
+impl FromStr for PrivateKey {
+    type Err = Error;
+
+    fn from_str(hex: &str) -> Result<Self, Error> {
+        PrivateKey::from_slice(&Zeroizing::new(from_hex(strip_prefix(hex))?))
+    }
+}
+
+/// Formats the compressed public key as `0x`-prefixed hexadecimal.
+impl fmt::Display for PublicKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write_hex(
+            formatter,
+            &self.to_compressed().map_err(|_| fmt::Error)?,
+            true,
+        )
+    }
+}
+
+/// Formats the compressed public key as hexadecimal, with the `0x` prefix for `{:#x}`.
+impl fmt::LowerHex for PublicKey {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let bytes = self.to_compressed().map_err(|_| fmt::Error)?;
+        write_hex(formatter, &bytes, formatter.alternate())
+    }
+}
+
+/// Parses a compressed public key from hexadecimal, with or without the `0x` prefix.
+impl FromStr for PublicKey {
+    type Err = Error;
+
+    fn from_str(hex: &str) -> Result<Self, Error> {
+        PublicKey::from_compressed(&from_hex(strip_prefix(hex))?)
+    }
+}
+
+/// Formats the compressed signature as `0x`-prefixed hexadecimal.
+impl fmt::Display for Signature {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        write_hex(
+            formatter,
+            &self.to_compressed().map_err(|_| fmt::Error)?,
+            true,
+        )
+    }
+}
+
+/// Formats the compressed signature as hexadecimal, with the `0x` prefix for `{:#x}`.
+impl fmt::LowerHex for Signature {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        let bytes = self.to_compressed().map_err(|_| fmt::Error)?;
+        write_hex(formatter, &bytes, formatter.alternate())
+    }
+}
+
+/// Parses a compressed signature from hexadecimal, with or without the `0x` prefix.
+impl FromStr for Signature {
+    type Err = Error;
+
+    fn from_str(hex: &str) -> Result<Self, Error> {
+        Signature::from_compressed(&from_hex(strip_prefix(hex))?)
+    }
+}
+
+/// Parses a private key from 32 big endian bytes (see `PrivateKey::from_slice`).
+impl TryFrom<&[u8]> for PrivateKey {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+        PrivateKey::from_slice(bytes)
+    }
+}
+
+/// Serializes a private key as 32 big endian bytes.
+impl TryFrom<&PrivateKey> for Vec<u8> {
+    type Error = Error;
+
+    fn try_from(private_key: &PrivateKey) -> Result<Self, Error> {
+        private_key.to_bytes()
+    }
+}
+
+/// Parses a public key from its compressed format (see `PublicKey::from_compressed`).
+impl TryFrom<&[u8]> for PublicKey {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+        PublicKey::from_compressed(bytes)
+    }
+}
+
+/// Serializes a public key in compressed format.
+impl TryFrom<&PublicKey> for Vec<u8> {
+    type Error = Error;
+
+    fn try_from(public_key: &PublicKey) -> Result<Self, Error> {
+        public_key.to_compressed()
+    }
+}
+
+/// Parses a signature from its compressed format (see `Signature::from_compressed`).
+impl TryFrom<&[u8]> for Signature {
+    type Error = Error;
+
+    fn try_from(bytes: &[u8]) -> Result<Self, Error> {
+        Signature::from_compressed(bytes)
+    }
+}
+
+/// Serializes a signature in compressed format.
+impl TryFrom<&Signature> for Vec<u8> {
+    type Error = Error;
+
+    fn try_from(signature: &Signature) -> Result<Self, Error> {
+        signature.to_compressed()
+    }
+}
+
+/// Creates a private key from a nonzero scalar.
+impl TryFrom<Fr> for PrivateKey {
+    type Error = Error;
+
+    fn try_from(scalar: Fr) -> Result<Self, Error> {
+        if scalar.is_zero() {
+            return Err(Error::ZeroSecretKey);
+        }
+
+        Ok(PrivateKey(scalar))
+    }
+}
+
+/// Gets the point in G2 of a public key.
+impl From<PublicKey> for G2 {
+    fn from(public_key: PublicKey) -> Self {
+        public_key.0
+    }
+}
+
+/// Gets the point in G1 of a signature.
+impl From<Signature> for G1 {
+    fn from(signature: Signature) -> Self {
+        signature.0
+    }
+}
+
+/// Gets the point in G1 of a hashed message.
+impl From<HashedMessage> for G1 {
+    fn from(message: HashedMessage) -> Self {
+        message.0
+    }
+}
+
+impl ByteEncoding for PrivateKey {
+    type Error = Error;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        PrivateKey::from_slice(bytes)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, Error> {
+        self.to_bytes()
+    }
+}
+
+impl ByteEncoding for PublicKey {
+    type Error = Error;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        PublicKey::from_compressed(bytes)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, Error> {
+        self.to_compressed()
+    }
+}
+
+impl ByteEncoding for Signature {
+    type Error = Error;
+
+    fn decode(bytes: &[u8]) -> Result<Self, Error> {
+        Signature::from_compressed(bytes)
+    }
+
+    fn encode(&self) -> Result<Vec<u8>, Error> {
+        self.to_compressed()
+    }
+}
+
+/// Variants of base64 (RFC 4648).
+#[cfg(feature = "base64")]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Base64Variant {
+    /// Standard alphabet with padding
+    Standard,
+    /// URL and filename safe alphabet without padding
+    UrlSafe,
+}
+
+#[cfg(feature = "base64")]
+impl Base64Variant {
+    /// Function to get the configuration of the variant.
+    fn config(self) -> base64::Config {
+        match self {
+            Base64Variant::Standard => base64::STANDARD,
+            Base64Variant::UrlSafe => base64::URL_SAFE_NO_PAD,
+        }
+    }
+
+    /// Function to get the length of the encoding of `length` bytes.
+    fn encoded_length(self, length: usize) -> usize {
+        // Every 3 bytes take 4 characters, and the last 1 or 2 bytes take 4 characters with
+        // padding or 2 or 3 characters without
+        let remainder = match (self, length % 3) {
+            (_, 0) => 0,
+            (Base64Variant::Standard, _) => 4,
+            (Base64Variant::UrlSafe, remainder) => remainder + 1,
+        };
+
+        length / 3 * 4 + remainder
+    }
+}
+
+/// Function to encode a public key in base64.
+///
+/// # Arguments
+///
+/// * `public_key` - The public key bytes (a point in G2 in compressed format)
+/// * `variant`    - The variant of base64
+///
+/// # Returns
+///
+/// * If successful, the encoded public key; otherwise `Error`
+#[cfg(feature = "base64")]
+pub fn public_key_to_base64(public_key: &[u8], variant: Base64Variant) -> Result<String, Error> {
+    let public_key = PublicKey::from_compressed(public_key)?.to_compressed()?;
+
+    Ok(base64::encode_config(&public_key, variant.config()))
+}
+
+/// Function to decode a public key from base64, validating it.
+///
+/// # Arguments
+///
+/// * `encoded` - The encoded public key
+/// * `variant` - The variant of base64
+///
+/// # Returns
+///
+/// * If successful, the public key bytes (a point in G2 in compressed format); otherwise `Error`
+#[cfg(feature = "base64")]
+pub fn public_key_from_base64(encoded: &str, variant: Base64Variant) -> Result<Vec<u8>, Error> {
+    let public_key = decode_base64(encoded, variant, 65)?;
+    PublicKey::from_compressed(&public_key)?;
+
+    Ok(public_key)
+}
+
+/// Function to encode a signature in base64.
+///
+/// # Arguments
+///
+/// * `signature` - The signature bytes (a point in G1 in compressed format)
+/// * `variant`   - The variant of base64
+///
+/// # Returns
+///
+/// * If successful, the encoded signature; otherwise `Error`
+#[cfg(feature = "base64")]
+pub fn signature_to_base64(signature: &[u8], variant: Base64Variant) -> Result<String, Error> {
+    let signature = Signature::from_compressed(signature)?.to_compressed()?;
+
+    Ok(base64::encode_config(&signature, variant.config()))
+}
+
+/// Function to decode a signature from base64, validating it.
+///
+/// # Arguments
+///
+/// * `encoded` - The encoded signature
+/// * `variant` - The variant of base64
+///
+/// # Returns
+///
+/// * If successful, the signature bytes (a point in G1 in compressed format); otherwise `Error`
+#[cfg(feature = "base64")]
+pub fn signature_from_base64(encoded: &str, variant: Base64Variant) -> Result<Vec<u8>, Error> {
+    let signature = decode_base64(encoded, variant, 33)?;
+    Signature::from_compressed(&signature)?;
+
+    Ok(signature)
+}
+
+/// Function to decode base64 strictly, rejecting any encoding other than the canonical one of
+/// `length` bytes.
+#[cfg(feature = "base64")]
+fn decode_base64(encoded: &str, variant: Base64Variant, length: usize) -> Result<Vec<u8>, Error> {
+    if encoded.len() != variant.encoded_length(length) {
+        return Err(Error::InvalidLength);
+    }
+    let bytes =
+        base64::decode_config(encoded, variant.config()).map_err(|_| Error::InvalidEncoding)?;
+    // Padding and trailing bits must be canonical
+    if base64::encode_config(&bytes, variant.config()) != encoded {
+        return Err(Error::InvalidEncoding);
+    }
+
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::{Bn256, KeyPair};
+    use crate::MultiSignature;
+    use alloc::string::ToString;
+
+    /// Test formatting and parsing keys and signatures as hexadecimal
+    #[test]
+    fn test_hex() {
+        let secret_key = "2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c";
+        let key_pair = KeyPair::from_secret_key(&hex::decode(secret_key).unwrap()).unwrap();
+        let public_key = hex::encode(key_pair.public());
+
+        let private_key: PrivateKey = secret_key.parse().unwrap();
+        assert_eq!(format!("{:x}", private_key), secret_key);
+        assert_eq!(format!("{:#x}", private_key), format!("0x{}", secret_key));
+        let private_key: PrivateKey = format!("0x{}", secret_key).parse().unwrap();
+        assert_eq!(format!("{:x}", private_key), secret_key);
+        assert_eq!(format!("{:?}", private_key), "PrivateKey(<redacted>)");
+
+        let parsed: PublicKey = format!("0x{}", public_key).parse().unwrap();
+        assert_eq!(parsed.to_string(), format!("0x{}", public_key));
+        assert_eq!(format!("{:x}", parsed), public_key);
+        assert_eq!(
+            public_key.to_uppercase().parse::<PublicKey>().unwrap(),
+            parsed
+        );
+
+        let signature = Bn256::default()
+            .sign(&hex::decode(secret_key).unwrap(), b"sample")
+            .unwrap();
+        let parsed: Signature = hex::encode(&signature).parse().unwrap();
+        assert_eq!(parsed.to_string(), format!("0x{}", hex::encode(&signature)));
+        assert_eq!(parsed.to_string().parse::<Signature>().unwrap(), parsed);
+    }
+
+    /// Test converting keys and signatures from and to bytes
+    #[test]
+    fn test_try_from() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+
+        let private_key = PrivateKey::try_from(&secret_key[..]).unwrap();
+        assert_eq!(Vec::try_from(&private_key).unwrap(), secret_key);
+        let parsed = PublicKey::try_from(&public_key[..]).unwrap();
+        assert_eq!(Vec::try_from(&parsed).unwrap(), public_key);
+        let parsed = Signature::try_from(&signature[..]).unwrap();
+        assert_eq!(Vec::try_from(&parsed).unwrap(), signature);
+
+        assert!(PrivateKey::try_from(&secret_key[1..]).is_err());
+        assert!(PublicKey::try_from(&signature[..]).is_err());
+        assert!(Signature::try_from(&public_key[..]).is_err());
+    }
+
+    /// Test that malformed hexadecimal is rejected
+    #[test]
+    fn test_hex_invalid() {
+        assert!("0x".parse::<Signature>().is_err());
+        assert!("0x0".parse::<Signature>().is_err());
+        assert!("0xzz".parse::<PublicKey>().is_err());
+        assert!(
+            "00x2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c"
+                .parse::<PrivateKey>()
+                .is_err()
+        );
+        assert!(
+            "0x0x2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c"
+                .parse::<PrivateKey>()
+                .is_err()
+        );
+    }
+
+    /// Test encoding public keys and signatures in base64
+    #[cfg(feature = "base64")]
+    #[test]
+    fn test_base64() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let public_key = Bn256::default().derive_public_key(&secret_key).unwrap();
+        let signature = Bn256::default().sign(&secret_key, b"sample").unwrap();
+
+        for &variant in [Base64Variant::Standard, Base64Variant::UrlSafe].iter() {
+            let encoded = public_key_to_base64(&public_key, variant).unwrap();
+            assert_eq!(encoded.len(), variant.encoded_length(65));
+            assert_eq!(
+                public_key_from_base64(&encoded, variant).unwrap(),
+                public_key
+            );
+            let encoded = signature_to_base64(&signature, variant).unwrap();
+            assert_eq!(encoded.len(), variant.encoded_length(33));
+            assert_eq!(signature_from_base64(&encoded, variant).unwrap(), signature);
+        }
+
+        let standard = public_key_to_base64(&public_key, Base64Variant::Standard).unwrap();
+        assert!(standard.ends_with('='));
+        let url_safe = public_key_to_base64(&public_key, Base64Variant::UrlSafe).unwrap();
+        assert_eq!(
+            url_safe,
+            standard
+                .trim_end_matches('=')
+                .replace('+', "-")
+                .replace('/', "_")
+        );
+    }
+
+    /// Test that base64 decoding is strict
+    #[cfg(feature = "base64")]
+    #[test]
+    fn test_base64_strict() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let public_key = Bn256::default().derive_public_key(&secret_key).unwrap();
+        let standard = public_key_to_base64(&public_key, Base64Variant::Standard).unwrap();
+        let url_safe = public_key_to_base64(&public_key, Base64Variant::UrlSafe).unwrap();
+
+        // Wrong variant, missing padding, whitespace and wrong length
+        assert!(public_key_from_base64(&standard, Base64Variant::UrlSafe).is_err());
+        assert!(public_key_from_base64(&url_safe, Base64Variant::Standard).is_err());
+        assert!(
+            public_key_from_base64(&format!("{}\n", standard), Base64Variant::Standard).is_err()
+        );
+        assert!(public_key_from_base64(&standard[4..], Base64Variant::Standard).is_err());
+
+        // Non-zero trailing bits: 65 bytes leave 4 unused bits in the last character
+        let mut trailing = url_safe.clone().into_bytes();
+        let last = trailing.len() - 1;
+        trailing[last] = if trailing[last] == b'B' { b'C' } else { b'B' };
+        let trailing = String::from_utf8(trailing).unwrap();
+        assert!(public_key_from_base64(&trailing, Base64Variant::UrlSafe).is_err());
+
+        // A signature is not a public key
+        let signature = Bn256::default().sign(&secret_key, b"sample").unwrap();
+        let encoded = signature_to_base64(&signature, Base64Variant::Standard).unwrap();
+        assert!(public_key_from_base64(&encoded, Base64Variant::Standard).is_err());
+    }
+}
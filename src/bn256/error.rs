@@ -1,25 +1,139 @@
 //! Errors returned by the bn256 library
 use bn::{CurveError, FieldError, GroupError};
-use failure::Fail;
 
-#[derive(Debug, Fail)]
+use alloc::boxed::Box;
+
+use super::PointFormat;
+
+/// Errors of the bn256 library.
+///
+/// The variants describe why an operation failed. Errors of the public inputs (secret keys, public
+/// keys and signatures) are wrapped in `InvalidInput` or `InvalidItem` naming the offending input,
+/// so `kind` returns the underlying variant for matching. New variants may be added in the future.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
 pub enum Error {
-    #[fail(display = "Failed to find a valid point while converting hash to point")]
+    #[error("Beacon does not follow the previous one in the chain")]
+    BrokenChain,
+    #[error("Ciphertext could not be decrypted with the given key")]
+    DecryptionFailed,
+    #[error("Aggregate verification of repeated messages is not allowed by the scheme")]
+    DuplicateMessages,
+    #[error("Dealer has been disqualified from the key generation")]
+    DisqualifiedDealer,
+    #[error("Shares must belong to distinct participants")]
+    DuplicateShareIndex,
+    #[error("Failed to read or write the file")]
+    FileError,
+    #[error("Another public key with the same fingerprint is present")]
+    FingerprintCollision,
+    #[error("Imaginary part of the X coordinate of a G2 point is out of range")]
+    G2CoordinateOutOfRange,
+    #[error("Failed to find a valid point while converting hash to point")]
     HashToPointError,
-    #[fail(display = "Failed to get data from an index out of bounds")]
+    #[error("Domain separation tag must not be empty nor over 255 bytes")]
+    InvalidDstLength,
+    #[error("Failed to expand message due to an invalid output length")]
+    InvalidExpandLength,
+    #[error("Failed to get data from an index out of bounds")]
     IndexOutOfBounds,
-    #[fail(display = "Failed to create group or field due to invalid input encoding")]
+    #[error("Public key must not be the point at infinity")]
+    IdentityPublicKey,
+    #[error("Signature must not be the point at infinity")]
+    IdentitySignature,
+    #[error("Not enough valid shares to reach the threshold")]
+    InsufficientShares,
+    #[error("CBOR encoding is malformed, not deterministic or of an unexpected type")]
+    InvalidCbor,
+    #[error("Failed to create group or field due to invalid input encoding")]
     InvalidEncoding,
-    #[fail(display = "Failed to map point to a curve")]
+    #[error("Invalid {input}: {source}")]
+    InvalidInput {
+        /// Name of the input, e.g. `public key`
+        input: &'static str,
+        /// Reason why the input is not valid
+        source: Box<Error>,
+    },
+    #[error("Invalid item {index} of the input: {source}")]
+    InvalidItem {
+        /// Position of the item in the input
+        index: usize,
+        /// Reason why the item is not valid
+        source: Box<Error>,
+    },
+    #[error("Exported key share has an unknown format or version")]
+    InvalidExportedShare,
+    #[error("Failed to map point to a curve")]
     InvalidGroupPoint,
-    #[fail(display = "Failed to create group or field due to invalid input length")]
+    #[error("Derivation path must be `m` followed by `/`-separated indices below 2^32")]
+    InvalidDerivationPath,
+    #[error("Key file has an unknown format or version")]
+    InvalidKeyFile,
+    #[error("Keystore is malformed or uses unsupported functions")]
+    InvalidKeystore,
+    #[error("Failed to create group or field due to invalid input length")]
     InvalidLength,
-    #[fail(display = "Failed to create a field element")]
+    #[error("Mnemonic has unknown words, a wrong number of words or a wrong checksum")]
+    InvalidMnemonic,
+    #[error("Keystore checksum does not match the password")]
+    InvalidPassword,
+    #[error("PEM or DER encoding is malformed or has an unexpected label or algorithm")]
+    InvalidPem,
+    #[error("Point is not valid in the detected {0} format")]
+    InvalidPointEncoding(PointFormat),
+    #[error("Signer bitmap does not match the members of the group")]
+    InvalidSignerBitmap,
+    #[error("Threshold must be between 1 and the number of participants")]
+    InvalidThreshold,
+    #[error("Key share does not match the verification vector of the dealer")]
+    InvalidShare,
+    #[error("Missing the deal of a qualified dealer")]
+    MissingDeal,
+    #[error("Coordinate must be smaller than the field modulus")]
+    NonCanonicalCoordinate,
+    #[error("Secret key must be smaller than the order of the group")]
+    NonCanonicalSecretKey,
+    #[error("Failed to create a field element")]
     NotMemberError,
-    #[fail(display = "Point was already in affine coordinates (division-by-zero)")]
+    #[error("Failed to find a valid point within the try and increment attempts")]
+    TryAndIncrementExhausted,
+    #[error("Point was already in affine coordinates (division-by-zero)")]
     PointInJacobian,
-    #[fail(display = "BLS verification failed")]
+    #[error("Encoding has trailing bytes after the expected length")]
+    TrailingBytes,
+    #[error("Operation not supported by the selected scheme")]
+    UnsupportedScheme,
+    #[error("BLS verification failed")]
     VerificationFailed,
+    #[error("Secret key must not be zero")]
+    ZeroSecretKey,
+}
+
+impl Error {
+    /// Function to get the underlying error, skipping the context of `InvalidInput` and
+    /// `InvalidItem`.
+    pub fn kind(&self) -> &Error {
+        match self {
+            Error::InvalidInput { source, .. } | Error::InvalidItem { source, .. } => source.kind(),
+            error => error,
+        }
+    }
+
+    /// Function to add the name of the input which caused the error.
+    pub(crate) fn input(self, input: &'static str) -> Self {
+        Error::InvalidInput {
+            input,
+            source: Box::new(self),
+        }
+    }
+
+    /// Function to add the position of the item of the input which caused the error.
+    pub(crate) fn item(self, index: usize) -> Self {
+        Error::InvalidItem {
+            index,
+            source: Box::new(self),
+        }
+    }
 }
 
 impl From<CurveError> for Error {
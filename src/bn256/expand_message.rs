@@ -0,0 +1,190 @@
+//! Message expansion functions from the [hash-to-curve draft](https://tools.ietf.org/html/draft-irtf-cfrg-hash-to-curve) (section 5.4).
+//!
+//! These functions expand a message and a domain separation tag (DST) into an arbitrary number
+//! of uniformly random bytes. They are used internally for hashing to the field, but they are
+//! also exposed so other protocols can derive exactly the same bytes.
+use alloc::vec::Vec;
+use digest::generic_array::typenum::Unsigned;
+use digest::{BlockInput, Digest, ExtendableOutput, Input, XofReader};
+
+use super::error::Error;
+
+/// Prefix used to hash domain separation tags longer than 255 bytes.
+const OVERSIZE_DST_PREFIX: &[u8] = b"H2C-OVERSIZE-DST-";
+
+/// Function to expand a message into `len_in_bytes` uniformly random bytes using a Merkle-Damgård
+/// hash function (`expand_message_xmd`).
+///
+/// Domain separation tags longer than 255 bytes are hashed as specified by the draft.
+///
+/// # Arguments
+///
+/// * `message`      - A slice containing the message to be expanded.
+/// * `dst`          - The domain separation tag.
+/// * `len_in_bytes` - The length of the requested output.
+///
+/// # Returns
+///
+/// * If successful, a vector with `len_in_bytes` uniform bytes.
+pub fn expand_message_xmd<D: Digest + BlockInput>(
+    message: &[u8],
+    dst: &[u8],
+    len_in_bytes: usize,
+) -> Result<Vec<u8>, Error> {
+    let b_in_bytes = D::output_size();
+    let s_in_bytes = D::BlockSize::to_usize();
+    let ell = len_in_bytes.div_ceil(b_in_bytes);
+    if ell > 255 || len_in_bytes > 65535 {
+        return Err(Error::InvalidExpandLength);
+    }
+
+    // Hash oversized DSTs: `H("H2C-OVERSIZE-DST-" || DST)`
+    let oversize_dst;
+    let dst = if dst.len() > 255 {
+        oversize_dst = D::new().chain(OVERSIZE_DST_PREFIX).chain(dst).result();
+        &oversize_dst[..]
+    } else {
+        dst
+    };
+    let dst_prime = [dst, &[dst.len() as u8]].concat();
+
+    // `b_0 = H(Z_pad || msg || l_i_b_str || I2OSP(0, 1) || DST_prime)`
+    let b_0 = D::new()
+        .chain(vec![0u8; s_in_bytes])
+        .chain(message)
+        .chain([(len_in_bytes >> 8) as u8, len_in_bytes as u8, 0])
+        .chain(&dst_prime)
+        .result();
+
+    // `b_1 = H(b_0 || I2OSP(1, 1) || DST_prime)`
+    let mut b_i = D::new().chain(&b_0).chain([1]).chain(&dst_prime).result();
+    let mut uniform_bytes = Vec::with_capacity(ell * b_in_bytes);
+    uniform_bytes.extend_from_slice(&b_i);
+
+    // `b_i = H(strxor(b_0, b_(i - 1)) || I2OSP(i, 1) || DST_prime)`
+    for i in 2..=ell {
+        let xored: Vec<u8> = b_0.iter().zip(b_i.iter()).map(|(a, b)| a ^ b).collect();
+        b_i = D::new()
+            .chain(&xored)
+            .chain([i as u8])
+            .chain(&dst_prime)
+            .result();
+        uniform_bytes.extend_from_slice(&b_i);
+    }
+    uniform_bytes.truncate(len_in_bytes);
+
+    Ok(uniform_bytes)
+}
+
+/// Function to expand a message into `len_in_bytes` uniformly random bytes using an extendable
+/// output function such as SHAKE-128 or SHAKE-256 (`expand_message_xof`).
+///
+/// Domain separation tags longer than 255 bytes must be hashed by the caller (as specified by the
+/// draft for the target security level), otherwise an error is returned.
+///
+/// # Arguments
+///
+/// * `message`      - A slice containing the message to be expanded.
+/// * `dst`          - The domain separation tag.
+/// * `len_in_bytes` - The length of the requested output.
+///
+/// # Returns
+///
+/// * If successful, a vector with `len_in_bytes` uniform bytes.
+pub fn expand_message_xof<X: Input + ExtendableOutput + Default>(
+    message: &[u8],
+    dst: &[u8],
+    len_in_bytes: usize,
+) -> Result<Vec<u8>, Error> {
+    if len_in_bytes > 65535 {
+        return Err(Error::InvalidExpandLength);
+    }
+    if dst.len() > 255 {
+        return Err(Error::InvalidDstLength);
+    }
+
+    // `msg_prime = msg || I2OSP(len_in_bytes, 2) || DST || I2OSP(len(DST), 1)`
+    let mut xof = X::default();
+    xof.input(message);
+    xof.input([(len_in_bytes >> 8) as u8, len_in_bytes as u8]);
+    xof.input(dst);
+    xof.input([dst.len() as u8]);
+
+    let mut uniform_bytes = vec![0u8; len_in_bytes];
+    xof.xof_result().read(&mut uniform_bytes);
+
+    Ok(uniform_bytes)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sha2::{Sha256, Sha512};
+    use sha3::{Shake128, Shake256};
+
+    /// Test `expand_message_xmd` with the SHA-256 test vectors from the draft
+    #[test]
+    fn test_expand_message_xmd_sha256() {
+        let dst = b"QUUX-V01-CS02-with-expander-SHA256-128";
+
+        let uniform_bytes = expand_message_xmd::<Sha256>(b"", dst, 0x20).unwrap();
+        assert_eq!(
+            hex::encode(uniform_bytes),
+            "68a985b87eb6b46952128911f2a4412bbc302a9d759667f87f7a21d803f07235"
+        );
+
+        let uniform_bytes = expand_message_xmd::<Sha256>(b"abc", dst, 0x20).unwrap();
+        assert_eq!(
+            hex::encode(uniform_bytes),
+            "d8ccab23b5985ccea865c6c97b6e5b8350e794e603b4b97902f53a8a0d605615"
+        );
+    }
+
+    /// Test `expand_message_xmd` with the SHA-512 test vectors from the draft
+    #[test]
+    fn test_expand_message_xmd_sha512() {
+        let dst = b"QUUX-V01-CS02-with-expander-SHA512-256";
+
+        let uniform_bytes = expand_message_xmd::<Sha512>(b"", dst, 0x20).unwrap();
+        assert_eq!(
+            hex::encode(uniform_bytes),
+            "6b9a7312411d92f921c6f68ca0b6380730a1a4d982c507211a90964c394179ba"
+        );
+    }
+
+    /// Test `expand_message_xmd` rejects output lengths over `255 * b_in_bytes`
+    #[test]
+    fn test_expand_message_xmd_invalid_length() {
+        assert!(expand_message_xmd::<Sha256>(b"abc", b"DST", 255 * 32 + 1).is_err());
+    }
+
+    /// Test `expand_message_xof` with the SHAKE-128 and SHAKE-256 test vectors from the draft
+    #[test]
+    fn test_expand_message_xof() {
+        let dst = b"QUUX-V01-CS02-with-expander-SHAKE128";
+        let uniform_bytes = expand_message_xof::<Shake128>(b"", dst, 0x20).unwrap();
+        assert_eq!(
+            hex::encode(uniform_bytes),
+            "86518c9cd86581486e9485aa74ab35ba150d1c75c88e26b7043e44e2acd735a2"
+        );
+        let uniform_bytes = expand_message_xof::<Shake128>(b"abc", dst, 0x20).unwrap();
+        assert_eq!(
+            hex::encode(uniform_bytes),
+            "8696af52a4d862417c0763556073f47bc9b9ba43c99b505305cb1ec04a9ab468"
+        );
+
+        let dst = b"QUUX-V01-CS02-with-expander-SHAKE256";
+        let uniform_bytes = expand_message_xof::<Shake256>(b"", dst, 0x20).unwrap();
+        assert_eq!(
+            hex::encode(uniform_bytes),
+            "2ffc05c48ed32b95d72e807f6eab9f7530dd1c2f013914c8fed38c5ccc15ad76"
+        );
+    }
+
+    /// Test `expand_message_xof` rejects domain separation tags over 255 bytes
+    #[test]
+    fn test_expand_message_xof_invalid_dst() {
+        let dst = [0x42u8; 256];
+        assert!(expand_message_xof::<Shake128>(b"abc", &dst, 0x20).is_err());
+    }
+}
@@ -0,0 +1,171 @@
+//! Fingerprints of public keys, short identifiers for logging, deduplication and key lookups.
+//!
+//! The fingerprint of a public key is the first `FINGERPRINT_LENGTH` bytes of the SHA-256 digest
+//! of its canonical compressed encoding, so it does not depend on how the key was received.
+//! `PublicKeyMap` stores public keys by fingerprint, rejecting distinct keys whose fingerprints
+//! collide rather than silently replacing one by the other.
+use alloc::collections::btree_map::{BTreeMap, Entry};
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+
+use super::error::Error;
+use super::PublicKey;
+
+/// Length of fingerprints in bytes.
+pub const FINGERPRINT_LENGTH: usize = 8;
+
+/// The fingerprint of a public key.
+pub type Fingerprint = [u8; FINGERPRINT_LENGTH];
+
+/// Function to compute the fingerprint of a public key.
+///
+/// # Arguments
+///
+/// * `public_key` - The public key bytes (a point in G2 in compressed format)
+///
+/// # Returns
+///
+/// * If successful, the `Fingerprint`; otherwise `Error`
+pub fn fingerprint(public_key: &[u8]) -> Result<Fingerprint, Error> {
+    PublicKey::from_compressed(public_key)?.fingerprint()
+}
+
+/// Function to compute the fingerprint of a canonical compressed public key.
+pub(super) fn fingerprint_compressed(public_key: &[u8]) -> Fingerprint {
+    let mut fingerprint = [0u8; FINGERPRINT_LENGTH];
+    fingerprint.copy_from_slice(&Sha256::digest(public_key)[..FINGERPRINT_LENGTH]);
+
+    fingerprint
+}
+
+/// A set of public keys indexed by fingerprint.
+#[derive(Clone, Debug, Default)]
+pub struct PublicKeyMap {
+    /// The canonical compressed public keys by fingerprint
+    keys: BTreeMap<Fingerprint, Vec<u8>>,
+}
+
+impl PublicKeyMap {
+    /// Function to create an empty `PublicKeyMap`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Function to insert a public key, which is validated first.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key` - The public key bytes (a point in G2 in compressed format)
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the fingerprint of the public key; otherwise `Error`, which is
+    ///   `Error::FingerprintCollision` if another public key with the same fingerprint is present
+    pub fn insert(&mut self, public_key: &[u8]) -> Result<Fingerprint, Error> {
+        let public_key = PublicKey::from_compressed(public_key)?.to_compressed()?;
+        let fingerprint = fingerprint_compressed(&public_key);
+        match self.keys.entry(fingerprint) {
+            Entry::Occupied(entry) => {
+                if *entry.get() != public_key {
+                    return Err(Error::FingerprintCollision);
+                }
+            }
+            Entry::Vacant(entry) => {
+                entry.insert(public_key);
+            }
+        }
+
+        Ok(fingerprint)
+    }
+
+    /// Function to get the public key with a fingerprint.
+    pub fn get(&self, fingerprint: &Fingerprint) -> Option<&[u8]> {
+        self.keys.get(fingerprint).map(Vec::as_slice)
+    }
+
+    /// Function to remove the public key with a fingerprint, returning it if it was present.
+    pub fn remove(&mut self, fingerprint: &Fingerprint) -> Option<Vec<u8>> {
+        self.keys.remove(fingerprint)
+    }
+
+    /// Function to check whether a public key is present.
+    pub fn contains(&self, public_key: &[u8]) -> bool {
+        match PublicKey::from_compressed(public_key).and_then(|key| key.to_compressed()) {
+            Ok(public_key) => {
+                self.get(&fingerprint_compressed(&public_key)) == Some(&public_key[..])
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Function to get the number of public keys.
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Function to check whether there are no public keys.
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Function to iterate over the fingerprints and public keys, ordered by fingerprint.
+    pub fn iter(&self) -> impl Iterator<Item = (&Fingerprint, &[u8])> {
+        self.keys
+            .iter()
+            .map(|(fingerprint, public_key)| (fingerprint, public_key.as_slice()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::Bn256;
+    use crate::MultiSignature;
+
+    /// Test fingerprinting public keys and looking them up
+    #[test]
+    fn test_public_key_map() {
+        let bn256 = Bn256::default();
+        let public_key = bn256
+            .derive_public_key(
+                &hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                    .unwrap(),
+            )
+            .unwrap();
+        let other_public_key = bn256
+            .derive_public_key(
+                &hex::decode("09afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721")
+                    .unwrap(),
+            )
+            .unwrap();
+
+        let expected = fingerprint_compressed(&public_key);
+        assert_eq!(
+            expected[..],
+            Sha256::digest(&public_key)[..FINGERPRINT_LENGTH]
+        );
+        assert_eq!(fingerprint(&public_key).unwrap(), expected);
+        assert!(fingerprint(&public_key[1..]).is_err());
+
+        let mut map = PublicKeyMap::new();
+        assert!(map.is_empty());
+        assert_eq!(map.insert(&public_key).unwrap(), expected);
+        assert_eq!(map.insert(&public_key).unwrap(), expected);
+        let other_fingerprint = map.insert(&other_public_key).unwrap();
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&expected), Some(&public_key[..]));
+        assert!(map.contains(&other_public_key));
+        assert_eq!(map.iter().count(), 2);
+
+        // A different key under an existing fingerprint
+        map.keys.insert(other_fingerprint, public_key.clone());
+        match map.insert(&other_public_key) {
+            Err(Error::FingerprintCollision) => {}
+            _ => panic!("Expected FingerprintCollision error"),
+        }
+        assert!(!map.contains(&other_public_key));
+
+        assert_eq!(map.remove(&expected), Some(public_key.clone()));
+        assert!(map.get(&expected).is_none());
+    }
+}
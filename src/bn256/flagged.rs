@@ -0,0 +1,242 @@
+//! Compressed encoding with the flags in the most significant bits of X, the layout used by
+//! gnark for BN256 and, with three flag bits instead of two, by Zcash, py_ecc and blst for
+//! BLS12-381.
+//!
+//! Points are encoded with their affine X coordinate only, in 32 big endian bytes for G1
+//! (signatures) and 64 bytes for G2 (public keys, `X.imaginary || X.real`). Base field elements
+//! take 254 bits, which leaves the two most significant bits of the first byte for the flags:
+//!
+//! - `0b10`: compressed point whose Y is the lexicographically smallest of `Y` and `-Y`
+//! - `0b11`: compressed point whose Y is the lexicographically largest of `Y` and `-Y`
+//! - `0b01`: point at infinity
+//!
+//! An element of Fq2 is compared by its real part, or by its imaginary part if the real part is
+//! zero. The point at infinity is never a valid signature or public key, so it is rejected when
+//! decoding.
+use alloc::vec::Vec;
+use bn::{AffineG1, AffineG2, Fq, Fq2, G1, G2};
+use subtle::{Choice, ConstantTimeEq};
+
+use super::error::Error;
+use super::{
+    check_length, core, ct_compare, fq_from_slice, g1_from_compressed, g1_to_compressed, PublicKey,
+};
+
+/// Length of a flagged G1 point in bytes.
+pub const G1_LENGTH: usize = 32;
+
+/// Length of a flagged G2 point in bytes.
+pub const G2_LENGTH: usize = 64;
+
+/// Mask of the flags in the first byte.
+const FLAG_MASK: u8 = 0b1100_0000;
+
+/// Flags of a compressed point with the lexicographically smallest Y.
+const COMPRESSED_SMALLEST: u8 = 0b1000_0000;
+
+/// Flags of a compressed point with the lexicographically largest Y.
+const COMPRESSED_LARGEST: u8 = 0b1100_0000;
+
+/// Flags of the point at infinity.
+const INFINITY: u8 = 0b0100_0000;
+
+/// Function to convert a signature from compressed (33 bytes) to flagged format (32 bytes).
+///
+/// # Arguments
+///
+/// * `signature` - The signature bytes (a point in G1 in compressed format)
+///
+/// # Returns
+///
+/// * If successful, a vector of 32 bytes with the flagged signature; otherwise `Error`
+pub fn signature_to_flagged(signature: &[u8]) -> Result<Vec<u8>, Error> {
+    let point = g1_from_compressed(signature)?;
+    let affine = AffineG1::from_jacobian(point).ok_or(Error::PointInJacobian)?;
+    let mut bytes = [0u8; G1_LENGTH];
+    affine.x().to_big_endian(&mut bytes)?;
+    bytes[0] |= flags(is_largest(affine.y()).into());
+
+    Ok(bytes.to_vec())
+}
+
+/// Function to convert a signature from flagged (32 bytes) to compressed format (33 bytes).
+///
+/// # Arguments
+///
+/// * `bytes` - The flagged signature bytes
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the compressed signature; otherwise `Error`
+pub fn signature_from_flagged(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    check_length(bytes, G1_LENGTH)?;
+    let (largest, x) = split_flags(bytes)?;
+    let x = fq_from_slice(&x)?;
+    let y = (x * x * x + G1::b()).sqrt().ok_or(Error::NotMemberError)?;
+    let y = if is_largest(y).ct_eq(&Choice::from(largest as u8)).into() {
+        y
+    } else {
+        -y
+    };
+
+    g1_to_compressed(AffineG1::new(x, y)?.into())
+}
+
+/// Function to convert a public key from compressed (65 bytes) to flagged format (64 bytes).
+///
+/// # Arguments
+///
+/// * `public_key` - The public key bytes (a point in G2 in compressed format)
+///
+/// # Returns
+///
+/// * If successful, a vector of 64 bytes with the flagged public key; otherwise `Error`
+pub fn public_key_to_flagged(public_key: &[u8]) -> Result<Vec<u8>, Error> {
+    let PublicKey(point) = PublicKey::from_compressed(public_key)?;
+    let affine = AffineG2::from_jacobian(point).ok_or(Error::PointInJacobian)?;
+    let mut bytes = [0u8; G2_LENGTH];
+    affine.x().imaginary().to_big_endian(&mut bytes[..32])?;
+    affine.x().real().to_big_endian(&mut bytes[32..])?;
+    bytes[0] |= flags(is_largest_fq2(affine.y()).into());
+
+    Ok(bytes.to_vec())
+}
+
+/// Function to convert a public key from flagged (64 bytes) to compressed format (65 bytes),
+/// validating it.
+///
+/// # Arguments
+///
+/// * `bytes` - The flagged public key bytes
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the compressed public key; otherwise `Error`
+pub fn public_key_from_flagged(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    check_length(bytes, G2_LENGTH)?;
+    let (largest, x) = split_flags(bytes)?;
+    let x = Fq2::new(fq_from_slice(&x[32..])?, fq_from_slice(&x[..32])?);
+    let y = (x * x * x + G2::b()).sqrt().ok_or(Error::NotMemberError)?;
+    let y = if is_largest_fq2(y).ct_eq(&Choice::from(largest as u8)).into() {
+        y
+    } else {
+        -y
+    };
+    let point: G2 = AffineG2::new(x, y)?.into();
+    core::key_validate(point)?;
+
+    PublicKey(point).to_compressed()
+}
+
+/// Function to get the flags of a compressed point.
+fn flags(largest: bool) -> u8 {
+    if largest {
+        COMPRESSED_LARGEST
+    } else {
+        COMPRESSED_SMALLEST
+    }
+}
+
+/// Function to split the flags from a flagged point.
+///
+/// # Arguments
+///
+/// * `bytes` - The flagged point bytes
+///
+/// # Returns
+///
+/// * If successful, whether Y is the lexicographically largest and the bytes of X; otherwise
+///   `Error`
+fn split_flags(bytes: &[u8]) -> Result<(bool, Vec<u8>), Error> {
+    let largest = match bytes[0] & FLAG_MASK {
+        COMPRESSED_SMALLEST => false,
+        COMPRESSED_LARGEST => true,
+        // The point at infinity is not a valid signature or public key
+        INFINITY => return Err(Error::InvalidEncoding),
+        _ => return Err(Error::InvalidEncoding),
+    };
+    let mut x = bytes.to_vec();
+    x[0] &= !FLAG_MASK;
+
+    Ok((largest, x))
+}
+
+/// Function to check, in constant time, whether an element of Fq is lexicographically larger than
+/// its negation.
+fn is_largest(y: Fq) -> Choice {
+    let (greater, _) = ct_compare(&y.into_u256().0, &(-y).into_u256().0);
+
+    greater
+}
+
+/// Function to check, in constant time, whether an element of Fq2 is lexicographically larger than
+/// its negation.
+fn is_largest_fq2(y: Fq2) -> Choice {
+    let real_is_zero = Choice::from(y.real().is_zero() as u8);
+
+    (real_is_zero & is_largest(y.imaginary())) | (!real_is_zero & is_largest(y.real()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::Bn256;
+    use crate::MultiSignature;
+    use bn::Group;
+
+    /// Test the flagged encoding of the generators
+    #[test]
+    fn test_flagged_generators() {
+        let generator = g1_to_compressed(G1::one()).unwrap();
+        let flagged = signature_to_flagged(&generator).unwrap();
+        assert_eq!(
+            hex::encode(&flagged),
+            "8000000000000000000000000000000000000000000000000000000000000001"
+        );
+        assert_eq!(signature_from_flagged(&flagged).unwrap(), generator);
+
+        let generator = PublicKey(G2::one()).to_compressed().unwrap();
+        let flagged = public_key_to_flagged(&generator).unwrap();
+        assert_eq!(
+            hex::encode(&flagged),
+            "998e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+             1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed"
+        );
+        assert_eq!(public_key_from_flagged(&flagged).unwrap(), generator);
+    }
+
+    /// Test round trips of keys and signatures, and the rejection of invalid flags
+    #[test]
+    fn test_flagged_round_trip() {
+        let secret_key =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+
+        let flagged_public_key = public_key_to_flagged(&public_key).unwrap();
+        let flagged_signature = signature_to_flagged(&signature).unwrap();
+        assert_eq!(
+            public_key_from_flagged(&flagged_public_key).unwrap(),
+            public_key
+        );
+        assert_eq!(
+            signature_from_flagged(&flagged_signature).unwrap(),
+            signature
+        );
+
+        // Flipping the sign flag gives the negated point
+        let mut negated = flagged_signature.clone();
+        negated[0] ^= COMPRESSED_LARGEST ^ COMPRESSED_SMALLEST;
+        let negated = G1::from_compressed(&signature_from_flagged(&negated).unwrap()).unwrap();
+        assert_eq!(negated, -G1::from_compressed(&signature).unwrap());
+
+        let mut invalid = flagged_signature.clone();
+        invalid[0] &= !FLAG_MASK;
+        assert!(signature_from_flagged(&invalid).is_err());
+        invalid[0] |= INFINITY;
+        assert!(signature_from_flagged(&invalid).is_err());
+        assert!(public_key_from_flagged(&flagged_public_key[1..]).is_err());
+    }
+}
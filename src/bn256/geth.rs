@@ -0,0 +1,173 @@
+//! Encodings compatible with `Marshal` and `Unmarshal` of the bn256 package of go-ethereum
+//! (`crypto/bn256`), from which the test vectors of this crate come.
+//!
+//! Points are encoded with their affine coordinates in 32 big endian bytes each:
+//!
+//! - G1 (signatures): `X || Y`, 64 bytes
+//! - G2 (public keys): `X.imaginary || X.real || Y.imaginary || Y.real`, 128 bytes
+//!
+//! The point at infinity is encoded as zeros. It is never a valid signature or public key, so it
+//! is rejected when decoding.
+use alloc::vec::Vec;
+use bn::{AffineG2, Fq2, G2};
+
+use super::error::Error;
+use super::{
+    check_length, core, fq_from_slice, g1_from_compressed, g1_from_uncompressed, g1_to_compressed,
+    g1_to_uncompressed, PublicKey,
+};
+
+/// Length of a marshaled G1 point in bytes.
+pub const G1_LENGTH: usize = 64;
+
+/// Length of a marshaled G2 point in bytes.
+pub const G2_LENGTH: usize = 128;
+
+/// Function to marshal a signature as go-ethereum does.
+///
+/// # Arguments
+///
+/// * `signature` - The signature bytes (a point in G1 in compressed format)
+///
+/// # Returns
+///
+/// * If successful, a vector of 64 bytes with the marshaled signature; otherwise `Error`
+pub fn marshal_signature(signature: &[u8]) -> Result<Vec<u8>, Error> {
+    g1_to_uncompressed(g1_from_compressed(signature)?)
+}
+
+/// Function to unmarshal a signature marshaled by go-ethereum.
+///
+/// # Arguments
+///
+/// * `bytes` - The marshaled signature (64 bytes)
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the compressed signature; otherwise `Error`
+pub fn unmarshal_signature(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    g1_to_compressed(g1_from_uncompressed(bytes)?)
+}
+
+/// Function to marshal a public key as go-ethereum does.
+///
+/// # Arguments
+///
+/// * `public_key` - The public key bytes (a point in G2 in compressed format)
+///
+/// # Returns
+///
+/// * If successful, a vector of 128 bytes with the marshaled public key; otherwise `Error`
+pub fn marshal_public_key(public_key: &[u8]) -> Result<Vec<u8>, Error> {
+    let PublicKey(point) = PublicKey::from_compressed(public_key)?;
+
+    Ok(marshal_g2(point)?.to_vec())
+}
+
+/// Function to marshal a `G2` point, encoding the point at infinity as zeros.
+///
+/// # Arguments
+///
+/// * `point` - A `G2` point.
+///
+/// # Returns
+///
+/// * If successful, the 128-byte marshaled `G2` point.
+pub(super) fn marshal_g2(point: G2) -> Result<[u8; G2_LENGTH], Error> {
+    let mut bytes = [0u8; G2_LENGTH];
+    if let Some(affine) = AffineG2::from_jacobian(point) {
+        let coordinates = [
+            affine.x().imaginary(),
+            affine.x().real(),
+            affine.y().imaginary(),
+            affine.y().real(),
+        ];
+        for (coordinate, chunk) in coordinates.iter().zip(bytes.chunks_mut(32)) {
+            coordinate.into_u256().to_big_endian(chunk)?;
+        }
+    }
+
+    Ok(bytes)
+}
+
+/// Function to unmarshal a public key marshaled by go-ethereum, validating it.
+///
+/// # Arguments
+///
+/// * `bytes` - The marshaled public key (128 bytes)
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the compressed public key; otherwise `Error`
+pub fn unmarshal_public_key(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    check_length(bytes, G2_LENGTH)?;
+    let x = Fq2::new(
+        fq_from_slice(&bytes[32..64])?,
+        fq_from_slice(&bytes[0..32])?,
+    );
+    let y = Fq2::new(
+        fq_from_slice(&bytes[96..128])?,
+        fq_from_slice(&bytes[64..96])?,
+    );
+    let point: G2 = AffineG2::new(x, y)?.into();
+    core::key_validate(point)?;
+
+    PublicKey(point).to_compressed()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::{public_key_to_uncompressed, Bn256};
+    use crate::MultiSignature;
+    use bn::{Group, G1};
+
+    /// Test marshaling the generators as go-ethereum does
+    #[test]
+    fn test_marshal_generators() {
+        let generator = PublicKey(G2::one()).to_compressed().unwrap();
+        let marshaled = marshal_public_key(&generator).unwrap();
+        assert_eq!(
+            hex::encode(&marshaled),
+            "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2\
+             1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed\
+             090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b\
+             12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa"
+        );
+        assert_eq!(unmarshal_public_key(&marshaled).unwrap(), generator);
+
+        let generator = g1_to_compressed(G1::one()).unwrap();
+        let marshaled = marshal_signature(&generator).unwrap();
+        let mut expected = [0u8; G1_LENGTH];
+        expected[31] = 1;
+        expected[63] = 2;
+        assert_eq!(&marshaled[..], &expected[..]);
+        assert_eq!(unmarshal_signature(&marshaled).unwrap(), generator);
+    }
+
+    /// Test round trips of keys and signatures, and the rejection of the point at infinity
+    #[test]
+    fn test_marshal_round_trip() {
+        let secret_key =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+
+        let marshaled = marshal_public_key(&public_key).unwrap();
+        let uncompressed = public_key_to_uncompressed(&public_key).unwrap();
+        // Same coordinates as the uncompressed format, with the imaginary parts first
+        assert_eq!(&marshaled[0..32], &uncompressed[32..64]);
+        assert_eq!(&marshaled[32..64], &uncompressed[0..32]);
+        assert_eq!(unmarshal_public_key(&marshaled).unwrap(), public_key);
+        assert_eq!(
+            unmarshal_signature(&marshal_signature(&signature).unwrap()).unwrap(),
+            signature
+        );
+
+        assert!(unmarshal_public_key(&[0u8; G2_LENGTH]).is_err());
+        assert!(unmarshal_signature(&[0u8; G1_LENGTH]).is_err());
+        assert!(unmarshal_public_key(&marshaled[1..]).is_err());
+    }
+}
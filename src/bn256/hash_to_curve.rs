@@ -0,0 +1,377 @@
+//! Hashing to G1 as specified in the [hash-to-curve draft](https://tools.ietf.org/html/draft-irtf-cfrg-hash-to-curve).
+//!
+//! This module implements the `BN254G1_XMD:SHA-256_SVDW_RO_` suite:
+//!
+//! - `expand_message::expand_message_xmd` with SHA-256 for expanding the message into uniform bytes
+//! - `hash_to_field::hash_to_field` reducing `L = 48` bytes modulo `p` for each field element
+//! - the Shallue–van de Woestijne map with `Z = 1`
+//! - the random oracle construction `map_to_curve(u0) + map_to_curve(u1)`
+//!
+//! Ciphersuites expanding messages with Keccak-256 instead of SHA-256 (e.g.
+//! `BN254G1_XMD:KECCAK-256_SVDW_RO_`) are supported by `hash_to_curve_with_hash`.
+//!
+//! Cofactor clearing is not required as G1 has cofactor 1.
+//!
+//! The [Fouque–Tibouchi](https://www.di.ens.fr/~fouque/pub/latincrypt12.pdf) encoding for BN curves
+//! can be selected instead of Shallue–van de Woestijne with `hash_to_curve_with_map`.
+//!
+//! [mcl](https://github.com/herumi/mcl) (and the verifiers compatible with it) hashes a message to
+//! a single field element and maps it once with the Fouque–Tibouchi encoding, which is provided by
+//! `hash_to_curve_mcl` and `map_to_curve_mcl`:
+//!
+//! - `t` is `SHA-256(message)` read as a little endian integer with its bits from the 253rd on
+//!   cleared (`Fp::setHashOf`)
+//! - `sqrt(-3)` is `(-3)^((p + 1) / 4)`, the square root computed by mcl for `p = 3 mod 4`
+//! - `t = 0` is rejected instead of being mapped to a fixed point
+//! - the sign of `y` is given by the Legendre symbol of `t`
+//!
+//! The test vectors of this path are generated by this crate, following the algorithm of mcl's
+//! `MapTo::calcBN`.
+//!
+//! Contrary to try and increment, the running time of `hash_to_curve` does not depend on the
+//! input message.
+use bn::{AffineG1, Fq, G1};
+use digest::{BlockInput, Digest};
+use sha2::Sha256;
+use sha3::Keccak256;
+
+use super::error::Error;
+use super::hash_to_field::hash_to_field;
+
+/// Maps from field elements to points in G1 supported by `hash_to_curve_with_map`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MapToCurve {
+    /// Shallue–van de Woestijne map, as specified by the hash-to-curve draft.
+    ShallueVanDeWoestijne,
+    /// Fouque–Tibouchi encoding for BN curves, as used by mcl compatible verifiers.
+    FouqueTibouchi,
+}
+
+/// Hash functions for expanding messages with `expand_message_xmd`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HashFunction {
+    /// SHA-256, as used by the `BN254G1_XMD:SHA-256_SVDW_RO_` suite.
+    Sha256,
+    /// Keccak-256, as used by EVM compatible deployments (e.g. the drand `evmnet` network).
+    Keccak256,
+}
+
+/// Function to hash an arbitrary message to a point in G1 using the Shallue–van de Woestijne map.
+///
+/// # Arguments
+///
+/// * `message` - A slice containing the message to be hashed.
+/// * `dst`     - The domain separation tag.
+///
+/// # Returns
+///
+/// * If successful, a point in the `G1` group representing the hashed point.
+pub fn hash_to_curve(message: &[u8], dst: &[u8]) -> Result<G1, Error> {
+    hash_to_curve_with_map(message, dst, MapToCurve::ShallueVanDeWoestijne)
+}
+
+/// Function to hash an arbitrary message to a point in G1 using the selected map.
+///
+/// # Arguments
+///
+/// * `message` - A slice containing the message to be hashed.
+/// * `dst`     - The domain separation tag.
+/// * `map`     - The map from field elements to points in G1.
+///
+/// # Returns
+///
+/// * If successful, a point in the `G1` group representing the hashed point.
+pub fn hash_to_curve_with_map(message: &[u8], dst: &[u8], map: MapToCurve) -> Result<G1, Error> {
+    hash_to_curve_with_hash(message, dst, map, HashFunction::Sha256)
+}
+
+/// Function to hash an arbitrary message to a point in G1 using the selected map and hash
+/// function for expanding the message.
+///
+/// # Arguments
+///
+/// * `message` - A slice containing the message to be hashed.
+/// * `dst`     - The domain separation tag.
+/// * `map`     - The map from field elements to points in G1.
+/// * `hash`    - The hash function used by `expand_message_xmd`.
+///
+/// # Returns
+///
+/// * If successful, a point in the `G1` group representing the hashed point.
+pub fn hash_to_curve_with_hash(
+    message: &[u8],
+    dst: &[u8],
+    map: MapToCurve,
+    hash: HashFunction,
+) -> Result<G1, Error> {
+    match hash {
+        HashFunction::Sha256 => hash_to_curve_with_digest::<Sha256>(message, dst, map),
+        HashFunction::Keccak256 => hash_to_curve_with_digest::<Keccak256>(message, dst, map),
+    }
+}
+
+/// Function to hash a message to G1 expanding it with the digest `D`.
+fn hash_to_curve_with_digest<D: Digest + BlockInput>(
+    message: &[u8],
+    dst: &[u8],
+    map: MapToCurve,
+) -> Result<G1, Error> {
+    let map = match map {
+        MapToCurve::ShallueVanDeWoestijne => map_to_curve,
+        MapToCurve::FouqueTibouchi => map_to_curve_fouque_tibouchi,
+    };
+    let u = hash_to_field::<Fq, D>(message, dst, 2)?;
+    let q0 = map(u[0])?;
+    let q1 = map(u[1])?;
+
+    Ok(q0 + q1)
+}
+
+/// Function to map a field element to a point in G1 using the Shallue–van de Woestijne method.
+///
+/// # Arguments
+///
+/// * `u` - The field element to be mapped.
+///
+/// # Returns
+///
+/// * If successful, a point in the `G1` group.
+pub fn map_to_curve(u: Fq) -> Result<G1, Error> {
+    let one = Fq::one();
+    let z = one;
+    let three_z2 = (one + one + one) * z * z;
+
+    // `c1 = g(Z)`
+    let c1 = curve_equation(z);
+    // `c2 = -Z / 2`
+    let c2 = -(z * inverse_or_zero(one + one));
+    // `c3 = sqrt(-g(Z) * (3 * Z^2 + 4 * A))` such that `sgn0(c3) = 0`
+    let c3 = (-(c1 * three_z2)).sqrt().ok_or(Error::HashToPointError)?;
+    let c3 = if sgn0(c3) { -c3 } else { c3 };
+    // `c4 = -4 * g(Z) / (3 * Z^2 + 4 * A)`
+    let c4 = -((one + one + one + one) * c1) * inverse_or_zero(three_z2);
+
+    let tv1 = u * u * c1;
+    let tv2 = one + tv1;
+    let tv1 = one - tv1;
+    let tv3 = inverse_or_zero(tv1 * tv2);
+    let tv4 = u * tv1 * tv3 * c3;
+
+    let x1 = c2 - tv4;
+    let x2 = c2 + tv4;
+    let x3 = tv2 * tv2 * tv3;
+    let x3 = x3 * x3 * c4 + z;
+
+    let x = if is_square(curve_equation(x1)) {
+        x1
+    } else if is_square(curve_equation(x2)) {
+        x2
+    } else {
+        x3
+    };
+    let y = curve_equation(x).sqrt().ok_or(Error::HashToPointError)?;
+    let y = if sgn0(u) == sgn0(y) { y } else { -y };
+
+    Ok(AffineG1::new(x, y)?.into())
+}
+
+/// Function to map a field element to a point in G1 using the Fouque–Tibouchi encoding.
+///
+/// The square root of `-3` is the one returned by `Fq::sqrt`, i.e. `(-3)^((p + 1) / 4)`.
+///
+/// # Arguments
+///
+/// * `t` - The field element to be mapped.
+///
+/// # Returns
+///
+/// * If successful, a point in the `G1` group.
+pub fn map_to_curve_fouque_tibouchi(t: Fq) -> Result<G1, Error> {
+    let one = Fq::one();
+    let sqrt_minus_three = (-(one + one + one)).sqrt().ok_or(Error::HashToPointError)?;
+    // Primitive cube root of unity `(-1 + sqrt(-3)) / 2`
+    let cube_root = (sqrt_minus_three - one) * inverse_or_zero(one + one);
+
+    // `f(0) = ((-1 + sqrt(-3)) / 2, sqrt(1 + b))`
+    if t.is_zero() {
+        let y = (one + G1::b()).sqrt().ok_or(Error::HashToPointError)?;
+        return Ok(AffineG1::new(cube_root, y)?.into());
+    }
+
+    // `w = sqrt(-3) * t / (1 + b + t^2)`
+    let w = sqrt_minus_three * t * inverse_or_zero(one + G1::b() + t * t);
+    let x1 = cube_root - t * w;
+    let x2 = -one - x1;
+    let x3 = one + inverse_or_zero(w * w);
+
+    let x = if is_square(curve_equation(x1)) {
+        x1
+    } else if is_square(curve_equation(x2)) {
+        x2
+    } else {
+        x3
+    };
+    // The sign of `y` is given by the Legendre symbol of `t`
+    let y = curve_equation(x).sqrt().ok_or(Error::HashToPointError)?;
+    let y = if is_square(t) { y } else { -y };
+
+    Ok(AffineG1::new(x, y)?.into())
+}
+
+/// Function to hash an arbitrary message to a point in G1 as mcl does for bn256, mapping a single
+/// field element with the Fouque–Tibouchi encoding (see `map_to_curve_mcl`).
+///
+/// # Arguments
+///
+/// * `message` - A slice containing the message to be hashed.
+///
+/// # Returns
+///
+/// * If successful, a point in the `G1` group representing the hashed point.
+pub fn hash_to_curve_mcl(message: &[u8]) -> Result<G1, Error> {
+    // `Fp::setHashOf`: little endian digest masked to 253 bits, hence lower than `p`
+    let mut digest = Sha256::digest(message);
+    digest[31] &= 0x1f;
+    digest.reverse();
+
+    map_to_curve_mcl(Fq::from_slice(&digest)?)
+}
+
+/// Function to map a field element to a point in G1 with the Fouque–Tibouchi encoding as mcl
+/// does (`MapTo::calcBN`), which differs from `map_to_curve_fouque_tibouchi` by rejecting `t = 0`.
+///
+/// # Arguments
+///
+/// * `t` - The field element to be mapped.
+///
+/// # Returns
+///
+/// * If successful, a point in the `G1` group; otherwise `Error::HashToPointError` for `t = 0`.
+pub fn map_to_curve_mcl(t: Fq) -> Result<G1, Error> {
+    if t.is_zero() {
+        return Err(Error::HashToPointError);
+    }
+
+    map_to_curve_fouque_tibouchi(t)
+}
+
+/// Function to evaluate the curve equation `g(x) = x^3 + B` (`A = 0` for bn256).
+fn curve_equation(x: Fq) -> Fq {
+    x * x * x + G1::b()
+}
+
+/// Function to compute the multiplicative inverse of an element, returning zero for zero.
+fn inverse_or_zero(x: Fq) -> Fq {
+    x.inverse().unwrap_or_else(Fq::zero)
+}
+
+/// Function to check whether an element is a square in `Fq`.
+fn is_square(x: Fq) -> bool {
+    x.is_zero() || x.sqrt().is_some()
+}
+
+/// Function to get the sign of an element as defined by the draft (parity of its integer value).
+pub(super) fn sgn0(x: Fq) -> bool {
+    x.into_u256().get_bit(0).unwrap_or(false)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloc::string::String;
+
+    /// Function to serialize the affine coordinates of a G1 point as a hex string `x || y`.
+    fn to_hex_coordinates(point: G1) -> String {
+        let affine = AffineG1::from_jacobian(point).unwrap();
+        let mut bytes = [0u8; 64];
+        affine
+            .x()
+            .into_u256()
+            .to_big_endian(&mut bytes[0..32])
+            .unwrap();
+        affine
+            .y()
+            .into_u256()
+            .to_big_endian(&mut bytes[32..64])
+            .unwrap();
+
+        hex::encode(&bytes[..])
+    }
+
+    /// Test `hash_to_curve` with the `BN254G1_XMD:SHA-256_SVDW_RO_` test vectors
+    #[test]
+    fn test_hash_to_curve() {
+        let dst = b"QUUX-V01-CS02-with-BN254G1_XMD:SHA-256_SVDW_RO_";
+
+        let point = hash_to_curve(b"", dst).unwrap();
+        assert_eq!(
+            to_hex_coordinates(point),
+            "0a976ab906170db1f9638d376514dbf8c42aef256a54bbd48521f20749e59e86\
+             02925ead66b9e68bfc309b014398640ab55f6619ab59bc1fab2210ad4c4d53d5"
+        );
+
+        let point = hash_to_curve(b"abc", dst).unwrap();
+        assert_eq!(
+            to_hex_coordinates(point),
+            "23f717bee89b1003957139f193e6be7da1df5f1374b26a4643b0378b5baf53d1\
+             04142f826b71ee574452dbc47e05bc3e1a647478403a7ba38b7b93948f4e151d"
+        );
+    }
+
+    /// Test the Fouque–Tibouchi map, including the special case `t = 0`
+    #[test]
+    fn test_map_to_curve_fouque_tibouchi() {
+        let point = map_to_curve_fouque_tibouchi(Fq::zero()).unwrap();
+        assert_eq!(
+            to_hex_coordinates(point),
+            "000000000000000059e26bcea0d48bacd4f263f1acdb5c4f5763473177fffffe\
+             0000000000000000000000000000000000000000000000000000000000000002"
+        );
+
+        let point = map_to_curve_fouque_tibouchi(Fq::one()).unwrap();
+        assert_eq!(
+            to_hex_coordinates(point),
+            "09ada94a2d09ecd55acb1b6d4732ff1404781e147c9a5fe573a879ef0ce5cc40\
+             0c13c679211acf4c754446808b9d61256aead7d728ca2215a354acd190f58771"
+        );
+    }
+
+    /// Test `hash_to_curve_with_map` using the Fouque–Tibouchi map
+    #[test]
+    fn test_hash_to_curve_fouque_tibouchi() {
+        let dst = b"BN254G1_XMD:SHA-256_FT_RO_TESTGEN";
+        let point = hash_to_curve_with_map(b"abc", dst, MapToCurve::FouqueTibouchi).unwrap();
+        assert_eq!(
+            to_hex_coordinates(point),
+            "181fcb30e1eba2994c0aeddb583f507a43f4ab48ba91af277f682c453c2ba944\
+             28b5ae75ad888f24c3cd86449ebab7681466c6f8faf2cb3aac63c94dccedff7d"
+        );
+    }
+
+    /// Test the mcl compatible single element path, including the rejection of `t = 0`
+    #[test]
+    fn test_hash_to_curve_mcl() {
+        match map_to_curve_mcl(Fq::zero()) {
+            Err(Error::HashToPointError) => {}
+            _ => panic!("Expected HashToPointError error"),
+        }
+        assert_eq!(
+            map_to_curve_mcl(Fq::one()).unwrap(),
+            map_to_curve_fouque_tibouchi(Fq::one()).unwrap()
+        );
+
+        // `t` is `SHA-256("abc")` as a little endian integer masked to 253 bits
+        let t = Fq::from_slice(
+            &hex::decode("0d1500f261ff10b49c7a1796a36103b02322ae5dde404141eacf018fbf1678ba")
+                .unwrap(),
+        )
+        .unwrap();
+        let point = hash_to_curve_mcl(b"abc").unwrap();
+        assert_eq!(point, map_to_curve_mcl(t).unwrap());
+        assert_eq!(
+            to_hex_coordinates(point),
+            "048aeeec30d3e53021ae7233ef9dd848b99800e6a7b2f34b38723652e8f5d8b7\
+             27ce37e30c6be3c3bad44f9be911203479db787a82062225ac7d4920ece54c28"
+        );
+    }
+}
@@ -0,0 +1,167 @@
+//! Hashing to the fields of bn256 as specified in the [hash-to-curve draft](https://tools.ietf.org/html/draft-irtf-cfrg-hash-to-curve) (section 5.3).
+//!
+//! The message is expanded with `expand_message_xmd` and every `L = 48` bytes are interpreted as
+//! a big endian integer reduced modulo the field characteristic. Elements of `Fq2` consume `2 * L`
+//! bytes, the first half being the real coefficient.
+//!
+//! Besides being used by `hash_to_curve`, these functions can be used to derive challenges or
+//! scalars (`Fr`) from arbitrary data.
+use alloc::vec::Vec;
+use bn::{arith, Fq, Fq2, Fr};
+use digest::{BlockInput, Digest};
+
+use super::error::Error;
+use super::expand_message::expand_message_xmd;
+
+/// Number of bytes hashed for each coordinate: `ceil((ceil(log2(p)) + k) / 8)` with `k = 128`.
+const L: usize = 48;
+
+/// Fields whose elements can be obtained from uniformly random bytes.
+pub trait FromUniformBytes: Sized {
+    /// Number of uniform bytes consumed for each element.
+    const UNIFORM_BYTES: usize;
+
+    /// Function to create an element from `UNIFORM_BYTES` uniformly random bytes.
+    fn from_uniform_bytes(bytes: &[u8]) -> Result<Self, Error>;
+}
+
+impl FromUniformBytes for Fq {
+    const UNIFORM_BYTES: usize = L;
+
+    fn from_uniform_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let (_, reduced) = arith::U512::from_slice(&widen(bytes)?)?.divrem(&Fq::modulus());
+        let mut reduced_bytes = [0u8; 32];
+        reduced.to_big_endian(&mut reduced_bytes)?;
+
+        Ok(Fq::from_slice(&reduced_bytes)?)
+    }
+}
+
+impl FromUniformBytes for Fq2 {
+    const UNIFORM_BYTES: usize = 2 * L;
+
+    fn from_uniform_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != Self::UNIFORM_BYTES {
+            return Err(Error::InvalidLength);
+        }
+        let real = Fq::from_uniform_bytes(&bytes[..L])?;
+        let imaginary = Fq::from_uniform_bytes(&bytes[L..])?;
+
+        Ok(Fq2::new(real, imaginary))
+    }
+}
+
+impl FromUniformBytes for Fr {
+    const UNIFORM_BYTES: usize = L;
+
+    fn from_uniform_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Fr::interpret(&widen(bytes)?))
+    }
+}
+
+/// Function to left pad `L` big endian bytes to 64 bytes.
+fn widen(bytes: &[u8]) -> Result<[u8; 64], Error> {
+    if bytes.len() != L {
+        return Err(Error::InvalidLength);
+    }
+    let mut wide = [0u8; 64];
+    wide[64 - L..].copy_from_slice(bytes);
+
+    Ok(wide)
+}
+
+/// Function to hash an arbitrary message to `count` field elements.
+///
+/// # Arguments
+///
+/// * `message` - A slice containing the message to be hashed.
+/// * `dst`     - The domain separation tag.
+/// * `count`   - The number of field elements to output.
+///
+/// # Returns
+///
+/// * If successful, a vector with `count` field elements.
+pub fn hash_to_field<F: FromUniformBytes, D: Digest + BlockInput>(
+    message: &[u8],
+    dst: &[u8],
+    count: usize,
+) -> Result<Vec<F>, Error> {
+    let length = count
+        .checked_mul(F::UNIFORM_BYTES)
+        .ok_or(Error::InvalidExpandLength)?;
+    let uniform_bytes = expand_message_xmd::<D>(message, dst, length)?;
+
+    uniform_bytes
+        .chunks(F::UNIFORM_BYTES)
+        .map(F::from_uniform_bytes)
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sha2::Sha256;
+
+    /// Function to serialize an `Fq` element as a hex string.
+    fn to_hex(element: Fq) -> alloc::string::String {
+        let mut bytes = [0u8; 32];
+        element.into_u256().to_big_endian(&mut bytes).unwrap();
+
+        hex::encode(bytes)
+    }
+
+    /// Test `hash_to_field` for `Fq` with the `BN254G1_XMD:SHA-256_SVDW_RO_` suite parameters
+    #[test]
+    fn test_hash_to_field_fq() {
+        let dst = b"QUUX-V01-CS02-with-BN254G1_XMD:SHA-256_SVDW_RO_";
+        let u = hash_to_field::<Fq, Sha256>(b"abc", dst, 2).unwrap();
+
+        assert_eq!(
+            to_hex(u[0]),
+            "11945105b5e3d3b9392b5a2318409cbc28b7246aa47fa30da5739907737799a9"
+        );
+        assert_eq!(
+            to_hex(u[1]),
+            "1255fc9ad5a6e0fb440916f091229bda611c41be2f2283c3d8f98c596be4c8c9"
+        );
+    }
+
+    /// Test `hash_to_field` for `Fq2`
+    #[test]
+    fn test_hash_to_field_fq2() {
+        let u = hash_to_field::<Fq2, Sha256>(b"abc", b"TEST-FQ2", 1).unwrap();
+
+        assert_eq!(
+            to_hex(u[0].real()),
+            "26b3f1259dba9091f5284d7581e9599de78755388b86deb985bf72af51e23861"
+        );
+        assert_eq!(
+            to_hex(u[0].imaginary()),
+            "1b13b1b9f0828325a941bb4e5e8ee67952e22d32f3ea22600d7af360d9c574c0"
+        );
+    }
+
+    /// Test `hash_to_field` for `Fr`
+    #[test]
+    fn test_hash_to_field_fr() {
+        let u = hash_to_field::<Fr, Sha256>(b"abc", b"TEST-FR", 1).unwrap();
+        let mut bytes = [0u8; 32];
+        u[0].into_u256().to_big_endian(&mut bytes).unwrap();
+
+        assert_eq!(
+            hex::encode(bytes),
+            "24f176053e107eaa82d4d2ec9d9a29a822553591479e7842937454d66ab0e282"
+        );
+    }
+
+    /// Test that `hash_to_field` rejects counts whose output length overflows
+    #[test]
+    fn test_hash_to_field_overflow() {
+        for count in [usize::MAX / 2, usize::MAX].iter() {
+            match hash_to_field::<Fq, Sha256>(b"abc", b"TEST-FQ", *count) {
+                Err(Error::InvalidExpandLength) => {}
+                _ => panic!("Expected InvalidExpandLength error"),
+            }
+        }
+    }
+}
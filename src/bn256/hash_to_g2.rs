@@ -0,0 +1,217 @@
+//! Hashing to G2 following the [hash-to-curve draft](https://tools.ietf.org/html/draft-irtf-cfrg-hash-to-curve).
+//!
+//! This module hashes messages to the twisted curve `y^2 = x^3 + 3 / (9 + i)` over `Fq2` with:
+//!
+//! - `hash_to_field::hash_to_field` over `Fq2` with `expand_message_xmd` and SHA-256
+//! - the Shallue–van de Woestijne map with `Z = 1`
+//! - the random oracle construction `map_to_curve_g2(u0) + map_to_curve_g2(u1)`
+//! - cofactor clearing by multiplying with `h = 2p - r`
+//!
+//! Contrary to G1, the twist has a cofactor different from 1, so the mapped points need to be
+//! multiplied by the cofactor in order to land in the prime order subgroup. The result of
+//! `hash_to_curve_g2` is also checked to be in the subgroup before being returned.
+//!
+//! The draft defines no suite for the G2 group of BN254, so the choices above (in particular
+//! `Z = 1` and the cofactor clearing) are specific to this crate. Points are only guaranteed to
+//! match the ones hashed by this crate, not by other BN254 libraries, and the test vector is
+//! generated by this implementation.
+use bn::{arith, Fq, Fq2, Fr, Group, G2};
+use sha2::Sha256;
+
+use super::error::Error;
+use super::hash_to_curve::sgn0 as sgn0_fq;
+use super::hash_to_field::hash_to_field;
+
+/// Cofactor of the G2 twist: `h = 2p - r` (big endian).
+const COFACTOR: [u8; 32] = [
+    0x30, 0x64, 0x4e, 0x72, 0xe1, 0x31, 0xa0, 0x29, 0xb8, 0x50, 0x45, 0xb6, 0x81, 0x81, 0x58, 0x5e,
+    0x06, 0xce, 0xec, 0xda, 0x57, 0x2a, 0x24, 0x89, 0x34, 0x5f, 0x22, 0x99, 0xc0, 0xf9, 0xfa, 0x8d,
+];
+
+/// Function to hash an arbitrary message to a point in G2.
+///
+/// # Arguments
+///
+/// * `message` - A slice containing the message to be hashed.
+/// * `dst`     - The domain separation tag.
+///
+/// # Returns
+///
+/// * If successful, a point in the prime order subgroup of `G2` representing the hashed point.
+pub fn hash_to_curve_g2(message: &[u8], dst: &[u8]) -> Result<G2, Error> {
+    let u = hash_to_field::<Fq2, Sha256>(message, dst, 2)?;
+    let q0 = map_to_curve_g2(u[0])?;
+    let q1 = map_to_curve_g2(u[1])?;
+    let point = clear_cofactor_g2(q0 + q1)?;
+
+    if is_in_subgroup_g2(point) {
+        Ok(point)
+    } else {
+        Err(Error::InvalidGroupPoint)
+    }
+}
+
+/// Function to map a field element to a point in the twisted curve using the Shallue–van de
+/// Woestijne method.
+///
+/// The resulting point is not necessarily in the prime order subgroup, see `clear_cofactor_g2`.
+///
+/// # Arguments
+///
+/// * `u` - The field element to be mapped.
+///
+/// # Returns
+///
+/// * If successful, a point in the twisted curve.
+pub fn map_to_curve_g2(u: Fq2) -> Result<G2, Error> {
+    let one = Fq2::one();
+    let z = one;
+    let three_z2 = (one + one + one) * z * z;
+
+    // `c1 = g(Z)`
+    let c1 = curve_equation(z);
+    // `c2 = -Z / 2`
+    let c2 = -(z * inverse_or_zero(one + one));
+    // `c3 = sqrt(-g(Z) * (3 * Z^2 + 4 * A))` such that `sgn0(c3) = 0`
+    let c3 = (-(c1 * three_z2)).sqrt().ok_or(Error::HashToPointError)?;
+    let c3 = if sgn0(c3) { -c3 } else { c3 };
+    // `c4 = -4 * g(Z) / (3 * Z^2 + 4 * A)`
+    let c4 = -((one + one + one + one) * c1) * inverse_or_zero(three_z2);
+
+    let tv1 = u * u * c1;
+    let tv2 = one + tv1;
+    let tv1 = one - tv1;
+    let tv3 = inverse_or_zero(tv1 * tv2);
+    let tv4 = u * tv1 * tv3 * c3;
+
+    let x1 = c2 - tv4;
+    let x2 = c2 + tv4;
+    let x3 = tv2 * tv2 * tv3;
+    let x3 = x3 * x3 * c4 + z;
+
+    let x = if is_square(curve_equation(x1)) {
+        x1
+    } else if is_square(curve_equation(x2)) {
+        x2
+    } else {
+        x3
+    };
+    let y = curve_equation(x).sqrt().ok_or(Error::HashToPointError)?;
+    let y = if sgn0(u) == sgn0(y) { y } else { -y };
+
+    // The point is built in projective coordinates, as `AffineG2` rejects points outside the subgroup
+    Ok(G2::new(x, y, one))
+}
+
+/// Function to multiply a point in the twisted curve by the cofactor `h = 2p - r`.
+///
+/// # Arguments
+///
+/// * `point` - A point in the twisted curve.
+///
+/// # Returns
+///
+/// * If successful, a point in the prime order subgroup of `G2`.
+pub fn clear_cofactor_g2(point: G2) -> Result<G2, Error> {
+    let cofactor = arith::U256::from_slice(&COFACTOR)?;
+
+    // Double and add, as the cofactor is larger than the group order
+    let mut result = G2::zero();
+    for i in (0..256).rev() {
+        result = result + result;
+        if cofactor.get_bit(i).ok_or(Error::IndexOutOfBounds)? {
+            result = result + point;
+        }
+    }
+
+    Ok(result)
+}
+
+/// Function to check whether a point belongs to the prime order subgroup of `G2` (`r * P = 0`).
+///
+/// # Arguments
+///
+/// * `point` - A point in the twisted curve.
+///
+/// # Returns
+///
+/// * `true` if the point is in the subgroup, `false` otherwise.
+pub fn is_in_subgroup_g2(point: G2) -> bool {
+    // `(r - 1) * P + P = r * P`
+    (point * (-Fr::one()) + point).is_zero()
+}
+
+/// Function to evaluate the twisted curve equation `g(x) = x^3 + B`.
+fn curve_equation(x: Fq2) -> Fq2 {
+    x * x * x + G2::b()
+}
+
+/// Function to compute the multiplicative inverse of an element, returning zero for zero.
+fn inverse_or_zero(x: Fq2) -> Fq2 {
+    // `1 / (a + bi) = (a - bi) / (a^2 + b^2)`
+    let norm = x.real() * x.real() + x.imaginary() * x.imaginary();
+    let norm_inverse = norm.inverse().unwrap_or_else(Fq::zero);
+
+    Fq2::new(x.real() * norm_inverse, -(x.imaginary() * norm_inverse))
+}
+
+/// Function to check whether an element is a square in `Fq2`.
+fn is_square(x: Fq2) -> bool {
+    x.is_zero() || x.sqrt().is_some()
+}
+
+/// Function to get the sign of an element as defined by the draft for extension fields of degree 2.
+fn sgn0(x: Fq2) -> bool {
+    sgn0_fq(x.real()) || (x.real().is_zero() && sgn0_fq(x.imaginary()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bn::AffineG2;
+
+    /// Test `hash_to_curve_g2` with own test vector (no external vector exists for this map)
+    #[test]
+    fn test_hash_to_curve_g2() {
+        let dst = b"BN254G2_XMD:SHA-256_SVDW_RO_TESTGEN";
+        let point = hash_to_curve_g2(b"abc", dst).unwrap();
+
+        let affine = AffineG2::from_jacobian(point).unwrap();
+        let mut bytes = [0u8; 128];
+        let coordinates = [
+            affine.x().real(),
+            affine.x().imaginary(),
+            affine.y().real(),
+            affine.y().imaginary(),
+        ];
+        for (coordinate, chunk) in coordinates.iter().zip(bytes.chunks_mut(32)) {
+            coordinate.into_u256().to_big_endian(chunk).unwrap();
+        }
+
+        assert_eq!(
+            hex::encode(&bytes[..]),
+            "12183535aa507d670f462db9201bc115c6bdc914bf25621e0cd1f1576249f4dc\
+             2998a42b1ce9a5f23b2b342c88bc183c7696b68dc01d144c16312a17e81d26f5\
+             2670d6bd0c4c766c68cd70f2efa3a8949de896c6bffe8ba561382639f598a718\
+             1737a909018be69a11e274362734bcf6a272d178848791ad73d3d66217a1faad"
+        );
+    }
+
+    /// Test that mapped points need cofactor clearing to be in the subgroup
+    #[test]
+    fn test_clear_cofactor_g2() {
+        let point = map_to_curve_g2(Fq2::one()).unwrap();
+        assert!(!is_in_subgroup_g2(point));
+
+        let cleared = clear_cofactor_g2(point).unwrap();
+        assert!(is_in_subgroup_g2(cleared));
+        assert!(!cleared.is_zero());
+    }
+
+    /// Test the subgroup check with the generator of G2
+    #[test]
+    fn test_is_in_subgroup_g2() {
+        assert!(is_in_subgroup_g2(G2::one()));
+        assert!(is_in_subgroup_g2(G2::zero()));
+    }
+}
@@ -0,0 +1,410 @@
+//! Boneh–Franklin identity-based encryption ([FullIdent](https://crypto.stanford.edu/~dabo/papers/bfibe.pdf), section 4.2).
+//!
+//! The master key pair is a regular BLS key pair `(s, P = s * G2)`, and the private key of an
+//! identity `id` is `d = s * Q` with `Q = H1(id)` hashed to G1, i.e. the BLS signature of the
+//! identity. Given a random `sigma`, the ciphertext of a message `m` is:
+//!
+//! - `U = r * G2`, with `r = H3(sigma || m)`
+//! - `V = sigma XOR H2(e(Q, P)^r)`
+//! - `W = m XOR H4(sigma)`
+//!
+//! `H2` hashes the 12 coefficients in Fq of the pairing, as canonical 32-byte big endian integers
+//! in the order of the tower `Fq12 = Fq6[w]`, `Fq6 = Fq2[v]`, `Fq2 = Fq[i]` (see `gt_to_bytes`).
+//!
+//! Decryption recovers `e(Q, P)^r = e(d, U)` with the private key of the identity, and checks that
+//! `U` was built from the recovered `sigma` and `m`, rejecting any tampered ciphertext.
+//!
+//! As private keys are BLS signatures, they can also be extracted by a committee holding a
+//! threshold master key (see `threshold`): every member extracts with its key share, and any `t`
+//! partial keys are combined with `threshold::combine`. The `tlock` module builds on this scheme
+//! with beacon signatures as private keys.
+use ::core::mem;
+use alloc::vec::Vec;
+use bn::{pairing, Fq, Fr, Group, Gt, G1, G2};
+use sha2::Sha256;
+use subtle::ConstantTimeEq;
+
+use super::error::Error;
+use super::expand_message::expand_message_xmd;
+use super::hash_to_curve::hash_to_curve;
+use super::hash_to_field::hash_to_field;
+use super::{
+    core, g1_from_compressed, g1_to_compressed, g2_from_compressed, PrivateKey, PublicKey,
+};
+
+/// Domain separation tag used for hashing identities to G1 (`H1`).
+pub const IBE_DST: &[u8] = b"BLS_IBE_BN254G1_XMD:SHA-256_SVDW_RO_";
+
+/// Domain separation tag used for masking `sigma` with the pairing (`H2`).
+const IBE_H2_DST: &[u8] = b"BLS_IBE_H2_BN254_XMD:SHA-256_";
+
+/// Domain separation tag used for deriving `r` from `sigma` and the message (`H3`).
+const IBE_H3_DST: &[u8] = b"BLS_IBE_H3_BN254_XMD:SHA-256_";
+
+/// Domain separation tag used for masking the message with `sigma` (`H4`).
+const IBE_H4_DST: &[u8] = b"BLS_IBE_H4_BN254_XMD:SHA-256_";
+
+/// Length of the random `sigma` in bytes.
+pub const SIGMA_LENGTH: usize = 32;
+
+/// Length of a compressed point in G2 in bytes.
+const U_LENGTH: usize = 65;
+
+/// A message encrypted to an identity.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ciphertext {
+    /// Compressed point `U = r * G2`
+    pub u: Vec<u8>,
+    /// Masked `sigma`
+    pub v: Vec<u8>,
+    /// Masked message
+    pub w: Vec<u8>,
+}
+
+impl Ciphertext {
+    /// Function to serialize the ciphertext as `U` (65 bytes), `V` (32 bytes) and `W` (as long as
+    /// the message).
+    ///
+    /// # Returns
+    ///
+    /// * A vector of bytes with the serialized ciphertext
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&self.u[..], &self.v, &self.w].concat()
+    }
+
+    /// Function to deserialize a ciphertext encoded with `to_bytes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized ciphertext
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `Ciphertext`; otherwise `Error`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let header = U_LENGTH + SIGMA_LENGTH;
+        if bytes.len() < header {
+            return Err(Error::InvalidLength);
+        }
+
+        Ok(Ciphertext {
+            u: bytes[..U_LENGTH].to_vec(),
+            v: bytes[U_LENGTH..header].to_vec(),
+            w: bytes[header..].to_vec(),
+        })
+    }
+}
+
+/// Function to derive the master public key from the master secret key (`Setup`).
+///
+/// # Arguments
+///
+/// * `master_secret_key` - The master secret key bytes, a canonical nonzero scalar (32 big endian
+///   bytes lower than the group order) such as the ones generated by `PrivateKey::random` or
+///   `keygen::key_gen`
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the master public key (a compressed point in G2);
+///   otherwise `Error` if the master secret key is not a canonical scalar
+pub fn setup(master_secret_key: &[u8]) -> Result<Vec<u8>, Error> {
+    PrivateKey::from_slice(master_secret_key)?
+        .derive_public_key()?
+        .to_compressed()
+}
+
+/// Function to extract the private key of an identity (`Extract`).
+///
+/// # Arguments
+///
+/// * `master_secret_key` - The master secret key bytes, a canonical nonzero scalar (see `setup`)
+/// * `identity`          - The identity bytes
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the private key (a compressed point in G1)
+pub fn extract(master_secret_key: &[u8], identity: &[u8]) -> Result<Vec<u8>, Error> {
+    let PrivateKey(secret) = PrivateKey::from_slice(master_secret_key)?;
+
+    g1_to_compressed(core::core_sign(secret, hash_to_curve(identity, IBE_DST)?))
+}
+
+/// Function to check the private key of an identity against the master public key.
+///
+/// # Arguments
+///
+/// * `master_public_key` - The master public key bytes
+/// * `identity`          - The identity bytes
+/// * `private_key`       - The private key bytes
+///
+/// # Returns
+///
+/// * If the private key is valid, `Ok(())`; otherwise `Error`
+pub fn verify_private_key(
+    master_public_key: &[u8],
+    identity: &[u8],
+    private_key: &[u8],
+) -> Result<(), Error> {
+    let PublicKey(master_public_key) = PublicKey::from_compressed(master_public_key)?;
+
+    core::core_verify(
+        master_public_key,
+        hash_to_curve(identity, IBE_DST)?,
+        g1_from_compressed(private_key)?,
+    )
+}
+
+/// Function to encrypt a message to an identity (`Encrypt`).
+///
+/// # Arguments
+///
+/// * `master_public_key` - The master public key bytes
+/// * `identity`          - The identity bytes
+/// * `message`           - The message bytes (up to 8160 bytes)
+/// * `sigma`             - 32 secret bytes chosen uniformly at random for every encryption
+///
+/// # Returns
+///
+/// * If successful, the `Ciphertext`; otherwise `Error`
+pub fn encrypt(
+    master_public_key: &[u8],
+    identity: &[u8],
+    message: &[u8],
+    sigma: &[u8],
+) -> Result<Ciphertext, Error> {
+    let PublicKey(master_public_key) = PublicKey::from_compressed(master_public_key)?;
+
+    encrypt_to_point(
+        master_public_key,
+        hash_to_curve(identity, IBE_DST)?,
+        message,
+        sigma,
+    )
+}
+
+/// Function to decrypt a ciphertext with the private key of its identity (`Decrypt`).
+///
+/// # Arguments
+///
+/// * `private_key` - The private key bytes of the identity
+/// * `ciphertext`  - The ciphertext
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the message; otherwise `Error::DecryptionFailed`
+pub fn decrypt(private_key: &[u8], ciphertext: &Ciphertext) -> Result<Vec<u8>, Error> {
+    if ciphertext.v.len() != SIGMA_LENGTH {
+        return Err(Error::InvalidLength);
+    }
+    let private_key = g1_from_compressed(private_key)?;
+    let u = g2_from_compressed(&ciphertext.u)?;
+
+    let sigma = xor(&ciphertext.v, &mask_sigma(pairing(private_key, u))?);
+    let message = xor(&ciphertext.w, &mask_message(&sigma, ciphertext.w.len())?);
+    // Fujisaki-Okamoto check: the ciphertext must have been built from sigma and the message
+    let expected = PublicKey(G2::one() * derive_r(&sigma, &message)?);
+    if !bool::from(expected.ct_eq(&PublicKey(u))) {
+        return Err(Error::DecryptionFailed);
+    }
+
+    Ok(message)
+}
+
+/// Function to encrypt a message to an identity already hashed to G1.
+pub(super) fn encrypt_to_point(
+    master_public_key: G2,
+    identity: G1,
+    message: &[u8],
+    sigma: &[u8],
+) -> Result<Ciphertext, Error> {
+    if sigma.len() != SIGMA_LENGTH {
+        return Err(Error::InvalidLength);
+    }
+
+    let r = derive_r(sigma, message)?;
+    let u = PublicKey(G2::one() * r).to_compressed()?;
+    let v = xor(
+        sigma,
+        &mask_sigma(pairing(identity * r, master_public_key))?,
+    );
+    let w = xor(message, &mask_message(sigma, message.len())?);
+
+    Ok(Ciphertext { u, v, w })
+}
+
+/// Function to derive the scalar `r = H3(sigma || message)`.
+fn derive_r(sigma: &[u8], message: &[u8]) -> Result<Fr, Error> {
+    let input = [sigma, message].concat();
+
+    Ok(hash_to_field::<Fr, Sha256>(&input, IBE_H3_DST, 1)?[0])
+}
+
+/// Function to compute the mask `H2(g)` of `sigma`.
+fn mask_sigma(element: Gt) -> Result<Vec<u8>, Error> {
+    expand_message_xmd::<Sha256>(&gt_to_bytes(element), IBE_H2_DST, SIGMA_LENGTH)
+}
+
+/// Function to compute the mask `H4(sigma)` of a message.
+fn mask_message(sigma: &[u8], length: usize) -> Result<Vec<u8>, Error> {
+    if length == 0 {
+        return Ok(Vec::new());
+    }
+
+    expand_message_xmd::<Sha256>(sigma, IBE_H4_DST, length)
+}
+
+/// Function to compute the XOR of two byte strings of the same length.
+fn xor(left: &[u8], right: &[u8]) -> Vec<u8> {
+    left.iter().zip(right).map(|(a, b)| a ^ b).collect()
+}
+
+/// Function to serialize an element of Gt as its 12 coefficients in Fq (32 big endian bytes each).
+///
+/// The coefficients are in the order of the tower `Fq12 = Fq6[w]`, `Fq6 = Fq2[v]`,
+/// `Fq2 = Fq[i]`, lowest degree first and with the real part of every `Fq2` before the imaginary
+/// one. They are reduced and taken out of the Montgomery form, so the encoding is unique and
+/// matches the usual serialization of `Fq12`.
+fn gt_to_bytes(element: Gt) -> Vec<u8> {
+    let mut bytes = vec![0u8; 12 * 32];
+    for (coefficient, buffer) in gt_coefficients(element).iter().zip(bytes.chunks_mut(32)) {
+        coefficient
+            .to_big_endian(buffer)
+            .expect("buffer has the length of an Fq");
+    }
+
+    bytes
+}
+
+/// Function to get the 12 coefficients in Fq of an element of Gt.
+///
+/// `bn` does not expose the coefficients of `Gt`, so they are read through its layout: `Gt`, its
+/// `Fq12`, `Fq6` and `Fq2` are `#[repr(C)]` structs of their coefficients, and the public `Fq` is a
+/// `#[repr(C)]` wrapper of the same Montgomery representation, so the `Fq` values are the
+/// coefficients. The `bn` revision is pinned in `Cargo.toml`, and `test_gt_coefficients` checks
+/// the layout against the field arithmetic of `bn`.
+fn gt_coefficients(element: Gt) -> [Fq; 12] {
+    // `Fq` is `Copy` without invalid bit patterns, and `transmute` checks that the sizes match
+    unsafe { mem::transmute::<Gt, [Fq; 12]>(element) }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use bn::Fq2;
+
+    /// Test the setup, extraction, encryption and decryption
+    #[test]
+    fn test_ibe() {
+        let master_secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let master_public_key = setup(&master_secret_key).unwrap();
+        let identity: &[u8] = b"alice@example.com";
+        let sigma = [7u8; SIGMA_LENGTH];
+        let message: &[u8] = b"identity-based message";
+
+        let private_key = extract(&master_secret_key, identity).unwrap();
+        assert!(verify_private_key(&master_public_key, identity, &private_key).is_ok());
+        assert!(verify_private_key(&master_public_key, b"bob@example.com", &private_key).is_err());
+
+        let ciphertext = encrypt(&master_public_key, identity, message, &sigma).unwrap();
+        assert_eq!(
+            Ciphertext::from_bytes(&ciphertext.to_bytes()).unwrap(),
+            ciphertext
+        );
+        assert_eq!(decrypt(&private_key, &ciphertext).unwrap(), message);
+
+        let other_key = extract(&master_secret_key, b"bob@example.com").unwrap();
+        match decrypt(&other_key, &ciphertext) {
+            Err(Error::DecryptionFailed) => {}
+            _ => panic!("Expected DecryptionFailed error"),
+        }
+        let mut tampered = ciphertext.clone();
+        tampered.v[0] ^= 1;
+        assert!(decrypt(&private_key, &tampered).is_err());
+    }
+
+    /// Test the message length limit of the H4 mask (255 blocks of SHA-256)
+    #[test]
+    fn test_ibe_message_length() {
+        let master_secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let master_public_key = setup(&master_secret_key).unwrap();
+        let identity: &[u8] = b"alice@example.com";
+        let sigma = [7u8; SIGMA_LENGTH];
+        let private_key = extract(&master_secret_key, identity).unwrap();
+
+        let message = vec![0x5a; 8160];
+        let ciphertext = encrypt(&master_public_key, identity, &message, &sigma).unwrap();
+        assert_eq!(decrypt(&private_key, &ciphertext).unwrap(), message);
+
+        match encrypt(&master_public_key, identity, &[0x5a; 8161], &sigma) {
+            Err(Error::InvalidExpandLength) => {}
+            _ => panic!("Expected InvalidExpandLength error"),
+        }
+    }
+
+    /// Function to multiply elements of Fq12 given as coefficients, in the tower of `bn`
+    /// (`i^2 = -1`, `v^3 = 9 + i` and `w^2 = v`).
+    fn fq12_mul(a: &[Fq; 12], b: &[Fq; 12]) -> [Fq; 12] {
+        let xi = Fq2::new(Fq::from_str("9").unwrap(), Fq::one());
+        let fq2 = |c: &[Fq; 12], k: usize| Fq2::new(c[2 * k], c[2 * k + 1]);
+        let fq6_mul = |a: [Fq2; 3], b: [Fq2; 3]| {
+            [
+                a[0] * b[0] + xi * (a[1] * b[2] + a[2] * b[1]),
+                a[0] * b[1] + a[1] * b[0] + xi * (a[2] * b[2]),
+                a[0] * b[2] + a[1] * b[1] + a[2] * b[0],
+            ]
+        };
+        let (a0, a1) = (
+            [fq2(a, 0), fq2(a, 1), fq2(a, 2)],
+            [fq2(a, 3), fq2(a, 4), fq2(a, 5)],
+        );
+        let (b0, b1) = (
+            [fq2(b, 0), fq2(b, 1), fq2(b, 2)],
+            [fq2(b, 3), fq2(b, 4), fq2(b, 5)],
+        );
+        let (low, high) = (fq6_mul(a0, b0), fq6_mul(a1, b1));
+        let (cross_0, cross_1) = (fq6_mul(a0, b1), fq6_mul(a1, b0));
+        // `w^2 = v`, so the high product is shifted by one power of `v`
+        let product = [
+            low[0] + xi * high[2],
+            low[1] + high[0],
+            low[2] + high[1],
+            cross_0[0] + cross_1[0],
+            cross_0[1] + cross_1[1],
+            cross_0[2] + cross_1[2],
+        ];
+
+        let mut result = [Fq::zero(); 12];
+        for (k, coefficient) in product.iter().enumerate() {
+            result[2 * k] = coefficient.real();
+            result[2 * k + 1] = coefficient.imaginary();
+        }
+        result
+    }
+
+    /// Test that the coefficients read from Gt follow the field arithmetic of `bn`
+    #[test]
+    fn test_gt_coefficients() {
+        let mut one = [Fq::zero(); 12];
+        one[0] = Fq::one();
+        assert!(gt_coefficients(Gt::one()) == one);
+
+        let g = pairing(G1::one(), G2::one());
+        let h = pairing(G1::one() + G1::one(), G2::one());
+        assert!(fq12_mul(&gt_coefficients(g), &gt_coefficients(h)) == gt_coefficients(g * h));
+
+        // Elements of Gt are unitary, so their inverse is their conjugate
+        let conjugate = gt_coefficients(g.inverse().unwrap());
+        let coefficients = gt_coefficients(g);
+        assert!(conjugate[..6] == coefficients[..6]);
+        for k in 6..12 {
+            assert!(conjugate[k] == -coefficients[k]);
+        }
+        let mut expected = [0u8; 12 * 32];
+        expected[31] = 1;
+        assert_eq!(gt_to_bytes(Gt::one()), expected.to_vec());
+    }
+}
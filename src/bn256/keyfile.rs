@@ -0,0 +1,347 @@
+//! Encrypted key files, a compact alternative to keystores for applications that only need to
+//! persist a secret key safely.
+//!
+//! The secret key is encrypted with AES-256-GCM under a key derived from the password with scrypt.
+//! A key file is made of a header followed by the ciphertext and its 16-byte tag:
+//!
+//! | Field      | Size (bytes) | Description                           |
+//! |------------|--------------|---------------------------------------|
+//! | magic      | 4            | `BLSK`                                |
+//! | version    | 1            | `1`                                   |
+//! | log_n      | 1            | scrypt cost parameter, `n = 2^log_n`  |
+//! | r          | 4            | scrypt block size (big endian)        |
+//! | p          | 4            | scrypt parallelization (big endian)   |
+//! | salt       | 32           | scrypt salt                           |
+//! | nonce      | 12           | AES-GCM nonce                         |
+//! | ciphertext | 48           | encrypted secret key and tag          |
+//!
+//! The header is authenticated as associated data, so tampering with any field makes decryption
+//! fail. As the scrypt parameters are read before the tag can be checked, their cost is bounded
+//! (`log_n <= 20`, `r <= 32` and `p <= 16`) so that a corrupted header cannot exhaust the memory.
+//! Files are written and read with `PrivateKey::save_encrypted` and `PrivateKey::load_encrypted`.
+use ::core::convert::TryFrom;
+use aes_gcm::aead::{Aead, NewAead, Payload};
+use aes_gcm::Aes256Gcm;
+use alloc::vec::Vec;
+use byteorder::{BigEndian, ByteOrder};
+use zeroize::Zeroizing;
+
+use super::error::Error;
+use super::PrivateKey;
+
+/// Magic bytes identifying key files.
+pub const MAGIC: &[u8] = b"BLSK";
+
+/// Version of the key file format.
+pub const VERSION: u8 = 1;
+
+/// Length of the scrypt salt in bytes.
+pub const SALT_LENGTH: usize = 32;
+
+/// Length of the AES-GCM nonce in bytes.
+pub const NONCE_LENGTH: usize = 12;
+
+/// Maximum logarithm of the scrypt CPU/memory cost accepted in key files.
+pub const MAX_LOG_N: u8 = 20;
+
+/// Maximum scrypt block size accepted in key files.
+pub const MAX_R: u32 = 32;
+
+/// Maximum scrypt parallelization accepted in key files.
+pub const MAX_P: u32 = 16;
+
+/// Length of the header in bytes.
+const HEADER_LENGTH: usize = 4 + 1 + 1 + 4 + 4 + SALT_LENGTH + NONCE_LENGTH;
+
+/// Length of the encrypted secret key and its tag in bytes.
+const CIPHERTEXT_LENGTH: usize = 32 + 16;
+
+/// Parameters of scrypt.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct KeyFileParams {
+    /// Logarithm of the CPU/memory cost
+    pub log_n: u8,
+    /// Block size
+    pub r: u32,
+    /// Parallelization
+    pub p: u32,
+}
+
+impl Default for KeyFileParams {
+    /// Interactive parameters (`n = 2^15`, `r = 8`, `p = 1`).
+    fn default() -> Self {
+        KeyFileParams {
+            log_n: 15,
+            r: 8,
+            p: 1,
+        }
+    }
+}
+
+/// Function to check that the scrypt parameters do not exceed the maximum cost accepted in key
+/// files, before deriving any key from them.
+///
+/// # Arguments
+///
+/// * `params` - The scrypt parameters
+///
+/// # Returns
+///
+/// * If the cost is bounded, `Ok(())`; otherwise `Error::InvalidKeyFile`
+pub fn check_params(params: KeyFileParams) -> Result<(), Error> {
+    if params.log_n > MAX_LOG_N || params.r > MAX_R || params.p > MAX_P {
+        return Err(Error::InvalidKeyFile);
+    }
+
+    Ok(())
+}
+
+/// Function to encrypt a secret key into the contents of a key file.
+///
+/// # Arguments
+///
+/// * `secret_key` - The secret key bytes
+/// * `password`   - The password
+/// * `params`     - The scrypt parameters, within the bounds of `check_params`
+/// * `salt`       - 32 bytes chosen uniformly at random for every file
+/// * `nonce`      - 12 bytes chosen uniformly at random for every file
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the key file; otherwise `Error`
+pub fn encrypt(
+    secret_key: &[u8],
+    password: &str,
+    params: KeyFileParams,
+    salt: &[u8],
+    nonce: &[u8],
+) -> Result<Vec<u8>, Error> {
+    if salt.len() != SALT_LENGTH || nonce.len() != NONCE_LENGTH {
+        return Err(Error::InvalidLength);
+    }
+    PrivateKey::from_slice(secret_key)?;
+    check_params(params)?;
+
+    let mut header = Vec::with_capacity(HEADER_LENGTH + CIPHERTEXT_LENGTH);
+    header.extend_from_slice(MAGIC);
+    header.push(VERSION);
+    header.push(params.log_n);
+    header.extend_from_slice(&params.r.to_be_bytes());
+    header.extend_from_slice(&params.p.to_be_bytes());
+    header.extend_from_slice(salt);
+    header.extend_from_slice(nonce);
+
+    let ciphertext = seal(password, params, salt, nonce, secret_key, &header)?;
+
+    Ok([header, ciphertext].concat())
+}
+
+/// Function to decrypt the secret key of a key file.
+///
+/// # Arguments
+///
+/// * `bytes`    - The contents of the key file
+/// * `password` - The password
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the secret key; otherwise `Error::InvalidKeyFile` if
+///   the header is malformed or its scrypt cost exceeds the bounds of `check_params`, or
+///   `Error::DecryptionFailed` if the password is wrong or the file has been tampered with
+pub fn decrypt(bytes: &[u8], password: &str) -> Result<Vec<u8>, Error> {
+    if bytes.len() != HEADER_LENGTH + CIPHERTEXT_LENGTH || &bytes[0..4] != MAGIC {
+        return Err(Error::InvalidKeyFile);
+    }
+    if bytes[4] != VERSION {
+        return Err(Error::InvalidKeyFile);
+    }
+    let params = KeyFileParams {
+        log_n: bytes[5],
+        r: BigEndian::read_u32(&bytes[6..10]),
+        p: BigEndian::read_u32(&bytes[10..14]),
+    };
+    check_params(params)?;
+    let salt = &bytes[14..14 + SALT_LENGTH];
+    let nonce = &bytes[14 + SALT_LENGTH..HEADER_LENGTH];
+    let (header, ciphertext) = bytes.split_at(HEADER_LENGTH);
+    let secret_key = Zeroizing::new(open(password, params, salt, nonce, ciphertext, header)?);
+    PrivateKey::from_slice(&secret_key)?;
+
+    Ok(secret_key.to_vec())
+}
+
+/// Function to encrypt and authenticate a plaintext, together with associated data, under a key
+/// derived from the password.
+///
+/// # Arguments
+///
+/// * `password`  - The password
+/// * `params`    - The scrypt parameters
+/// * `salt`      - The scrypt salt
+/// * `nonce`     - The AES-GCM nonce (12 bytes)
+/// * `plaintext` - The plaintext
+/// * `aad`       - The associated data
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the ciphertext followed by its tag; otherwise `Error`
+pub(super) fn seal(
+    password: &str,
+    params: KeyFileParams,
+    salt: &[u8],
+    nonce: &[u8],
+    plaintext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let cipher = derive_cipher(password, params, salt)?;
+    let nonce = <[u8; NONCE_LENGTH]>::try_from(nonce).map_err(|_| Error::InvalidLength)?;
+    let payload = Payload {
+        msg: plaintext,
+        aad,
+    };
+
+    cipher
+        .encrypt(&nonce.into(), payload)
+        .map_err(|_| Error::InvalidLength)
+}
+
+/// Function to decrypt a ciphertext encrypted with `seal`, checking its tag.
+///
+/// # Arguments
+///
+/// * `password`   - The password
+/// * `params`     - The scrypt parameters
+/// * `salt`       - The scrypt salt
+/// * `nonce`      - The AES-GCM nonce (12 bytes)
+/// * `ciphertext` - The ciphertext followed by its tag
+/// * `aad`        - The associated data
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the plaintext; otherwise `Error::DecryptionFailed` if
+///   the password is wrong or the ciphertext or associated data have been tampered with
+pub(super) fn open(
+    password: &str,
+    params: KeyFileParams,
+    salt: &[u8],
+    nonce: &[u8],
+    ciphertext: &[u8],
+    aad: &[u8],
+) -> Result<Vec<u8>, Error> {
+    let cipher = derive_cipher(password, params, salt)?;
+    let nonce = <[u8; NONCE_LENGTH]>::try_from(nonce).map_err(|_| Error::InvalidLength)?;
+    let payload = Payload {
+        msg: ciphertext,
+        aad,
+    };
+
+    cipher
+        .decrypt(&nonce.into(), payload)
+        .map_err(|_| Error::DecryptionFailed)
+}
+
+/// Function to derive the AES-256-GCM cipher from the password with scrypt.
+fn derive_cipher(password: &str, params: KeyFileParams, salt: &[u8]) -> Result<Aes256Gcm, Error> {
+    let scrypt_params = scrypt::ScryptParams::new(params.log_n, params.r, params.p)
+        .map_err(|_| Error::InvalidKeyFile)?;
+    let mut key = Zeroizing::new([0u8; 32]);
+    scrypt::scrypt(password.as_bytes(), salt, &scrypt_params, &mut *key)
+        .map_err(|_| Error::InvalidKeyFile)?;
+
+    Ok(Aes256Gcm::new(&(*key).into()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Test encrypting and decrypting key files
+    #[test]
+    fn test_encrypt_decrypt() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        // Cheap scrypt parameters to keep the test fast
+        let params = KeyFileParams {
+            log_n: 4,
+            r: 8,
+            p: 1,
+        };
+        let file = encrypt(
+            &secret_key,
+            "password",
+            params,
+            &[1u8; SALT_LENGTH],
+            &[2u8; NONCE_LENGTH],
+        )
+        .unwrap();
+        assert_eq!(&file[0..5], b"BLSK\x01");
+        assert_eq!(decrypt(&file, "password").unwrap(), secret_key);
+
+        match decrypt(&file, "wrong password") {
+            Err(Error::DecryptionFailed) => {}
+            _ => panic!("Expected DecryptionFailed error"),
+        }
+        // Authenticated header
+        let mut tampered = file.clone();
+        tampered[20] ^= 1;
+        assert!(decrypt(&tampered, "password").is_err());
+        // Unknown version
+        let mut tampered = file.clone();
+        tampered[4] = 2;
+        match decrypt(&tampered, "password") {
+            Err(Error::InvalidKeyFile) => {}
+            _ => panic!("Expected InvalidKeyFile error"),
+        }
+        assert!(decrypt(&file[1..], "password").is_err());
+    }
+
+    /// Test that headers with an excessive scrypt cost are rejected before deriving the key
+    #[test]
+    fn test_scrypt_cost_bounds() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let params = KeyFileParams {
+            log_n: 4,
+            r: 8,
+            p: 1,
+        };
+        let file = encrypt(
+            &secret_key,
+            "password",
+            params,
+            &[1u8; SALT_LENGTH],
+            &[2u8; NONCE_LENGTH],
+        )
+        .unwrap();
+
+        let mut tampered = file.clone();
+        tampered[5] = 60;
+        match decrypt(&tampered, "password") {
+            Err(Error::InvalidKeyFile) => {}
+            _ => panic!("Expected InvalidKeyFile error"),
+        }
+        let mut tampered = file;
+        tampered[6..10].copy_from_slice(&u32::MAX.to_be_bytes());
+        match decrypt(&tampered, "password") {
+            Err(Error::InvalidKeyFile) => {}
+            _ => panic!("Expected InvalidKeyFile error"),
+        }
+
+        let params = KeyFileParams {
+            log_n: 40,
+            ..params
+        };
+        assert!(check_params(params).is_err());
+        assert!(encrypt(
+            &secret_key,
+            "password",
+            params,
+            &[1u8; SALT_LENGTH],
+            &[2u8; NONCE_LENGTH],
+        )
+        .is_err());
+        assert!(check_params(KeyFileParams::default()).is_ok());
+    }
+}
@@ -0,0 +1,161 @@
+//! Deterministic key generation from input keying material (`KeyGen` of the
+//! [BLS IRTF draft](https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature-04), section 2.3).
+//!
+//! The secret key is derived with HKDF-SHA256 ([RFC 5869](https://tools.ietf.org/html/rfc5869)):
+//!
+//! 1. `salt = SHA-256(salt)`, starting from `salt = "BLS-SIG-KEYGEN-SALT-"`
+//! 2. `PRK = HKDF-Extract(salt, IKM || I2OSP(0, 1))`
+//! 3. `OKM = HKDF-Expand(PRK, key_info || I2OSP(48, 2), 48)`
+//! 4. `SK = OS2IP(OKM) mod r`, repeating from step 1 while `SK` is zero
+//!
+//! Keys derived from the same seed therefore match those of other conformant implementations
+//! using the same group order.
+use alloc::vec::Vec;
+use bn::Fr;
+use sha2::{Digest, Sha256};
+use zeroize::{Zeroize, Zeroizing};
+
+use super::error::Error;
+use super::hash_to_field::FromUniformBytes;
+use super::PrivateKey;
+
+/// Initial salt of the key derivation.
+const KEYGEN_SALT: &[u8] = b"BLS-SIG-KEYGEN-SALT-";
+
+/// Minimum length of the input keying material in bytes.
+pub const MIN_IKM_LENGTH: usize = 32;
+
+/// Number of bytes of output keying material reduced into a secret key, `ceil(3 * ceil(log2(r)) / 16)`.
+const OKM_LENGTH: usize = 48;
+
+/// Block size of SHA-256 in bytes.
+const BLOCK_LENGTH: usize = 64;
+
+/// Length of a SHA-256 digest in bytes.
+const HASH_LENGTH: usize = 32;
+
+/// Function to derive a secret key from input keying material (`KeyGen`).
+///
+/// # Arguments
+///
+/// * `ikm`      - The secret input keying material, at least 32 bytes
+/// * `key_info` - Optional bytes binding the key to an application (may be empty)
+///
+/// # Returns
+///
+/// * If successful, a vector of 32 big endian bytes with the secret key; otherwise `Error`
+pub fn key_gen(ikm: &[u8], key_info: &[u8]) -> Result<Vec<u8>, Error> {
+    if ikm.len() < MIN_IKM_LENGTH {
+        return Err(Error::InvalidLength);
+    }
+
+    let ikm = Zeroizing::new([ikm, &[0u8]].concat());
+    let info = [key_info, &(OKM_LENGTH as u16).to_be_bytes()].concat();
+    let mut salt = KEYGEN_SALT.to_vec();
+    loop {
+        salt = Sha256::digest(&salt).to_vec();
+        let prk = Zeroizing::new(hkdf_extract(&salt, &ikm));
+        let okm = Zeroizing::new(hkdf_expand(&prk, &info, OKM_LENGTH));
+        let secret_key = PrivateKey(Fr::from_uniform_bytes(&okm)?);
+        if !secret_key.0.is_zero() {
+            return secret_key.to_bytes();
+        }
+    }
+}
+
+/// Function to compute the pseudorandom key `HKDF-Extract(salt, ikm)`.
+pub(super) fn hkdf_extract(salt: &[u8], ikm: &[u8]) -> Vec<u8> {
+    hmac_sha256(salt, &[ikm])
+}
+
+/// Function to compute `length` bytes of output keying material `HKDF-Expand(prk, info, length)`,
+/// with `length` up to `255 * 32`.
+pub(super) fn hkdf_expand(prk: &[u8], info: &[u8], length: usize) -> Vec<u8> {
+    let mut okm = Vec::with_capacity(length + HASH_LENGTH);
+    let mut block = Zeroizing::new(Vec::new());
+    for counter in 1..=255u8 {
+        if okm.len() >= length {
+            break;
+        }
+        let next = Zeroizing::new(hmac_sha256(prk, &[&block, info, &[counter]]));
+        okm.extend_from_slice(&next);
+        block = next;
+    }
+    okm.truncate(length);
+
+    okm
+}
+
+/// Function to compute the HMAC-SHA256 of the concatenation of `chunks` under `key`.
+fn hmac_sha256(key: &[u8], chunks: &[&[u8]]) -> Vec<u8> {
+    let mut padded_key = [0u8; BLOCK_LENGTH];
+    if key.len() > BLOCK_LENGTH {
+        padded_key[..HASH_LENGTH].copy_from_slice(&Sha256::digest(key));
+    } else {
+        padded_key[..key.len()].copy_from_slice(key);
+    }
+
+    let inner_key = Zeroizing::new(padded_key.iter().map(|b| b ^ 0x36).collect::<Vec<u8>>());
+    let outer_key = Zeroizing::new(padded_key.iter().map(|b| b ^ 0x5c).collect::<Vec<u8>>());
+    padded_key.zeroize();
+
+    let mut inner = Sha256::new();
+    inner.input(&*inner_key);
+    for chunk in chunks {
+        inner.input(chunk);
+    }
+    let mut outer = Sha256::new();
+    outer.input(&*outer_key);
+    outer.input(inner.result());
+
+    outer.result().to_vec()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::Bn256;
+    use crate::MultiSignature;
+
+    /// Test HKDF with the test case 1 of RFC 5869
+    #[test]
+    fn test_hkdf() {
+        let ikm = [0x0b; 22];
+        let salt = hex::decode("000102030405060708090a0b0c").unwrap();
+        let info = hex::decode("f0f1f2f3f4f5f6f7f8f9").unwrap();
+
+        let prk = hkdf_extract(&salt, &ikm);
+        assert_eq!(
+            hex::encode(&prk),
+            "077709362c2e32df0ddc3f0dc47bba6390b6c73bb50f9c3122ec844ad7c2b3e5"
+        );
+        assert_eq!(
+            hex::encode(hkdf_expand(&prk, &info, 42)),
+            "3cb25f25faacd57a90434f64d0362f2a2d2d0a90cf1a5a4c5db02d56ecc4c5bf34007208d5b887185865"
+        );
+    }
+
+    /// Test deriving secret keys from input keying material
+    #[test]
+    fn test_key_gen() {
+        let ikm: Vec<u8> = (0u8..32).collect();
+        let secret_key = key_gen(&ikm, b"").unwrap();
+        assert_eq!(
+            hex::encode(&secret_key),
+            "23845b11cf32907fcf48263ad517aabff0c1033fec8814210dc941d3ba154271"
+        );
+        assert_eq!(
+            hex::encode(key_gen(&ikm, b"validator 1").unwrap()),
+            "24bcc41c322922f89da600aa35f10e8acdfdcffac5917520a2cd4f41fe226e4c"
+        );
+        match key_gen(&ikm[..31], b"") {
+            Err(Error::InvalidLength) => {}
+            _ => panic!("Expected InvalidLength error"),
+        }
+
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+        assert!(bn256.verify(&signature, b"sample", &public_key).is_ok());
+    }
+}
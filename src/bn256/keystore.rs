@@ -0,0 +1,527 @@
+//! Password-protected keystores following [EIP-2335](https://eips.ethereum.org/EIPS/eip-2335).
+//!
+//! A keystore is a JSON document holding a secret key encrypted with AES-128-CTR under a key
+//! derived from a password with scrypt or PBKDF2-HMAC-SHA256:
+//!
+//! 1. the password is normalized (NFKD) and stripped of control codes
+//! 2. `DK = KDF(password, salt)`, 32 bytes
+//! 3. `cipher_message = AES-128-CTR(DK[0..16], iv, secret_key)`
+//! 4. `checksum = SHA-256(DK[16..32] || cipher_message)`
+//!
+//! Decryption checks the checksum before decrypting, so wrong passwords are reported as
+//! `Error::InvalidPassword`. The `pubkey` field holds the compressed bn256 public key of the secret
+//! key, and the remaining fields (`description`, `path`, `uuid`) are left to the application.
+//!
+//! The parameters of the key derivation function come from the JSON document, so their cost is
+//! bounded before deriving the key: the derived key must be 32 bytes long, scrypt is limited to
+//! `n <= 2^20`, `r <= 32` and `p <= 16`, and PBKDF2 to `c <= 2^20` iterations (EIP-2335
+//! recommends `2^18`). Other parameters are rejected with `Error::InvalidKeystore`.
+use aes::Aes128;
+use alloc::string::String;
+use alloc::vec::Vec;
+use ctr::cipher::{NewStreamCipher, SyncStreamCipher};
+use ctr::Ctr128;
+use hmac::Hmac;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use subtle::ConstantTimeEq;
+use unicode_normalization::UnicodeNormalization;
+use zeroize::Zeroizing;
+
+use super::error::Error;
+use super::PrivateKey;
+
+/// Version of the keystore format.
+pub const KEYSTORE_VERSION: u32 = 4;
+
+/// Length of the derived key in bytes.
+const DERIVED_KEY_LENGTH: u32 = 32;
+
+/// Maximum scrypt CPU/memory cost accepted in keystores.
+pub const MAX_SCRYPT_N: u32 = 1 << 20;
+
+/// Maximum scrypt block size accepted in keystores.
+pub const MAX_SCRYPT_R: u32 = 32;
+
+/// Maximum scrypt parallelization accepted in keystores.
+pub const MAX_SCRYPT_P: u32 = 16;
+
+/// Maximum number of PBKDF2 iterations accepted in keystores.
+pub const MAX_PBKDF2_C: u32 = 1 << 20;
+
+/// Length of the AES-128-CTR initialization vector in bytes.
+pub const IV_LENGTH: usize = 16;
+
+/// Name of the PBKDF2 pseudorandom function.
+const PBKDF2_PRF: &str = "hmac-sha256";
+
+/// A password-protected secret key.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Keystore {
+    /// Encrypted secret key and parameters needed to decrypt it
+    pub crypto: Crypto,
+    /// Optional description of the keystore
+    #[serde(default)]
+    pub description: String,
+    /// Hex-encoded public key of the secret key
+    pub pubkey: String,
+    /// Derivation path of the secret key (empty if unknown)
+    pub path: String,
+    /// UUID identifying the keystore
+    pub uuid: String,
+    /// Version of the keystore format
+    pub version: u32,
+}
+
+/// Cryptographic modules of a keystore.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Crypto {
+    /// Key derivation function
+    pub kdf: Module<KdfParams>,
+    /// Checksum of the derived key and the cipher message
+    pub checksum: Module<ChecksumParams>,
+    /// Cipher encrypting the secret key
+    pub cipher: Module<CipherParams>,
+}
+
+/// A cryptographic module: a function, its parameters and its message.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Module<P> {
+    /// Name of the function
+    pub function: String,
+    /// Parameters of the function
+    pub params: P,
+    /// Hex-encoded message of the module
+    pub message: String,
+}
+
+/// Parameters of the key derivation function.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum KdfParams {
+    /// Parameters of scrypt
+    Scrypt(ScryptParams),
+    /// Parameters of PBKDF2
+    Pbkdf2(Pbkdf2Params),
+}
+
+/// Parameters of scrypt.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ScryptParams {
+    /// Length of the derived key in bytes
+    pub dklen: u32,
+    /// CPU/memory cost, a power of two
+    pub n: u32,
+    /// Parallelization
+    pub p: u32,
+    /// Block size
+    pub r: u32,
+    /// Hex-encoded salt
+    pub salt: String,
+}
+
+/// Parameters of PBKDF2.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Pbkdf2Params {
+    /// Length of the derived key in bytes
+    pub dklen: u32,
+    /// Number of iterations
+    pub c: u32,
+    /// Pseudorandom function (`hmac-sha256`)
+    pub prf: String,
+    /// Hex-encoded salt
+    pub salt: String,
+}
+
+/// Parameters of the checksum (none).
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ChecksumParams {}
+
+/// Parameters of the cipher.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CipherParams {
+    /// Hex-encoded initialization vector
+    pub iv: String,
+}
+
+impl KdfParams {
+    /// Function to create the scrypt parameters recommended by EIP-2335 (`n = 2^18`, `r = 8`,
+    /// `p = 1`).
+    ///
+    /// # Arguments
+    ///
+    /// * `salt` - 32 bytes chosen uniformly at random for every keystore
+    pub fn scrypt(salt: &[u8]) -> Self {
+        KdfParams::Scrypt(ScryptParams {
+            dklen: DERIVED_KEY_LENGTH,
+            n: 262_144,
+            p: 1,
+            r: 8,
+            salt: hex::encode(salt),
+        })
+    }
+
+    /// Function to create the PBKDF2 parameters recommended by EIP-2335 (`c = 2^18` iterations of
+    /// HMAC-SHA256).
+    ///
+    /// # Arguments
+    ///
+    /// * `salt` - 32 bytes chosen uniformly at random for every keystore
+    pub fn pbkdf2(salt: &[u8]) -> Self {
+        KdfParams::Pbkdf2(Pbkdf2Params {
+            dklen: DERIVED_KEY_LENGTH,
+            c: 262_144,
+            prf: String::from(PBKDF2_PRF),
+            salt: hex::encode(salt),
+        })
+    }
+
+    /// Function to get the name of the key derivation function.
+    fn function(&self) -> &'static str {
+        match self {
+            KdfParams::Scrypt(_) => "scrypt",
+            KdfParams::Pbkdf2(_) => "pbkdf2",
+        }
+    }
+
+    /// Function to derive the decryption key from a password.
+    fn derive_key(&self, password: &str) -> Result<Zeroizing<Vec<u8>>, Error> {
+        let password = normalize_password(password);
+        match self {
+            KdfParams::Scrypt(params) => {
+                check_dklen(params.dklen)?;
+                if !params.n.is_power_of_two()
+                    || params.n < 2
+                    || params.n > MAX_SCRYPT_N
+                    || params.r > MAX_SCRYPT_R
+                    || params.p > MAX_SCRYPT_P
+                {
+                    return Err(Error::InvalidKeystore);
+                }
+                let log_n = params.n.trailing_zeros() as u8;
+                let scrypt_params = scrypt::ScryptParams::new(log_n, params.r, params.p)
+                    .map_err(|_| Error::InvalidKeystore)?;
+                let mut key = Zeroizing::new(vec![0u8; params.dklen as usize]);
+                scrypt::scrypt(
+                    password.as_bytes(),
+                    &decode_hex(&params.salt)?,
+                    &scrypt_params,
+                    &mut key,
+                )
+                .map_err(|_| Error::InvalidKeystore)?;
+
+                Ok(key)
+            }
+            KdfParams::Pbkdf2(params) => {
+                check_dklen(params.dklen)?;
+                if params.prf != PBKDF2_PRF || params.c == 0 || params.c > MAX_PBKDF2_C {
+                    return Err(Error::InvalidKeystore);
+                }
+                let mut key = Zeroizing::new(vec![0u8; params.dklen as usize]);
+                pbkdf2::pbkdf2::<Hmac<Sha256>>(
+                    password.as_bytes(),
+                    &decode_hex(&params.salt)?,
+                    params.c as usize,
+                    &mut key,
+                );
+
+                Ok(key)
+            }
+        }
+    }
+}
+
+impl Keystore {
+    /// Function to encrypt a secret key with a password.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_key` - The secret key bytes
+    /// * `password`   - The password
+    /// * `kdf`        - The parameters of the key derivation function, including a random salt
+    /// * `iv`         - 16 bytes chosen uniformly at random for every keystore
+    /// * `uuid`       - The UUID identifying the keystore
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `Keystore`; otherwise `Error`
+    pub fn encrypt(
+        secret_key: &[u8],
+        password: &str,
+        kdf: KdfParams,
+        iv: &[u8],
+        uuid: &str,
+    ) -> Result<Self, Error> {
+        if iv.len() != IV_LENGTH {
+            return Err(Error::InvalidLength);
+        }
+        let public_key = PrivateKey::from_slice(secret_key)?
+            .derive_public_key()?
+            .to_compressed()?;
+
+        let key = kdf.derive_key(password)?;
+        let cipher_message = apply_cipher(&key, iv, secret_key)?;
+        let checksum = compute_checksum(&key, &cipher_message);
+
+        Ok(Keystore {
+            crypto: Crypto {
+                kdf: Module {
+                    function: String::from(kdf.function()),
+                    params: kdf,
+                    message: String::new(),
+                },
+                checksum: Module {
+                    function: String::from("sha256"),
+                    params: ChecksumParams::default(),
+                    message: hex::encode(checksum),
+                },
+                cipher: Module {
+                    function: String::from("aes-128-ctr"),
+                    params: CipherParams {
+                        iv: hex::encode(iv),
+                    },
+                    message: hex::encode(cipher_message),
+                },
+            },
+            description: String::new(),
+            pubkey: hex::encode(public_key),
+            path: String::new(),
+            uuid: String::from(uuid),
+            version: KEYSTORE_VERSION,
+        })
+    }
+
+    /// Function to decrypt the secret key of the keystore.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the secret key; otherwise `Error::InvalidPassword`
+    ///   if the checksum does not match, or `Error::InvalidKeystore` if the keystore is malformed
+    pub fn decrypt(&self, password: &str) -> Result<Vec<u8>, Error> {
+        let crypto = &self.crypto;
+        if self.version != KEYSTORE_VERSION
+            || crypto.kdf.function != crypto.kdf.params.function()
+            || crypto.checksum.function != "sha256"
+            || crypto.cipher.function != "aes-128-ctr"
+        {
+            return Err(Error::InvalidKeystore);
+        }
+        let cipher_message = decode_hex(&crypto.cipher.message)?;
+        let iv = decode_hex(&crypto.cipher.params.iv)?;
+        if iv.len() != IV_LENGTH {
+            return Err(Error::InvalidKeystore);
+        }
+
+        let key = crypto.kdf.params.derive_key(password)?;
+        let checksum = decode_hex(&crypto.checksum.message)?;
+        // Compared in constant time, so the checksum does not leak how much of it was guessed
+        if !bool::from(compute_checksum(&key, &cipher_message)[..].ct_eq(&checksum)) {
+            return Err(Error::InvalidPassword);
+        }
+        let secret_key = Zeroizing::new(apply_cipher(&key, &iv, &cipher_message)?);
+        PrivateKey::from_slice(&secret_key)?;
+
+        Ok(secret_key.to_vec())
+    }
+
+    /// Function to parse a keystore from its JSON encoding.
+    ///
+    /// # Arguments
+    ///
+    /// * `json` - The JSON document
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `Keystore`; otherwise `Error::InvalidKeystore`
+    pub fn from_json(json: &str) -> Result<Self, Error> {
+        serde_json::from_str(json).map_err(|_| Error::InvalidKeystore)
+    }
+
+    /// Function to encode the keystore as JSON.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a string with the JSON document
+    pub fn to_json(&self) -> Result<String, Error> {
+        serde_json::to_string_pretty(self).map_err(|_| Error::InvalidKeystore)
+    }
+}
+
+/// Function to normalize a password with NFKD and strip the C0, C1 and `Delete` control codes.
+fn normalize_password(password: &str) -> Zeroizing<String> {
+    Zeroizing::new(
+        password
+            .nfkd()
+            .filter(|&c| !matches!(c as u32, 0x00..=0x1f | 0x7f..=0x9f))
+            .collect(),
+    )
+}
+
+/// Function to check that the derived key has the length used by the cipher and the checksum.
+fn check_dklen(dklen: u32) -> Result<(), Error> {
+    if dklen != DERIVED_KEY_LENGTH {
+        return Err(Error::InvalidKeystore);
+    }
+
+    Ok(())
+}
+
+/// Function to encrypt or decrypt with AES-128-CTR keyed with the first 16 bytes of the derived
+/// key.
+fn apply_cipher(key: &[u8], iv: &[u8], data: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut cipher = Ctr128::<Aes128>::new_var(&key[..16], iv).map_err(|_| Error::InvalidLength)?;
+    let mut output = data.to_vec();
+    cipher.apply_keystream(&mut output);
+
+    Ok(output)
+}
+
+/// Function to compute the checksum `SHA-256(DK[16..32] || cipher_message)`.
+fn compute_checksum(key: &[u8], cipher_message: &[u8]) -> Vec<u8> {
+    let mut hasher = Sha256::new();
+    hasher.input(&key[16..32]);
+    hasher.input(cipher_message);
+
+    hasher.result().to_vec()
+}
+
+/// Function to decode a hex string of the keystore.
+fn decode_hex(string: &str) -> Result<Vec<u8>, Error> {
+    hex::decode(string).map_err(|_| Error::InvalidKeystore)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Password of the EIP-2335 test vectors, normalized to `testpassword🔑`
+    const PASSWORD: &str = "𝔱𝔢𝔰𝔱𝔭𝔞𝔰𝔰𝔴𝔬𝔯𝔡🔑";
+
+    /// Test decrypting the PBKDF2 test vector of EIP-2335
+    #[test]
+    fn test_decrypt_test_vector() {
+        let json = r#"{
+            "crypto": {
+                "kdf": {
+                    "function": "pbkdf2",
+                    "params": {
+                        "dklen": 32,
+                        "c": 262144,
+                        "prf": "hmac-sha256",
+                        "salt": "d4e56740f876aef8c010b86a40d5f56745a118d0906a34e69aec8c0db1cb8fa3"
+                    },
+                    "message": ""
+                },
+                "checksum": {
+                    "function": "sha256",
+                    "params": {},
+                    "message": "8a9f5d9912ed7e75ea794bc5a89bca5f193721d30868ade6f73043c6ea6febf1"
+                },
+                "cipher": {
+                    "function": "aes-128-ctr",
+                    "params": {
+                        "iv": "264daa3f303d7259501c93d997d84fe6"
+                    },
+                    "message": "cee03fde2af33149775b7223e7845e4fb2c8ae1792e5f99fe9ecf474cc8c16ad"
+                }
+            },
+            "description": "This is a test keystore that uses PBKDF2 to secure the secret.",
+            "pubkey": "9612d7a727c9d0a22e185a1c768478dfe919cada9266988cb32359c11f2b7b27f4ae4040902382ae2910c15e2b420d07",
+            "path": "m/12381/60/0/0",
+            "uuid": "64625def-3331-4eea-ab6f-782f3ed16a83",
+            "version": 4
+        }"#;
+        let keystore = Keystore::from_json(json).unwrap();
+
+        assert_eq!(
+            hex::encode(keystore.decrypt(PASSWORD).unwrap()),
+            "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f"
+        );
+        match keystore.decrypt("testpassword") {
+            Err(Error::InvalidPassword) => {}
+            _ => panic!("Expected InvalidPassword error"),
+        }
+        assert!(
+            Keystore::from_json(&json.replace("\"version\": 4", "\"version\": 3"))
+                .unwrap()
+                .decrypt(PASSWORD)
+                .is_err()
+        );
+    }
+
+    /// Test encrypting and decrypting secret keys
+    #[test]
+    fn test_encrypt_decrypt() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let uuid = "1d85ae20-35c5-4611-98e8-aa14a633906f";
+        let iv = [3u8; IV_LENGTH];
+
+        // Cheap scrypt parameters to keep the test fast
+        let mut kdf = KdfParams::scrypt(&[1u8; 32]);
+        if let KdfParams::Scrypt(params) = &mut kdf {
+            params.n = 16;
+        }
+        let keystore = Keystore::encrypt(&secret_key, PASSWORD, kdf, &iv, uuid).unwrap();
+        let parsed = Keystore::from_json(&keystore.to_json().unwrap()).unwrap();
+        assert_eq!(parsed, keystore);
+        assert_eq!(parsed.decrypt("testpassword🔑").unwrap(), secret_key);
+        assert!(parsed.decrypt("wrong password").is_err());
+
+        let mut kdf = KdfParams::pbkdf2(&[2u8; 32]);
+        if let KdfParams::Pbkdf2(params) = &mut kdf {
+            params.c = 16;
+        }
+        let keystore = Keystore::encrypt(&secret_key, PASSWORD, kdf, &iv, uuid).unwrap();
+        assert_eq!(keystore.crypto.kdf.function, "pbkdf2");
+        let parsed = Keystore::from_json(&keystore.to_json().unwrap()).unwrap();
+        assert_eq!(parsed.decrypt(PASSWORD).unwrap(), secret_key);
+    }
+
+    /// Test that key derivation parameters with an excessive cost are rejected
+    #[test]
+    fn test_kdf_cost_bounds() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let uuid = "1d85ae20-35c5-4611-98e8-aa14a633906f";
+        let iv = [3u8; IV_LENGTH];
+        let mut kdf = KdfParams::scrypt(&[1u8; 32]);
+        if let KdfParams::Scrypt(params) = &mut kdf {
+            params.n = 16;
+        }
+        let keystore = Keystore::encrypt(&secret_key, PASSWORD, kdf, &iv, uuid).unwrap();
+        let json = keystore.to_json().unwrap();
+
+        for (field, value) in [
+            ("\"n\": 16", "\"n\": 2147483648"),
+            ("\"r\": 8", "\"r\": 4294967295"),
+            ("\"p\": 1", "\"p\": 4294967295"),
+            ("\"dklen\": 32", "\"dklen\": 4294967295"),
+            ("\"dklen\": 32", "\"dklen\": 64"),
+        ]
+        .iter()
+        {
+            assert!(json.contains(field));
+            let tampered = Keystore::from_json(&json.replace(field, value)).unwrap();
+            match tampered.decrypt(PASSWORD) {
+                Err(Error::InvalidKeystore) => {}
+                _ => panic!("Expected InvalidKeystore error"),
+            }
+        }
+
+        let mut kdf = KdfParams::pbkdf2(&[2u8; 32]);
+        if let KdfParams::Pbkdf2(params) = &mut kdf {
+            params.c = u32::MAX;
+        }
+        match Keystore::encrypt(&secret_key, PASSWORD, kdf, &iv, uuid) {
+            Err(Error::InvalidKeystore) => {}
+            _ => panic!("Expected InvalidKeystore error"),
+        }
+    }
+}
@@ -0,0 +1,220 @@
+//! Mnemonic phrases for backing up secret keys ([BIP-39](https://github.com/bitcoin/bips/blob/master/bip-0039.mediawiki)).
+//!
+//! A mnemonic encodes 16 to 32 bytes of entropy, followed by the first `ENT / 32` bits of its
+//! SHA-256 checksum, as words of 11 bits from the English word list. The seed of a mnemonic is
+//! `PBKDF2-HMAC-SHA512(mnemonic, "mnemonic" || passphrase, 2048)`, both strings being normalized
+//! with NFKD, and secret keys are derived from the seed with `keygen::key_gen`.
+use alloc::string::String;
+use alloc::vec::Vec;
+use hmac::Hmac;
+use sha2::{Digest, Sha256, Sha512};
+use subtle::ConstantTimeEq;
+use unicode_normalization::UnicodeNormalization;
+use zeroize::{Zeroize, Zeroizing};
+
+use super::error::Error;
+use super::keygen::key_gen;
+
+/// English word list of BIP-39.
+const ENGLISH: &str = include_str!("mnemonic/english.txt");
+
+/// Number of PBKDF2 iterations used for deriving the seed.
+const SEED_ITERATIONS: usize = 2048;
+
+/// Length of the seed in bytes.
+pub const SEED_LENGTH: usize = 64;
+
+/// Function to encode entropy as a mnemonic phrase.
+///
+/// # Arguments
+///
+/// * `entropy` - 16, 20, 24, 28 or 32 bytes chosen uniformly at random
+///
+/// # Returns
+///
+/// * If successful, a string with the words of the mnemonic separated by spaces; otherwise
+///   `Error::InvalidLength`
+pub fn entropy_to_mnemonic(entropy: &[u8]) -> Result<String, Error> {
+    if ![16, 20, 24, 28, 32].contains(&entropy.len()) {
+        return Err(Error::InvalidLength);
+    }
+    let words: Vec<&str> = ENGLISH.lines().collect();
+    let checksum = Sha256::digest(entropy);
+    let bytes = [entropy, &checksum[..]].concat();
+    let word_count = entropy.len() * 3 / 4;
+
+    let mut mnemonic = Vec::with_capacity(word_count);
+    for i in 0..word_count {
+        mnemonic.push(words[read_bits(&bytes, i * 11)]);
+    }
+
+    Ok(mnemonic.join(" "))
+}
+
+/// Function to decode the entropy of a mnemonic phrase, checking its words and checksum.
+///
+/// # Arguments
+///
+/// * `mnemonic` - The mnemonic phrase (12, 15, 18, 21 or 24 words)
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the entropy; otherwise `Error::InvalidMnemonic`
+pub fn mnemonic_to_entropy(mnemonic: &str) -> Result<Vec<u8>, Error> {
+    let words: Vec<&str> = ENGLISH.lines().collect();
+    let mnemonic: Zeroizing<String> = Zeroizing::new(mnemonic.nfkd().collect());
+    let indices = mnemonic
+        .split_whitespace()
+        .map(|word| {
+            words
+                .binary_search(&word)
+                .map_err(|_| Error::InvalidMnemonic)
+        })
+        .collect::<Result<Vec<usize>, Error>>()?;
+    if ![12, 15, 18, 21, 24].contains(&indices.len()) {
+        return Err(Error::InvalidMnemonic);
+    }
+
+    // Pack the 11-bit indices, which hold the entropy followed by the checksum
+    let entropy_length = indices.len() * 4 / 3;
+    let mut bytes = Zeroizing::new(vec![0u8; entropy_length + 1]);
+    for (i, &index) in indices.iter().enumerate() {
+        for bit in 0..11 {
+            if index >> (10 - bit) & 1 == 1 {
+                let position = i * 11 + bit;
+                bytes[position / 8] |= 0x80 >> (position % 8);
+            }
+        }
+    }
+    let entropy = bytes[..entropy_length].to_vec();
+    let checksum_bits = entropy_length / 4;
+    let mask = (0xff00u16 >> checksum_bits) as u8;
+    let checksum = Sha256::digest(&entropy)[0] & mask;
+    if !bool::from(checksum.ct_eq(&(bytes[entropy_length] & mask))) {
+        return Err(Error::InvalidMnemonic);
+    }
+
+    Ok(entropy)
+}
+
+/// Function to derive the seed of a mnemonic phrase.
+///
+/// # Arguments
+///
+/// * `mnemonic`   - The mnemonic phrase
+/// * `passphrase` - The optional passphrase (may be empty)
+///
+/// # Returns
+///
+/// * If successful, a vector of 64 bytes with the seed; otherwise `Error::InvalidMnemonic`
+pub fn mnemonic_to_seed(mnemonic: &str, passphrase: &str) -> Result<Vec<u8>, Error> {
+    mnemonic_to_entropy(mnemonic)?.zeroize();
+    let normalized: Zeroizing<String> = Zeroizing::new(mnemonic.nfkd().collect());
+    let mnemonic = Zeroizing::new(
+        normalized
+            .split_whitespace()
+            .collect::<Vec<&str>>()
+            .join(" "),
+    );
+    let salt: Zeroizing<String> =
+        Zeroizing::new("mnemonic".chars().chain(passphrase.nfkd()).collect());
+
+    let mut seed = vec![0u8; SEED_LENGTH];
+    pbkdf2::pbkdf2::<Hmac<Sha512>>(
+        mnemonic.as_bytes(),
+        salt.as_bytes(),
+        SEED_ITERATIONS,
+        &mut seed,
+    );
+
+    Ok(seed)
+}
+
+/// Function to derive the secret key of a mnemonic phrase, i.e. `KeyGen` applied to its seed.
+///
+/// # Arguments
+///
+/// * `mnemonic`   - The mnemonic phrase
+/// * `passphrase` - The optional passphrase (may be empty)
+///
+/// # Returns
+///
+/// * If successful, a vector of 32 big endian bytes with the secret key; otherwise `Error`
+pub fn mnemonic_to_secret_key(mnemonic: &str, passphrase: &str) -> Result<Vec<u8>, Error> {
+    let seed = Zeroizing::new(mnemonic_to_seed(mnemonic, passphrase)?);
+
+    key_gen(&seed, b"")
+}
+
+/// Function to read the 11 bits starting at `offset` as a big endian integer.
+fn read_bits(bytes: &[u8], offset: usize) -> usize {
+    (0..11).fold(0, |index, bit| {
+        let position = offset + bit;
+        index << 1 | (bytes[position / 8] >> (7 - position % 8) & 1) as usize
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Test the mnemonics and seeds of the BIP-39 test vectors (passphrase `TREZOR`)
+    #[test]
+    fn test_mnemonic_test_vectors() {
+        let vectors = [
+            (
+                "00000000000000000000000000000000",
+                "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+                "c55257c360c07c72029aebc1b53c05ed0362ada38ead3e3e9efa3708e53495531f09a6987599d18264c1e1c92f2cf141630c7a3c4ab7c81b2f001698e7463b04",
+            ),
+            (
+                "7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f",
+                "legal winner thank year wave sausage worth useful legal winner thank yellow",
+                "2e8905819b8723fe2c1d161860e5ee1830318dbf49a83bd451cfb8440c28bd6fa457fe1296106559a3c80937a1c1069be3a3a5bd381ee6260e8d9739fce1f607",
+            ),
+            (
+                "8080808080808080808080808080808080808080808080808080808080808080",
+                "letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic avoid letter advice cage absurd amount doctor acoustic bless",
+                "c0c519bd0e91a2ed54357d9d1ebef6f5af218a153624cf4f2da911a0ed8f7a09e2ef61af0aca007096df430022f7a2b6fb91661a9589097069720d015e4e982f",
+            ),
+        ];
+        for (entropy, mnemonic, seed) in vectors.iter() {
+            let entropy = hex::decode(entropy).unwrap();
+            assert_eq!(entropy_to_mnemonic(&entropy).unwrap(), *mnemonic);
+            assert_eq!(mnemonic_to_entropy(mnemonic).unwrap(), entropy);
+            assert_eq!(
+                hex::encode(mnemonic_to_seed(mnemonic, "TREZOR").unwrap()),
+                *seed
+            );
+        }
+    }
+
+    /// Test recovering secret keys from mnemonics
+    #[test]
+    fn test_mnemonic_to_secret_key() {
+        let mnemonic = "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about";
+        assert_eq!(
+            hex::encode(mnemonic_to_secret_key(mnemonic, "TREZOR").unwrap()),
+            "254fb2238bbfc89722432066d0e795cb2250d3df84dcd7901dc7f4328bedf2d8"
+        );
+        assert!(
+            mnemonic_to_secret_key(mnemonic, "").unwrap()
+                != mnemonic_to_secret_key(mnemonic, "TREZOR").unwrap()
+        );
+
+        // Wrong checksum, unknown word and wrong number of words
+        for invalid in [
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon bitcoin",
+            "abandon abandon abandon abandon abandon abandon abandon abandon abandon abandon about",
+        ]
+        .iter()
+        {
+            match mnemonic_to_secret_key(invalid, "") {
+                Err(Error::InvalidMnemonic) => {}
+                _ => panic!("Expected InvalidMnemonic error"),
+            }
+        }
+        assert!(entropy_to_mnemonic(&[0u8; 15]).is_err());
+    }
+}
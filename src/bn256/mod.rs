@@ -10,8 +10,38 @@
 //! - test vectors from <a href="https://github.com/ethereum/go-ethereum/blob/7b189d6f1f7eedf46c6607901af291855b81112b/core/vm/contracts_test.go">Ethereum</a>
 //! - test vectors from <a href="https://asecuritysite.com/encryption/bn">Asecurity</a>
 //!
-//! <b>Hashing to G1</b>: In order to hash a specific message to G1 this module uses the try and increment algorithm. The running time of this algorithm is dependant on the input message, so it should be used only with public inputs. Alternatively different hashing methods can be implemented as specified in:
-//! - <a href="https://tools.ietf.org/html/draft-irtf-cfrg-hash-to--04#page-37">hash_to_ algorithms</a>
+//! <b>Hashing to G1</b>: In order to hash a specific message to G1 this module uses the try and increment algorithm. The running time of this algorithm is dependant on the input message, so it should be used only with public inputs. Alternatively, the `hash_to_curve` module implements the Shallue–van de Woestijne map as specified in:
+//! - <a href="https://tools.ietf.org/html/draft-irtf-cfrg-hash-to-curve">hash_to_curve algorithms</a>
+//!
+//! <b>Hashing to G2</b>: the `hash_to_g2` module hashes messages to G2 with the Shallue–van de Woestijne map, followed by the multiplication with the cofactor and a subgroup check.
+//!
+//! <b>Key generation</b>: the `keygen` module derives secret keys deterministically from input keying material with HKDF (`KeyGen` of the BLS draft), matching other conformant implementations, and the `derivation` module derives trees of keys by EIP-2334 paths such as `m/12381/3600/0/0/0`. With the `keystore` feature, the `keystore` module stores secret keys in password-protected EIP-2335 keystores, with the `keyfile` feature, `PrivateKey::save_encrypted` stores them in compact encrypted files, and with the `mnemonic` feature, the `mnemonic` module backs them up as BIP-39 mnemonic phrases. With the `pem` feature, the `pem` module encodes keys and signatures in DER (`SubjectPublicKeyInfo` and PKCS #8) and PEM.
+//!
+//! <b>Interoperability</b>: the `flagged` module converts signatures and public keys to the compressed format with the flags in the most significant bits of X, as used by gnark for BN256 and by Zcash-style BLS12-381 libraries. The `geth` module encodes signatures and public keys as `Marshal` of the bn256 package of go-ethereum, so they round-trip byte for byte with geth-based systems. The `precompile` module formats the inputs and parses the outputs of the bn256 precompiles of Ethereum (EIP-196 and EIP-197), including the pairing check that verifies a signature. With the `ssz` feature, the `ssz` module encodes public keys and signatures in SSZ and computes their hash tree roots, so they can be embedded in Eth2-style containers. With the `cbor` feature, the `cbor` module encodes keys, signatures and aggregate proofs in deterministic CBOR.
+//!
+//! <b>Incremental aggregation</b>: the `aggregator` module keeps running sums of signatures and public keys (`aggregator::SignatureAggregator`), so they can be aggregated as they arrive. `aggregator::AggregatePublicKey` maintains the aggregated key of a changing set of signers by adding and removing keys.
+//!
+//! <b>Structured messages</b>: the `signable` module serializes typed messages canonically with a domain tag (`signable::SignableMessage`), so they are signed and verified deterministically with `Bn256::sign_message` and `Bn256::verify_message`.
+//!
+//! <b>Engine configuration</b>: the `builder` module configures an engine in one place (`Bn256::builder`), selecting the hash function, the domain separation tag, the signature scheme and the validation strictness, and checks the settings before creating it.
+//!
+//! <b>Streaming</b>: the `streaming` module signs and verifies messages fed in chunks (`Bn256::streaming_signer` and `Bn256::streaming_verifier`), so large payloads are hashed without being loaded into memory. The signatures are the same as the ones of `sign`.
+//!
+//! <b>Fingerprints</b>: the `fingerprint` module identifies public keys by the first bytes of the SHA-256 digest of their compressed encoding (`PublicKey::fingerprint`), and `fingerprint::PublicKeyMap` looks them up by fingerprint.
+//!
+//! <b>External signers</b>: the `signer` module abstracts signing behind the `Signer` trait, so secret keys can be kept in an HSM or a secure enclave while signing with `Bn256::sign_with_signer`.
+//!
+//! <b>Core operations</b>: the `core` module exposes the low-level operations of the BLS draft (`CoreSign`, `CoreVerify`, `CoreAggregateVerify`) over curve points, on top of which the schemes of `Bn256` are built. The `pairing` module computes pairings and multi-pairings of the typed points (`pairing::pairing`, `pairing::multi_pairing`) and checks custom pairing equations (`pairing::pairing_equation`).
+//!
+//! <b>Accountable-subgroup multisignatures</b>: the `asm` module lets any subset of a fixed group of signers produce a multisignature that is verified together with a bitmap of the signers.
+//!
+//! <b>Threshold keys</b>: the `threshold` module splits a secret key into shares with Shamir secret sharing, together with a verification vector committing to the shares. The `dkg` module generates threshold keys without a trusted dealer, the `reshare` module transfers them to a new committee and the `refresh` module re-randomizes their shares. Shares are handed over to the members of a committee with the `share_export` module, optionally encrypted with a password.
+//!
+//! <b>Verifiable random functions</b>: the `vrf` module derives pseudorandom outputs from BLS signatures, which can be verified with the public key of the signer (`Bn256::vrf_prove` and `Bn256::vrf_verify`).
+//!
+//! <b>Identity-based encryption</b>: the `ibe` module implements the Boneh–Franklin scheme, whose private keys are BLS signatures of the identities.
+//!
+//! <b>Randomness beacons</b>: the `drand` module verifies the beacons of drand networks and extracts their randomness with a single call (`drand::verify_beacon`), while the `beacon` module produces and verifies chains of beacons with a threshold key. Messages can be encrypted to a future round with the `tlock` module, so that they can only be decrypted once the beacon of the round is published.
 //!
 //!<b>BLS resources</b>: The following resources have been used as a reference to implement BLS signatures:
 //!
@@ -21,354 +51,2247 @@
 //!
 //! # Disclaimer
 //!
-//! This module does not implement a defense against Rogue-key attacks, which means it should be used in protocols where the possession of the private key of each individual has been proven (i.e., by signing a message)
+//! Aggregating signatures of the same message is vulnerable to Rogue-key attacks unless the possession of the private key of each individual has been proven. Proofs of possession can be generated and checked with `Bn256::pop_prove` and `Bn256::pop_verify`. Alternatively, the message augmentation scheme (`Scheme::MessageAugmentation`) signs the public key together with the message, which defends against these attacks without proofs of possession.
 //!
 use crate::MultiSignature;
 
-use bn::{arith, pairing_batch, AffineG1, AffineG2, Fq, Fq2, Fr, Group, Gt, G1, G2};
+use ::core::cmp::Ordering;
+use ::core::marker::PhantomData;
+use ::core::ptr;
+use ::core::sync::atomic;
+use alloc::vec::Vec;
+use bn::{arith, AffineG1, AffineG2, Fq, Fq2, Fr, Group, G1, G2};
 use byteorder::{BigEndian, ByteOrder};
+use digest::generic_array::typenum::U32;
 use digest::Digest;
-use sha2;
-
+#[cfg(feature = "keyfile")]
+use rand_core::OsRng;
+#[cfg(feature = "rand")]
+use rand_core::{CryptoRng, RngCore};
+use sha2::Sha256;
+#[cfg(feature = "keyfile")]
+use std::path::Path;
+use subtle::{Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeGreater};
+#[cfg(feature = "keyfile")]
+use zeroize::Zeroizing;
+use zeroize::{Zeroize, ZeroizeOnDrop};
+
+pub mod aggregator;
+pub mod asm;
+pub mod beacon;
+pub mod builder;
+#[cfg(feature = "cbor")]
+pub mod cbor;
+pub mod ciphersuite;
+pub mod core;
+pub mod derivation;
+pub mod dkg;
+pub mod drand;
+pub mod encoding;
 pub mod error;
+pub mod expand_message;
+pub mod fingerprint;
+pub mod flagged;
+pub mod geth;
+pub mod hash_to_curve;
+pub mod hash_to_field;
+pub mod hash_to_g2;
+pub mod ibe;
+#[cfg(feature = "keyfile")]
+pub mod keyfile;
+pub mod keygen;
+#[cfg(feature = "keystore")]
+pub mod keystore;
+#[cfg(feature = "mnemonic")]
+pub mod mnemonic;
+pub mod pairing;
+#[cfg(feature = "pem")]
+pub mod pem;
+pub mod precompile;
+pub mod refresh;
+pub mod reshare;
+#[cfg(feature = "serde")]
+mod serialization;
+pub mod share_export;
+pub mod signable;
+pub mod signature_set;
+pub mod signer;
+#[cfg(feature = "ssz")]
+pub mod ssz;
+pub mod streaming;
+pub mod threshold;
+pub mod tlock;
+pub mod vrf;
+use ciphersuite::Ciphersuite;
 use error::Error;
+#[cfg(feature = "rand")]
+use hash_to_field::FromUniformBytes;
+use signature_set::SignatureSet;
+use signer::Signer;
+
+/// Identifier of the ciphersuite of `sign` and `verify`: messages hashed to G1 with try and
+/// increment, with the scheme and digest selected in the `Bn256` engine. It is specific to this
+/// crate, as try and increment is not a standard hash to curve suite.
+pub const TRY_AND_INCREMENT_ID: &[u8] = b"BLS_SIG_BN254G1_TAI_";
+
+/// Domain separation tag used for proofs of possession (`BN254G1_XMD:SHA-256_SVDW_RO_` suite).
+pub const POP_DST: &[u8] = b"BLS_POP_BN254G1_XMD:SHA-256_SVDW_RO_POP_";
+
+/// Domain separation tag used for deriving the coefficients of `batch_verify`.
+const BATCH_VERIFY_DST: &[u8] = b"BLS_BATCH_VERIFY_BN254_XMD:SHA-256_";
+
+/// Domain separation tag used for deriving the coefficients of delinearized aggregation.
+const DELINEARIZATION_DST: &[u8] = b"BLS_DELINEARIZATION_BN254_XMD:SHA-256_";
+
+/// BLS signature schemes from the [BLS IRTF draft](https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature) (section 3).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Scheme {
+    /// Basic scheme: aggregate verification requires all messages to be distinct.
+    Basic,
+    /// Message augmentation: the public key is prepended to the message before hashing.
+    MessageAugmentation,
+    /// Proof of possession: messages may be repeated, as every public key is expected to come
+    /// with a proof of possession of its secret key.
+    ProofOfPossession,
+}
 
 /// BLS multi signatures with curve bn256.
-pub struct Bn256;
+///
+/// The engine is generic over the hash function `D` used for hashing messages to G1 with try and
+/// increment, which defaults to SHA-256. Any digest with a 32-byte output can be used, e.g.
+/// `Bn256::<sha3::Keccak256>::new()` for signatures to be verified by Solidity contracts
+/// implementing try and increment with `keccak256`.
+pub struct Bn256<D = Sha256> {
+    /// Hash function used by try and increment.
+    digest: PhantomData<D>,
+    /// Signature scheme determining the signing input and the aggregate verification rules.
+    scheme: Scheme,
+    /// Whether the public key is prepended to the message before hashing to G1.
+    public_key_binding: bool,
+    /// Whether `Scheme::Basic` accepts repeated messages in aggregate verification.
+    allow_duplicate_messages: bool,
+    /// Number of bytes of the try and increment counter.
+    counter_width: usize,
+    /// Maximum number of try and increment attempts.
+    max_attempts: u64,
+    /// Whether verification accepts the point at infinity as public key or signature.
+    identity_policy: IdentityPolicy,
+    /// Domain separation tag for hashing to G1 with `hash_to_curve` instead of try and increment.
+    dst: Option<Vec<u8>>,
+}
 
-impl Bn256 {
-    /// Function to convert an arbitrary string to a point in the curve G1.
+impl Default for Bn256 {
+    fn default() -> Self {
+        Bn256::new()
+    }
+}
+
+impl<D: Digest<OutputSize = U32>> Bn256<D> {
+    /// Function to create a bn256 instance using the hash function `D` for hashing to G1.
+    ///
+    /// # Returns
+    ///
+    /// * A `Bn256` instance.
+    pub fn new() -> Self {
+        Bn256 {
+            digest: PhantomData,
+            scheme: Scheme::Basic,
+            public_key_binding: false,
+            allow_duplicate_messages: false,
+            counter_width: 1,
+            max_attempts: 255,
+            identity_policy: IdentityPolicy::Reject,
+            dst: None,
+        }
+    }
+
+    /// Function to select the signature scheme (`Scheme::Basic` by default).
+    ///
+    /// The scheme determines the input signed by `sign` (the message, or the public key followed by
+    /// the message for `Scheme::MessageAugmentation`) and the rules enforced by `aggregate_verify`.
     ///
     /// # Arguments
     ///
-    /// * `data` - A slice representing the data to be converted to a G1 point.
+    /// * `scheme` - The signature scheme.
     ///
     /// # Returns
     ///
-    /// * If successful, a `G1` representing the converted point.
-    fn arbitrary_string_to_g1(&self, data: &[u8]) -> Result<G1, Error> {
-        let mut v = vec![0x02];
-        v.extend(data);
-        let point = G1::from_compressed(&v)?;
+    /// * The `Bn256` instance with the updated setting.
+    pub fn with_scheme(mut self, scheme: Scheme) -> Self {
+        self.scheme = scheme;
 
-        Ok(point)
+        self
     }
 
-    /// Function to convert a `Hash(DATA|COUNTER)` to a point in the curve.
-    /// Similar to [VRF-draft-05](https://tools.ietf.org/pdf/draft-irtf-cfrg-vrf-05) (section 5.4.1.1).
+    /// Function to enable or disable binding the public key into the message hashing.
     ///
-    /// Point multiplication by the cofactor is not required for curve `bn256` as it has cofactor 1.
+    /// When enabled, `sign` and `verify` hash `PK || message` to G1 with try and increment, where
+    /// `PK` is the compressed public key of the signer, similarly to the
+    /// [VRF-draft-05](https://tools.ietf.org/pdf/draft-irtf-cfrg-vrf-05) (section 5.4.1.1). This
+    /// is disabled by default. Note that aggregated signatures of the same message can no longer
+    /// be verified against the aggregated public key, as every signer hashes a different input.
     ///
     /// # Arguments
     ///
-    /// * `msg` - A slice containing the input data.
+    /// * `enabled` - Whether the public key should be bound into the hash.
     ///
     /// # Returns
     ///
-    /// * If successful, a point in the `G1` group representing the hashed point.
-    fn hash_to_try_and_increment(&self, message: &[u8]) -> Result<G1, Error> {
-        let mut c = 0..255;
+    /// * The `Bn256` instance with the updated setting.
+    pub fn with_public_key_binding(mut self, enabled: bool) -> Self {
+        self.public_key_binding = enabled;
 
-        // Add counter suffix
-        let mut v = [&message[..], &[0x00]].concat();
-        let position = v.len() - 1;
+        self
+    }
 
-        // `Hash(cipher||PK||data)`
-        let point = c.find_map(|ctr| {
-            v[position] = ctr;
-            let attempted_hash = self.calculate_sha256(&v);
-            // Check validity of `H` (i.e. point exists in group G1)
-            self.arbitrary_string_to_g1(&attempted_hash).ok()
-        });
+    /// Function to opt out of the distinct messages rule of `Scheme::Basic` (enforced by default).
+    ///
+    /// Repeated messages should only be allowed if rogue key attacks are prevented by other means,
+    /// e.g. if the public keys are known to come with proofs of possession checked elsewhere.
+    ///
+    /// # Arguments
+    ///
+    /// * `allowed` - Whether aggregate verification accepts repeated messages.
+    ///
+    /// # Returns
+    ///
+    /// * The `Bn256` instance with the updated setting.
+    pub fn with_duplicate_messages(mut self, allowed: bool) -> Self {
+        self.allow_duplicate_messages = allowed;
 
-        // Return error if no valid point was found
-        point.ok_or(Error::HashToPointError)
+        self
     }
 
-    /// Function to convert `G1` point into compressed form (`0x02` if Y is even and `0x03` if Y is odd).
+    /// Function to configure the try and increment counter used for hashing to G1.
+    ///
+    /// The counter is appended to the message as a big endian integer of `counter_width` bytes
+    /// (between 1 and 8). By default a 1-byte counter with at most 255 attempts is used. If no
+    /// valid point is found within `max_attempts`, hashing fails with
+    /// `Error::TryAndIncrementExhausted`.
     ///
     /// # Arguments
     ///
-    /// * `point` - A `G1` point.
+    /// * `counter_width` - The number of bytes of the counter.
+    /// * `max_attempts`  - The maximum number of attempts.
     ///
     /// # Returns
     ///
-    /// * If successful, a `Vec<u8>` with the compressed `G1` point.
-    fn to_compressed_g1(&self, point: G1) -> Result<Vec<u8>, Error> {
-        // From Jacobian to Affine first!
-        let affine_coords = AffineG1::from_jacobian(point).ok_or(Error::PointInJacobian)?;
-        // Get X coordinate
-        let x = Fq::into_u256(affine_coords.x());
-        // Get Y coordinate
-        let y = Fq::into_u256(affine_coords.y());
-        // Get parity of Y
-        let parity = y.get_bit(0).ok_or(Error::IndexOutOfBounds)?;
-
-        // Take x as big endian into slice
-        let mut s = [0u8; 32];
-        x.to_big_endian(&mut s)?;
-        let mut result: Vec<u8> = Vec::new();
-        // Push 0x02 or 0x03 depending on parity
-        result.push(if parity { 3 } else { 2 });
-        // Append x
-        result.append(&mut s.to_vec());
+    /// * The `Bn256` instance with the updated setting.
+    pub fn with_try_and_increment(mut self, counter_width: usize, max_attempts: u64) -> Self {
+        self.counter_width = counter_width;
+        self.max_attempts = max_attempts;
 
-        Ok(result)
+        self
     }
 
-    /// Function to get the digest given some input data using SHA256 algorithm.
+    /// Function to select whether verification accepts the point at infinity as public key or
+    /// signature (`IdentityPolicy::Reject` by default).
     ///
     /// # Arguments
     ///
-    /// * `data` - A slice containing the input data.
+    /// * `policy` - The policy applied to the public keys and signatures being verified.
     ///
     /// # Returns
     ///
-    /// * The SHA256 digest as a slice.
-    fn calculate_sha256(&self, bytes: &[u8]) -> [u8; 32] {
-        let mut hasher = sha2::Sha256::new();
-        hasher.input(&bytes);
-        let mut hash = [0; 32];
-        hash.copy_from_slice(&hasher.result());
+    /// * The `Bn256` instance with the updated setting.
+    pub fn with_identity_policy(mut self, policy: IdentityPolicy) -> Self {
+        self.identity_policy = policy;
 
-        hash
+        self
     }
-}
 
-/// The scalar used as private key
-struct PrivateKey(bn::Fr);
+    /// Function to hash a message to G1 with try and increment, returning the number of attempts.
+    ///
+    /// The number of attempts can be used to monitor the hashing cost, e.g. for estimating the
+    /// gas consumed by on-chain verifiers.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - A slice containing the input data.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the hashed point in `G1` and the number of attempts used.
+    pub fn try_and_increment(&self, message: &[u8]) -> Result<(G1, u64), Error> {
+        // Validate the width before allocating the counter
+        self.check_counter_width()?;
+        // Add counter suffix
+        let mut v = [message, &vec![0x00; self.counter_width]].concat();
+        let position = v.len() - self.counter_width;
+
+        // `Hash(data||COUNTER)`
+        self.try_and_increment_with(|counter| {
+            v[position..].copy_from_slice(counter);
+            self.calculate_hash(&v)
+        })
+    }
 
-/// The public key as point in G2
-struct PublicKey(bn::G2);
+    /// Function to run try and increment given the hash of the message followed by a counter.
+    ///
+    /// # Arguments
+    ///
+    /// * `hash_with_counter` - A function returning `Hash(data||COUNTER)` given the counter bytes.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the hashed point in `G1` and the number of attempts used.
+    fn try_and_increment_with<F>(&self, mut hash_with_counter: F) -> Result<(G1, u64), Error>
+    where
+        F: FnMut(&[u8]) -> [u8; 32],
+    {
+        self.check_counter_width()?;
+        // Attempts are limited by the values the counter can represent
+        let max_attempts = match 1u64.checked_shl(8 * self.counter_width as u32) {
+            Some(counter_values) => self.max_attempts.min(counter_values),
+            None => self.max_attempts,
+        };
 
-impl PrivateKey {
-    /// Function to derive the bn256 public key from the private key.
-    fn derive_public_key(self) -> Result<PublicKey, Error> {
-        let PrivateKey(sk) = self;
+        for ctr in 0..max_attempts {
+            let attempted_hash = hash_with_counter(&ctr.to_be_bytes()[8 - self.counter_width..]);
+            // Check validity of `H` (i.e. point exists in group G1)
+            if let Ok(point) = self.arbitrary_string_to_g1(&attempted_hash) {
+                return Ok((point, ctr + 1));
+            }
+        }
 
-        Ok(PublicKey(G2::one() * sk))
+        // Return error if no valid point was found
+        Err(Error::TryAndIncrementExhausted)
     }
-}
 
-impl PublicKey {
-    /// Function to convert a complex coordinate (`Fq2`) to `U512`.
-    pub fn to_u512(&self, coord: Fq2) -> arith::U512 {
-        let c0: arith::U256 = (coord.real()).into_u256();
-        let c1: arith::U256 = (coord.imaginary()).into_u256();
+    /// Function to check that the try and increment counter is between 1 and 8 bytes wide.
+    fn check_counter_width(&self) -> Result<(), Error> {
+        if self.counter_width == 0 || self.counter_width > 8 {
+            return Err(Error::InvalidLength);
+        }
 
-        arith::U512::new(&c1, &c0, &Fq::modulus())
+        Ok(())
     }
 
-    /// Function to create a `PublicKey` from bytes representing a G2 point in compressed format.
-    pub fn from_compressed(bytes: &[u8]) -> Result<Self, Error> {
-        let uncompressed = G2::from_compressed(&bytes)?;
+    /// Function to convert an arbitrary string to a point in the curve G1.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - A slice representing the data to be converted to a G1 point.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a `G1` representing the converted point.
+    fn arbitrary_string_to_g1(&self, data: &[u8]) -> Result<G1, Error> {
+        let mut v = vec![0x02];
+        v.extend(data);
+        let point = G1::from_compressed(&v)?;
 
-        Ok(PublicKey(uncompressed))
+        Ok(point)
     }
 
-    /// Function to create a `PublicKey` from bytes representing a G2 point in uncompressed format.
-    pub fn from_uncompressed(bytes: &[u8]) -> Result<Self, Error> {
-        if bytes.len() != 128 {
-            return Err(Error::InvalidLength {});
-        }
-        let x = Fq2::new(
-            Fq::from_slice(&bytes[0..32])?,
-            Fq::from_slice(&bytes[32..64])?,
-        );
-        let y = Fq2::new(
-            Fq::from_slice(&bytes[64..96])?,
-            Fq::from_slice(&bytes[96..128])?,
-        );
-        let pub_key = AffineG2::new(x, y)?;
+    /// Function to convert a `Hash(DATA|COUNTER)` to a point in the curve.
+    /// Similar to [VRF-draft-05](https://tools.ietf.org/pdf/draft-irtf-cfrg-vrf-05) (section 5.4.1.1).
+    ///
+    /// Point multiplication by the cofactor is not required for curve `bn256` as it has cofactor 1.
+    ///
+    /// This is the hashing used by `sign` and `verify`, so the resulting point can be passed to
+    /// `sign_hashed` and `verify_hashed` in order to hash a message only once. If public key
+    /// binding is enabled, the compressed public key has to be prepended to the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `msg` - A slice containing the input data.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a point in the `G1` group representing the hashed point.
+    pub fn hash_to_try_and_increment(&self, message: &[u8]) -> Result<G1, Error> {
+        let (point, _attempts) = self.try_and_increment(message)?;
 
-        Ok(PublicKey(pub_key.into()))
+        Ok(point)
     }
 
-    /// Function to serialize the `PublicKey` to vector of bytes in compressed format.
-    pub fn to_compressed(&self) -> Result<Vec<u8>, Error> {
-        let modulus = Fq::modulus();
-        // From Jacobian to Affine first!
-        let affine_coords = AffineG2::from_jacobian(self.0).ok_or(Error::PointInJacobian)?;
-
-        // Get X real coordinate
-        let x_real = Fq::into_u256(affine_coords.x().real());
-        // Get X imaginary coordinate
-        let x_imaginary = Fq::into_u256(affine_coords.x().imaginary());
-        // Get Y and get sign
-        let y = affine_coords.y();
-        let y_neg = -y;
-        let sign: u8 = if self.to_u512(y) > self.to_u512(y_neg) {
-            0x0b
-        } else {
-            0x0a
-        };
-
-        // To U512 and its compressed representation
-        let compressed = arith::U512::new(&x_imaginary, &x_real, &modulus);
-        // To slice
-        let mut buf: [u8; 64] = [0; (4 * 16)];
-        for (l, i) in (0..4).rev().zip((0..4).map(|i| i * 16)) {
-            BigEndian::write_u128(&mut buf[i..], compressed.0[l]);
+    /// Function to hash the input of a signature to G1, with `hash_to_curve` if a domain
+    /// separation tag has been configured (see `Bn256Builder::dst`) and with try and increment
+    /// otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `input` - The bytes to hash, i.e. the message, prepended with the public key if required
+    ///   by the scheme.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the hashed point in `G1`.
+    fn hash_input(&self, input: &[u8]) -> Result<G1, Error> {
+        match &self.dst {
+            Some(dst) => hash_to_curve::hash_to_curve(input, dst),
+            None => self.hash_to_try_and_increment(input),
         }
-
-        // Result = sign || compressed
-        let mut result: Vec<u8> = Vec::new();
-        result.push(sign);
-        result.append(&mut buf.to_vec());
-
-        Ok(result)
     }
 
-    /// Function to serialize the `PublicKey` to vector of bytes in uncompressed format.
-    pub fn to_uncompressed(&self) -> Result<Vec<u8>, Error> {
-        // From Jacobian to Affine first!
-        let affine_coords = AffineG2::from_jacobian(self.0).ok_or(Error::PointInJacobian)?;
-        let mut result: [u8; 32 * 4] = [0; (4 * 32)];
-
-        // Get X real coordinate
-        Fq::into_u256(affine_coords.x().real()).to_big_endian(&mut result[0..32])?;
-
-        // Get X imaginary coordinate
-        Fq::into_u256(affine_coords.x().imaginary()).to_big_endian(&mut result[32..64])?;
-
-        // Get Y real coordinate
-        Fq::into_u256(affine_coords.y().real()).to_big_endian(&mut result[64..96])?;
-
-        // Get Y imaginary coordinate
-        Fq::into_u256(affine_coords.y().imaginary()).to_big_endian(&mut result[96..128])?;
+    /// Function to get the input to be hashed for a message, which is `PK || message` for the
+    /// message augmentation scheme (or if public key binding is enabled) and the message otherwise.
+    ///
+    /// # Arguments
+    ///
+    /// * `message`     - The message bytes
+    /// * `public_key`  - The compressed public key bytes of the signer
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the input to be hashed.
+    fn signing_input(&self, message: &[u8], public_key: &[u8]) -> Result<Vec<u8>, Error> {
+        augment_message(message, public_key, self.binds_public_key())
+    }
 
-        Ok(result.to_vec())
+    /// Function to hash a message to G1 with try and increment, prepending the compressed public
+    /// key if required by the scheme.
+    ///
+    /// # Arguments
+    ///
+    /// * `message`     - The message bytes
+    /// * `public_key`  - The compressed public key bytes of the signer
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a point in the `G1` group representing the hashed message.
+    fn hash_message(&self, message: &[u8], public_key: &[u8]) -> Result<G1, Error> {
+        self.hash_input(&self.signing_input(message, public_key)?)
     }
-}
 
-impl MultiSignature<&[u8], &[u8], &[u8]> for Bn256 {
-    type Error = Error;
+    /// Function to check whether the public key is prepended to the message before hashing, either
+    /// because of the message augmentation scheme or because public key binding is enabled.
+    fn binds_public_key(&self) -> bool {
+        self.public_key_binding || self.scheme == Scheme::MessageAugmentation
+    }
 
-    /// Function to derive public key (point in G2) given a secret key.
+    /// Function to verify an aggregated signature of the same message by several signers
+    /// (`FastAggregateVerify`).
+    ///
+    /// The public keys are aggregated internally, so exactly two pairings are computed regardless
+    /// of the number of signers. This is only available for `Scheme::ProofOfPossession`, as it is
+    /// only secure when the proofs of possession of all public keys have been verified (see
+    /// `pop_verify`).
     ///
     /// # Arguments
     ///
-    /// * `secret_key` - The secret key bytes
+    /// * `public_keys` - The public key bytes of every signer
+    /// * `message`     - The message signed by every signer
+    /// * `signature`   - The aggregated signature bytes
     ///
     /// # Returns
     ///
-    /// * If successful, a vector of bytes with the public key
-    fn derive_public_key(&mut self, secret_key: &[u8]) -> Result<Vec<u8>, Error> {
-        let scalar = Fr::from_slice(&secret_key[0..32])?;
-        let key = PrivateKey(scalar);
-        let public = key.derive_public_key()?;
+    /// * If successful, `Ok(())`; otherwise `Error`
+    pub fn fast_aggregate_verify(
+        &self,
+        public_keys: &[&[u8]],
+        message: &[u8],
+        signature: &[u8],
+    ) -> Result<(), Error> {
+        if self.scheme != Scheme::ProofOfPossession || self.binds_public_key() {
+            return Err(Error::UnsupportedScheme);
+        }
+        if public_keys.is_empty() {
+            return Err(Error::InvalidLength);
+        }
 
-        public.to_compressed()
+        let points: Result<Vec<G2>, Error> = public_keys
+            .iter()
+            .map(|&compressed| self.public_key_point(compressed))
+            .collect();
+        let aggregated_key = core::aggregate_public_keys(&points?);
+        let hash_point = self.hash_input(message)?;
+        let signature_point = self.signature_point(signature)?;
+
+        core::core_verify(aggregated_key, hash_point, signature_point)
     }
 
-    /// Function to sign a message given a private key (as a point in G1).
+    /// Function to verify several independent signatures at once, each one with its own public
+    /// key and message.
+    ///
+    /// The signatures are combined with pseudo-random coefficients into a single batch of `n + 1`
+    /// pairings instead of `2 * n`, while an invalid signature still makes the whole batch fail.
+    /// The coefficients are derived by hashing all the public keys, messages and signatures with
+    /// `hash_to_field`, so they cannot be predicted before fixing the batch and no random number
+    /// generator is required. If the batch fails, the signatures can be checked one by one with
+    /// `verify` to find the invalid ones.
     ///
     /// # Arguments
     ///
-    /// * `message`     - The message bytes
-    /// * `secret_key`  - The secret key bytes
+    /// * `public_keys` - The public key bytes of every signature
+    /// * `messages`    - The message of every signature
+    /// * `signatures`  - The signature bytes
     ///
     /// # Returns
     ///
-    /// * If successful, a vector of bytes with the signature
-    fn sign(&mut self, secret_key: &[u8], message: &[u8]) -> Result<Vec<u8>, Self::Error> {
-        // 1. Hash_to_try_and_increment --> H(m) as point in G1 (only if it exists)
-        let hash_point = self.hash_to_try_and_increment(&message)?;
-
-        // 2. Multiply hash_point times secret_key --> Signature in G1
-        let sk = Fr::from_slice(&secret_key)?;
-        let signature = hash_point * sk;
+    /// * If successful, `Ok(())`; otherwise `Error`
+    pub fn batch_verify(
+        &self,
+        public_keys: &[&[u8]],
+        messages: &[&[u8]],
+        signatures: &[&[u8]],
+    ) -> Result<(), Error> {
+        let n = public_keys.len();
+        if n == 0 || messages.len() != n || signatures.len() != n {
+            return Err(Error::InvalidLength);
+        }
 
-        // 3. Return signature as compressed bytes
-        self.to_compressed_g1(signature)
+        let mut public_key_points = Vec::with_capacity(n);
+        let mut hash_points = Vec::with_capacity(n);
+        let mut signature_points = Vec::with_capacity(n);
+        let mut transcript = Vec::new();
+        for i in 0..n {
+            public_key_points.push(self.public_key_point(public_keys[i])?);
+            hash_points.push(self.hash_message(messages[i], public_keys[i])?);
+            signature_points.push(self.signature_point(signatures[i])?);
+
+            // `PK_i || len(m_i) || m_i || S_i`
+            transcript.extend_from_slice(public_keys[i]);
+            transcript.extend_from_slice(&(messages[i].len() as u64).to_be_bytes());
+            transcript.extend_from_slice(messages[i]);
+            transcript.extend_from_slice(signatures[i]);
+        }
+        let coefficients =
+            hash_to_field::hash_to_field::<Fr, Sha256>(&transcript, BATCH_VERIFY_DST, n)?;
+
+        core::core_batch_verify(
+            &public_key_points,
+            &hash_points,
+            &signature_points,
+            &coefficients,
+        )
     }
 
-    /// Function to verify a signature (point in G1) given a public key (point in G2).
+    /// Function to verify several independent signatures at once as `batch_verify`, with the
+    /// messages already hashed (see `hash`).
+    ///
+    /// The coefficients are derived from the public keys, the compressed hashed messages and the
+    /// signatures.
     ///
     /// # Arguments
     ///
-    /// * `signature`   - The signature bytes
-    /// * `message`     - The message to be signed
-    /// * `public_key`  - The public key bytes
+    /// * `public_keys` - The public key bytes of every signature
+    /// * `messages`    - The hashed message of every signature
+    /// * `signatures`  - The signature bytes
     ///
     /// # Returns
     ///
     /// * If successful, `Ok(())`; otherwise `Error`
-    fn verify(
-        &mut self,
-        signature: &[u8],
-        message: &[u8],
-        public_key: &[u8],
-    ) -> Result<(), Self::Error> {
-        let mut vals = Vec::new();
-        // First pairing input: e(H(m), PubKey)
-        let hash_point = self.hash_to_try_and_increment(&message)?;
-        let public_key_point = G2::from_compressed(&public_key)?;
-        vals.push((hash_point, public_key_point));
-        // Second pairing input:  e(-Signature,G2::one())
-        let signature_point = G1::from_compressed(&signature)?;
-        vals.push((signature_point, -G2::one()));
-        // Pairing batch with one negated point
-        let mul = pairing_batch(&vals);
-        if mul == Gt::one() {
-            Ok(())
-        } else {
-            Err(Error::VerificationFailed)
+    pub fn batch_verify_hashed(
+        &self,
+        public_keys: &[&[u8]],
+        messages: &[HashedMessage],
+        signatures: &[&[u8]],
+    ) -> Result<(), Error> {
+        let n = public_keys.len();
+        if n == 0 || messages.len() != n || signatures.len() != n {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut public_key_points = Vec::with_capacity(n);
+        let mut signature_points = Vec::with_capacity(n);
+        let mut transcript = Vec::new();
+        for i in 0..n {
+            public_key_points.push(self.public_key_point(public_keys[i])?);
+            signature_points.push(self.signature_point(signatures[i])?);
+
+            // `PK_i || H(m_i) || S_i`
+            transcript.extend_from_slice(public_keys[i]);
+            transcript.extend_from_slice(&g1_to_compressed_array(messages[i].0)?);
+            transcript.extend_from_slice(signatures[i]);
         }
+        let hash_points: Vec<G1> = messages.iter().map(|message| message.0).collect();
+        let coefficients =
+            hash_to_field::hash_to_field::<Fr, Sha256>(&transcript, BATCH_VERIFY_DST, n)?;
+
+        core::core_batch_verify(
+            &public_key_points,
+            &hash_points,
+            &signature_points,
+            &coefficients,
+        )
     }
 
-    /// Function to aggregate public keys (sum of points in G2).
+    /// Function to aggregate public keys scaled by hash-derived coefficients, so that aggregating
+    /// signatures of the same message is safe without proofs of possession.
+    ///
+    /// Every public key is multiplied by `a_i = H(PK_i, {PK_1, ..., PK_n})` before being added, as
+    /// proposed by Boneh, Drijvers and Neven. The result can be used with `verify` to check a
+    /// signature aggregated with `aggregate_signatures_delinearized` over the same set of keys.
     ///
     /// # Arguments
     ///
-    /// * `public_keys`  - An array of public key bytes to be aggregated
+    /// * `public_keys` - The public key bytes of every signer
     ///
     /// # Returns
     ///
     /// * If successful, a vector of bytes with the aggregated public key
-    fn aggregate_public_keys(&mut self, public_keys: &[&[u8]]) -> Result<Vec<u8>, Self::Error> {
-        let agg_public_key: Result<G2, Error> =
-            public_keys.iter().try_fold(G2::zero(), |acc, &compressed| {
-                let public_key = PublicKey::from_compressed(&compressed)?;
-
-                Ok(acc + public_key.0)
-            });
+    pub fn aggregate_public_keys_delinearized(
+        &self,
+        public_keys: &[&[u8]],
+    ) -> Result<Vec<u8>, Error> {
+        let coefficients = delinearization_coefficients(public_keys)?;
+        let mut points = Vec::with_capacity(public_keys.len());
+        for (&public_key, &coefficient) in public_keys.iter().zip(&coefficients) {
+            points.push(PublicKey::from_compressed(public_key)?.0 * coefficient);
+        }
 
-        PublicKey(agg_public_key?).to_compressed()
+        PublicKey(core::aggregate_public_keys(&points)).to_compressed()
     }
 
-    /// Function to aggregate signatures (sum of points in G1).
+    /// Function to aggregate signatures scaled by the coefficients of their public keys (see
+    /// `aggregate_public_keys_delinearized`).
     ///
     /// # Arguments
     ///
-    /// * `signatures`  - An array of signature bytes to be aggregated
+    /// * `public_keys` - The public key bytes of every signer
+    /// * `signatures`  - The signature bytes of every signer (in the same order)
     ///
     /// # Returns
     ///
     /// * If successful, a vector of bytes with the aggregated signature
-    fn aggregate_signatures(&mut self, signatures: &[&[u8]]) -> Result<Vec<u8>, Self::Error> {
-        let agg_signatures: Result<G1, Error> =
-            signatures.iter().try_fold(G1::zero(), |acc, &compressed| {
-                let signature = G1::from_compressed(&compressed)?;
-
-                Ok(acc + signature)
-            });
+    pub fn aggregate_signatures_delinearized(
+        &self,
+        public_keys: &[&[u8]],
+        signatures: &[&[u8]],
+    ) -> Result<Vec<u8>, Error> {
+        if public_keys.len() != signatures.len() {
+            return Err(Error::InvalidLength);
+        }
+        let coefficients = delinearization_coefficients(public_keys)?;
+        let mut points = Vec::with_capacity(signatures.len());
+        for (&signature, &coefficient) in signatures.iter().zip(&coefficients) {
+            points.push(g1_from_compressed(signature)? * coefficient);
+        }
 
-        self.to_compressed_g1(agg_signatures?)
+        self.to_compressed_g1(core::aggregate_signatures(&points))
     }
-}
-
-#[cfg(test)]
-mod test {
-    use super::*;
 
-    // Test vectors taken from https://asecuritysite.com/encryption/go_bn256. The public keys in G2 are changed in order in the website, i.e., imaginary goes first.
-    // In order to construct the test vectors we need to do the following
-    // Get the modulus of Fq
-    // Get the components (real, imaginary) of x and y
-    // perform (imaginary*modulus) +  real
-    // Compress with 0x0a or 0x0b depending on the value of y
-    #[test]
+    /// Function to verify an aggregated signature of the messages in a `SignatureSet`.
+    ///
+    /// For `Scheme::Basic`, the set is first checked to contain distinct messages, unless repeated
+    /// messages have been explicitly allowed with `with_duplicate_messages`.
+    ///
+    /// # Arguments
+    ///
+    /// * `set`       - The public keys and messages of the signers
+    /// * `signature` - The aggregated signature bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    pub fn aggregate_verify_set(&self, set: &SignatureSet, signature: &[u8]) -> Result<(), Error> {
+        if set.is_empty() {
+            return Err(Error::InvalidLength);
+        }
+        if self.scheme == Scheme::Basic && !self.allow_duplicate_messages {
+            set.validate_distinct_messages()?;
+        }
+
+        let mut public_key_points = Vec::with_capacity(set.len());
+        let mut hash_points = Vec::with_capacity(set.len());
+        for (public_key, message) in set.public_keys().iter().zip(set.messages()) {
+            hash_points.push(self.hash_message(message, public_key)?);
+            public_key_points.push(self.public_key_point(public_key)?);
+        }
+        let signature_point = self.signature_point(signature)?;
+
+        core::core_aggregate_verify(&public_key_points, &hash_points, signature_point)
+    }
+
+    /// Function to generate a proof of possession of a secret key (`PopProve`).
+    ///
+    /// The proof is a signature of the compressed public key hashed to G1 with `hash_to_curve`
+    /// under `POP_DST`, which is different from any DST used for signing messages. Verifying the
+    /// proofs of all public keys is required before aggregating signatures of the same message.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_key` - The secret key bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the proof
+    pub fn pop_prove(&self, secret_key: &[u8]) -> Result<Vec<u8>, Error> {
+        let public_key = self.derive_public_key(secret_key)?;
+        let hash_point = hash_to_curve::hash_to_curve(&public_key, POP_DST)?;
+
+        self.sign_hashed(secret_key, hash_point)
+    }
+
+    /// Function to verify a proof of possession of the secret key of a public key (`PopVerify`).
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key`  - The public key bytes
+    /// * `proof`       - The proof bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    pub fn pop_verify(&self, public_key: &[u8], proof: &[u8]) -> Result<(), Error> {
+        // Serialize again so that the same encoding is hashed by provers and verifiers
+        let public_key = PublicKey::from_compressed(public_key)?.to_compressed()?;
+        let hash_point = hash_to_curve::hash_to_curve(&public_key, POP_DST)?;
+
+        self.verify_hashed(proof, hash_point, &public_key)
+    }
+
+    /// Function to sign a message given a secret key and a domain separation tag.
+    ///
+    /// The message is hashed to G1 with `hash_to_curve::hash_to_curve` (`BN254G1_XMD:SHA-256_SVDW_RO_`),
+    /// where the DST is used as specified by the hash-to-curve draft. Signatures are only valid for
+    /// the DST used for signing, so different applications cannot replay each other's signatures.
+    /// With the message augmentation scheme the compressed public key is prepended to the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_key`  - The secret key bytes
+    /// * `message`     - The message bytes
+    /// * `dst`         - The domain separation tag
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the signature
+    pub fn sign_with_dst<M: AsRef<[u8]>>(
+        &self,
+        secret_key: &[u8],
+        message: M,
+        dst: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let message = message.as_ref();
+        let input = if self.binds_public_key() {
+            let public_key = self.derive_public_key(secret_key)?;
+            self.signing_input(message, &public_key)?
+        } else {
+            message.to_vec()
+        };
+        let hash_point = hash_to_curve::hash_to_curve(&input, dst)?;
+
+        self.sign_hashed(secret_key, hash_point)
+    }
+
+    /// Function to verify a signature created with `sign_with_dst` given a public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `signature`   - The signature bytes
+    /// * `message`     - The message bytes
+    /// * `dst`         - The domain separation tag
+    /// * `public_key`  - The public key bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    pub fn verify_with_dst<M: AsRef<[u8]>>(
+        &self,
+        signature: &[u8],
+        message: M,
+        dst: &[u8],
+        public_key: &[u8],
+    ) -> Result<(), Error> {
+        let message = message.as_ref();
+        let input = self.signing_input(message, public_key)?;
+        let hash_point = hash_to_curve::hash_to_curve(&input, dst)?;
+
+        self.verify_hashed(signature, hash_point, public_key)
+    }
+
+    /// Function to sign a message following the given ciphersuite.
+    ///
+    /// The message is hashed to G1 with `Ciphersuite::hash_to_curve`, using the map, the hash
+    /// function and the identifier of the ciphersuite as DST, prepending the public key for the message augmentation scheme. The
+    /// scheme configured in the engine is not used.
+    ///
+    /// # Arguments
+    ///
+    /// * `ciphersuite` - The ciphersuite, e.g. `ciphersuite::BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL`
+    /// * `secret_key`  - The secret key bytes
+    /// * `message`     - The message bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the signature
+    pub fn sign_with_ciphersuite<M: AsRef<[u8]>>(
+        &self,
+        ciphersuite: &Ciphersuite,
+        secret_key: &[u8],
+        message: M,
+    ) -> Result<Vec<u8>, Error> {
+        let message = message.as_ref();
+        let input = if ciphersuite.scheme == Scheme::MessageAugmentation {
+            let public_key = self.derive_public_key(secret_key)?;
+            augment_message(message, &public_key, true)?
+        } else {
+            message.to_vec()
+        };
+        let hash_point = ciphersuite.hash_to_curve(&input)?;
+
+        self.sign_hashed(secret_key, hash_point)
+    }
+
+    /// Function to verify a signature created with `sign_with_ciphersuite` given a public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `ciphersuite` - The ciphersuite used for signing
+    /// * `signature`   - The signature bytes
+    /// * `message`     - The message bytes
+    /// * `public_key`  - The public key bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    pub fn verify_with_ciphersuite<M: AsRef<[u8]>>(
+        &self,
+        ciphersuite: &Ciphersuite,
+        signature: &[u8],
+        message: M,
+        public_key: &[u8],
+    ) -> Result<(), Error> {
+        let message = message.as_ref();
+        let augment = ciphersuite.scheme == Scheme::MessageAugmentation;
+        let input = augment_message(message, public_key, augment)?;
+        let hash_point = ciphersuite.hash_to_curve(&input)?;
+
+        self.verify_hashed(signature, hash_point, public_key)
+    }
+
+    /// Function to sign a message already hashed to G1 given a secret key.
+    ///
+    /// The point can be a `HashedMessage` obtained with `hash`, or a point obtained with
+    /// `hash_to_try_and_increment` or `hash_to_curve`, which allows hashing once when signing or
+    /// verifying many signatures over the same message.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_key`  - The secret key bytes
+    /// * `hash_point`  - The message hashed to G1
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the signature
+    pub fn sign_hashed<H: Into<G1>>(
+        &self,
+        secret_key: &[u8],
+        hash_point: H,
+    ) -> Result<Vec<u8>, Error> {
+        // Multiply hash_point times secret_key --> Signature in G1
+        let private_key =
+            PrivateKey::from_slice(secret_key).map_err(|error| error.input("secret key"))?;
+        let signature = core::core_sign(private_key.0, hash_point.into());
+
+        // Return signature as compressed bytes
+        self.to_compressed_g1(signature)
+    }
+
+    /// Function to sign a message with a `Signer`, e.g. a key held by an HSM.
+    ///
+    /// The message is hashed as in `sign`, binding the public key of the signer when required by
+    /// the scheme, so the signature is the same as the one of `sign` with the secret key.
+    ///
+    /// # Arguments
+    ///
+    /// * `signer`  - The signer
+    /// * `message` - The message bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the signature
+    pub fn sign_with_signer<S: Signer + ?Sized, M: AsRef<[u8]>>(
+        &self,
+        signer: &S,
+        message: M,
+    ) -> Result<Vec<u8>, Error> {
+        let message = message.as_ref();
+        let hash_point = if self.binds_public_key() {
+            let public_key = signer.public_key()?;
+            self.hash_message(message, &public_key)?
+        } else {
+            self.hash_input(message)?
+        };
+
+        signer.sign_hashed(hash_point)
+    }
+
+    /// Function to verify a signature given a public key and the message hashed to G1 (e.g. a
+    /// `HashedMessage`).
+    ///
+    /// # Arguments
+    ///
+    /// * `signature`   - The signature bytes
+    /// * `hash_point`  - The message hashed to G1
+    /// * `public_key`  - The public key bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    pub fn verify_hashed<H: Into<G1>>(
+        &self,
+        signature: &[u8],
+        hash_point: H,
+        public_key: &[u8],
+    ) -> Result<(), Error> {
+        let public_key_point = self.public_key_point(public_key)?;
+        let signature_point = self.signature_point(signature)?;
+
+        core::core_verify(public_key_point, hash_point.into(), signature_point)
+    }
+
+    /// Function to hash a message to G1 once, as `sign` and `verify` do, so the result can be
+    /// passed to `sign_hashed`, `verify_hashed` and `batch_verify_hashed` for many signers of the
+    /// same message.
+    ///
+    /// # Arguments
+    ///
+    /// * `message` - The message bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `HashedMessage`; otherwise `Error::UnsupportedScheme` if the public
+    ///   key of every signer is hashed with the message (message augmentation or public key
+    ///   binding), or `Error` if hashing fails
+    pub fn hash<M: AsRef<[u8]>>(&self, message: M) -> Result<HashedMessage, Error> {
+        let message = message.as_ref();
+        if self.binds_public_key() {
+            return Err(Error::UnsupportedScheme);
+        }
+
+        Ok(HashedMessage(self.hash_input(message)?))
+    }
+
+    /// Function to verify a signature with a prepared public key (see `PublicKey::prepare`).
+    ///
+    /// The result is the same as the one of `verify` with the compressed public key, but the
+    /// public key is neither decompressed nor checked to be in the subgroup again.
+    ///
+    /// # Arguments
+    ///
+    /// * `signature`   - The signature bytes
+    /// * `message`     - The message bytes
+    /// * `public_key`  - The prepared public key
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    pub fn verify_prepared<M: AsRef<[u8]>>(
+        &self,
+        signature: &[u8],
+        message: M,
+        public_key: &PreparedPublicKey,
+    ) -> Result<(), Error> {
+        let message = message.as_ref();
+        if self.identity_policy == IdentityPolicy::Reject && public_key.point.is_zero() {
+            return Err(Error::IdentityPublicKey);
+        }
+        let hash_point = self.hash_message(message, &public_key.compressed)?;
+        let signature_point = self.signature_point(signature)?;
+
+        core::core_verify(public_key.point, hash_point, signature_point)
+    }
+
+    /// Function to decode a public key to verify, applying the identity policy.
+    fn public_key_point(&self, public_key: &[u8]) -> Result<G2, Error> {
+        PublicKey::from_compressed_with_policy(public_key, self.identity_policy)
+            .map(|public_key| public_key.0)
+            .map_err(|error| error.input("public key"))
+    }
+
+    /// Function to decode a signature to verify, applying the identity policy.
+    fn signature_point(&self, signature: &[u8]) -> Result<G1, Error> {
+        g1_from_compressed_with_policy(signature, self.identity_policy)
+            .map_err(|error| error.input("signature"))
+    }
+
+    /// Function to convert `G1` point into compressed form (`0x02` if Y is even and `0x03` if Y is odd).
+    ///
+    /// # Arguments
+    ///
+    /// * `point` - A `G1` point.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a `Vec<u8>` with the compressed `G1` point.
+    fn to_compressed_g1(&self, point: G1) -> Result<Vec<u8>, Error> {
+        g1_to_compressed(point)
+    }
+
+    /// Function to get the digest given some input data using the hash function `D`.
+    ///
+    /// # Arguments
+    ///
+    /// * `data` - A slice containing the input data.
+    ///
+    /// # Returns
+    ///
+    /// * The 32-byte digest as a slice.
+    fn calculate_hash(&self, bytes: &[u8]) -> [u8; 32] {
+        let mut hash = [0; 32];
+        hash.copy_from_slice(&D::digest(bytes));
+
+        hash
+    }
+}
+
+/// Function to validate the encoding of a secret key without creating a `PrivateKey`.
+///
+/// A secret key is canonical if it is 32 big endian bytes encoding a nonzero integer smaller than
+/// the order of the group. Keys that are not canonical are rejected by the functions taking secret
+/// key bytes; `PrivateKey::from_slice_reduced` can be used instead to reduce them explicitly.
+///
+/// # Arguments
+///
+/// * `secret_key` - The secret key bytes
+///
+/// # Returns
+///
+/// * If successful, `Ok(())`; otherwise `Error::InvalidLength` if the key is not 32 bytes long,
+///   `Error::NonCanonicalSecretKey` if it is not smaller than the order of the group or
+///   `Error::ZeroSecretKey` if it is zero
+pub fn validate_secret_key(secret_key: &[u8]) -> Result<(), Error> {
+    PrivateKey::from_slice(secret_key).map(|_| ())
+}
+
+/// Function to validate the encoding of a public key without verifying any signature (see
+/// `PublicKey::validate`).
+///
+/// # Arguments
+///
+/// * `public_key` - The public key bytes (a point in G2 in compressed format)
+///
+/// # Returns
+///
+/// * If successful, `Ok(())`; otherwise `Error`
+pub fn validate_public_key(public_key: &[u8]) -> Result<(), Error> {
+    PublicKey::from_compressed(public_key)?.validate()
+}
+
+/// Number of points decompressed and added by every task of the parallel aggregation.
+#[cfg(feature = "parallel")]
+const AGGREGATION_CHUNK_SIZE: usize = 256;
+
+/// Function to decompress and add points, in parallel chunks with the `parallel` feature.
+///
+/// # Arguments
+///
+/// * `points`      - The compressed points
+/// * `decompress`  - The function decompressing and validating a point
+///
+/// # Returns
+///
+/// * If successful, the sum of the points; otherwise the error of the first invalid point, with
+///   its index (`Error::InvalidItem`)
+fn aggregate_points<T, F>(points: &[&[u8]], decompress: F) -> Result<T, Error>
+where
+    T: Group,
+    F: Fn(&[u8]) -> Result<T, Error> + Sync,
+{
+    let sum_chunk = |offset: usize, chunk: &[&[u8]]| {
+        chunk
+            .iter()
+            .enumerate()
+            .try_fold(T::zero(), |sum, (index, &point)| {
+                Ok(sum + decompress(point).map_err(|error| error.item(offset + index))?)
+            })
+    };
+
+    #[cfg(not(feature = "parallel"))]
+    return sum_chunk(0, points);
+
+    #[cfg(feature = "parallel")]
+    {
+        use rayon::prelude::*;
+
+        // The partial sums are collected in order, so the first invalid point is reported
+        let sums: Vec<Result<T, Error>> = points
+            .par_chunks(AGGREGATION_CHUNK_SIZE)
+            .enumerate()
+            .map(|(index, chunk)| sum_chunk(index * AGGREGATION_CHUNK_SIZE, chunk))
+            .collect();
+
+        sums.into_iter()
+            .try_fold(T::zero(), |sum, partial| Ok(sum + partial?))
+    }
+}
+
+/// Function to compare two integers, given as little endian 128-bit limbs of the same length, in
+/// constant time.
+///
+/// # Returns
+///
+/// * A `Choice` for whether `a` is greater than `b`, and a `Choice` for whether they are equal.
+fn ct_compare(a: &[u128], b: &[u128]) -> (Choice, Choice) {
+    let mut greater = Choice::from(0);
+    let mut equal = Choice::from(1);
+    for (a, b) in a.iter().rev().zip(b.iter().rev()) {
+        // `subtle` compares at most 64-bit integers without the `i128` feature
+        for &shift in &[64, 0] {
+            let (a, b) = ((a >> shift) as u64, (b >> shift) as u64);
+            greater |= equal & a.ct_gt(&b);
+            equal &= a.ct_eq(&b);
+        }
+    }
+
+    (greater, equal)
+}
+
+/// Function to convert `G1` point into compressed form (`0x02` if Y is even and `0x03` if Y is odd).
+///
+/// # Arguments
+///
+/// * `point` - A `G1` point.
+///
+/// # Returns
+///
+/// * If successful, a `Vec<u8>` with the compressed `G1` point.
+fn g1_to_compressed(point: G1) -> Result<Vec<u8>, Error> {
+    Ok(g1_to_compressed_array(point)?.to_vec())
+}
+
+/// Function to convert `G1` point into compressed form without allocating (see
+/// `g1_to_compressed`).
+///
+/// # Arguments
+///
+/// * `point` - A `G1` point.
+///
+/// # Returns
+///
+/// * If successful, the 33-byte compressed `G1` point.
+fn g1_to_compressed_array(point: G1) -> Result<[u8; 33], Error> {
+    // The point at infinity is encoded as all zeros
+    if point.is_zero() {
+        return Ok([0u8; 33]);
+    }
+    // From Jacobian to Affine first!
+    let affine_coords = AffineG1::from_jacobian(point).ok_or(Error::PointInJacobian)?;
+    // Get X coordinate
+    let x = Fq::into_u256(affine_coords.x());
+    // Get Y coordinate
+    let y = Fq::into_u256(affine_coords.y());
+    // Get parity of Y
+    let parity = y.get_bit(0).ok_or(Error::IndexOutOfBounds)?;
+
+    let mut result = [0u8; 33];
+    // 0x02 or 0x03 depending on parity
+    result[0] = if parity { 3 } else { 2 };
+    // Take x as big endian
+    x.to_big_endian(&mut result[1..])?;
+
+    Ok(result)
+}
+
+/// Function to create a `G1` point from its compressed form, rejecting non-canonical encodings.
+///
+/// # Arguments
+///
+/// * `bytes` - The 33-byte compressed `G1` point.
+///
+/// # Returns
+///
+/// * If successful, the `G1` point; otherwise `Error::TrailingBytes` if there are bytes after the
+///   point, or `Error::NonCanonicalCoordinate` if X is not smaller than the field modulus
+fn g1_from_compressed(bytes: &[u8]) -> Result<G1, Error> {
+    g1_from_compressed_with_policy(bytes, IdentityPolicy::Reject)
+}
+
+/// Function to create a `G1` point from its compressed form, accepting the point at infinity
+/// (all zeros) depending on the policy (see `g1_from_compressed`).
+///
+/// # Arguments
+///
+/// * `bytes`  - The 33-byte compressed `G1` point.
+/// * `policy` - Whether the point at infinity is accepted.
+///
+/// # Returns
+///
+/// * If successful, the `G1` point; otherwise `Error`
+fn g1_from_compressed_with_policy(bytes: &[u8], policy: IdentityPolicy) -> Result<G1, Error> {
+    check_length(bytes, 33)?;
+    if is_identity(bytes) {
+        return match policy {
+            IdentityPolicy::Accept => Ok(G1::zero()),
+            IdentityPolicy::Reject => Err(Error::IdentitySignature),
+        };
+    }
+    if bytes[0] != 2 && bytes[0] != 3 {
+        return Err(Error::InvalidEncoding);
+    }
+    fq_from_slice(&bytes[1..33])?;
+
+    Ok(G1::from_compressed(bytes)?)
+}
+
+/// Function to create a `G2` point from its compressed form, rejecting non-canonical encodings.
+///
+/// X is encoded as the 512-bit integer `imaginary * p + real`, so besides the real part being
+/// reduced, the imaginary part (the quotient) has to be smaller than the field modulus.
+///
+/// # Arguments
+///
+/// * `bytes` - The 65-byte compressed `G2` point.
+///
+/// # Returns
+///
+/// * If successful, the `G2` point; otherwise `Error::TrailingBytes` if there are bytes after the
+///   point, or `Error::G2CoordinateOutOfRange` if the imaginary part of X is out of range
+fn g2_from_compressed(bytes: &[u8]) -> Result<G2, Error> {
+    check_length(bytes, 65)?;
+    if bytes[0] != 10 && bytes[0] != 11 {
+        return Err(Error::InvalidEncoding);
+    }
+    let modulus = Fq::modulus();
+    match arith::U512::from_slice(&bytes[1..65])?.divrem(&modulus) {
+        (Some(imaginary), _) if imaginary < modulus => {}
+        _ => return Err(Error::G2CoordinateOutOfRange),
+    }
+
+    Ok(G2::from_compressed(bytes)?)
+}
+
+/// Function to create a base field element from 32 big endian bytes, rejecting values which are
+/// not smaller than the field modulus.
+///
+/// # Arguments
+///
+/// * `bytes` - The 32 big endian bytes.
+///
+/// # Returns
+///
+/// * If successful, the field element; otherwise `Error::NonCanonicalCoordinate`
+fn fq_from_slice(bytes: &[u8]) -> Result<Fq, Error> {
+    Fq::from_u256(arith::U256::from_slice(bytes)?).map_err(|_| Error::NonCanonicalCoordinate)
+}
+
+/// Function to check whether an encoding is the one of the point at infinity (all zeros).
+fn is_identity(bytes: &[u8]) -> bool {
+    bytes.iter().all(|&byte| byte == 0)
+}
+
+/// Function to check that an encoding has the expected length.
+///
+/// # Arguments
+///
+/// * `bytes` - The encoding.
+/// * `length` - The expected length.
+///
+/// # Returns
+///
+/// * If successful, `Ok(())`; otherwise `Error::TrailingBytes` if the encoding is longer than
+///   expected, or `Error::InvalidLength` if it is shorter
+fn check_length(bytes: &[u8], length: usize) -> Result<(), Error> {
+    match bytes.len().cmp(&length) {
+        Ordering::Greater => Err(Error::TrailingBytes),
+        Ordering::Less => Err(Error::InvalidLength),
+        Ordering::Equal => Ok(()),
+    }
+}
+
+/// Function to convert a public key from compressed (65 bytes) to uncompressed format (128 bytes,
+/// see `PublicKey::from_uncompressed`).
+///
+/// # Arguments
+///
+/// * `public_key` - The compressed public key bytes
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the uncompressed public key; otherwise `Error`
+pub fn public_key_to_uncompressed(public_key: &[u8]) -> Result<Vec<u8>, Error> {
+    PublicKey::from_compressed(public_key)?.to_uncompressed()
+}
+
+/// Function to convert a public key from uncompressed (128 bytes) to compressed format (65 bytes).
+///
+/// # Arguments
+///
+/// * `public_key` - The uncompressed public key bytes
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the compressed public key; otherwise `Error`
+pub fn public_key_from_uncompressed(public_key: &[u8]) -> Result<Vec<u8>, Error> {
+    PublicKey::from_uncompressed(public_key)?.to_compressed()
+}
+
+/// Function to convert a signature from compressed (33 bytes) to uncompressed format (64 bytes,
+/// X followed by Y in 32 big endian bytes each, as expected by the bn256 precompiles of Ethereum).
+///
+/// # Arguments
+///
+/// * `signature` - The compressed signature bytes
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the uncompressed signature; otherwise `Error`
+pub fn signature_to_uncompressed(signature: &[u8]) -> Result<Vec<u8>, Error> {
+    g1_to_uncompressed(g1_from_compressed(signature)?)
+}
+
+/// Function to convert a signature from uncompressed (64 bytes) to compressed format (33 bytes).
+///
+/// # Arguments
+///
+/// * `signature` - The uncompressed signature bytes
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the compressed signature; otherwise `Error`
+pub fn signature_from_uncompressed(signature: &[u8]) -> Result<Vec<u8>, Error> {
+    g1_to_compressed(g1_from_uncompressed(signature)?)
+}
+
+/// Function to convert `G1` point into uncompressed form (X followed by Y).
+///
+/// # Arguments
+///
+/// * `point` - A `G1` point.
+///
+/// # Returns
+///
+/// * If successful, a `Vec<u8>` with the 64-byte uncompressed `G1` point.
+fn g1_to_uncompressed(point: G1) -> Result<Vec<u8>, Error> {
+    // The point at infinity is encoded as all zeros
+    if point.is_zero() {
+        return Ok(vec![0u8; 64]);
+    }
+    let affine_coords = AffineG1::from_jacobian(point).ok_or(Error::PointInJacobian)?;
+    let mut result = [0u8; 64];
+    affine_coords
+        .x()
+        .into_u256()
+        .to_big_endian(&mut result[0..32])?;
+    affine_coords
+        .y()
+        .into_u256()
+        .to_big_endian(&mut result[32..64])?;
+
+    Ok(result.to_vec())
+}
+
+/// Function to create a `G1` point from its uncompressed form, checking it is on the curve.
+///
+/// # Arguments
+///
+/// * `bytes` - The 64-byte uncompressed `G1` point.
+///
+/// # Returns
+///
+/// * If successful, the `G1` point.
+fn g1_from_uncompressed(bytes: &[u8]) -> Result<G1, Error> {
+    check_length(bytes, 64)?;
+    if is_identity(bytes) {
+        return Err(Error::IdentitySignature);
+    }
+    let x = fq_from_slice(&bytes[0..32])?;
+    let y = fq_from_slice(&bytes[32..64])?;
+
+    Ok(AffineG1::new(x, y)?.into())
+}
+
+/// Function to prepend the compressed public key to a message if `augment` is set.
+///
+/// # Arguments
+///
+/// * `message`     - The message bytes
+/// * `public_key`  - The compressed public key bytes of the signer
+/// * `augment`     - Whether the public key should be prepended
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the input to be hashed.
+fn augment_message(message: &[u8], public_key: &[u8], augment: bool) -> Result<Vec<u8>, Error> {
+    if augment {
+        // Serialize again so that the same encoding is hashed by signers and verifiers
+        let public_key = PublicKey::from_compressed(public_key)?.to_compressed_array()?;
+        Ok([&public_key[..], message].concat())
+    } else {
+        Ok(message.to_vec())
+    }
+}
+
+/// Function to derive the coefficients `a_i = H(PK_i || PK_1 || ... || PK_n)` of a set of public keys.
+///
+/// # Arguments
+///
+/// * `public_keys` - The compressed public key bytes of every signer
+///
+/// # Returns
+///
+/// * If successful, a vector with the coefficient of every public key.
+fn delinearization_coefficients(public_keys: &[&[u8]]) -> Result<Vec<Fr>, Error> {
+    if public_keys.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+    // Serialize again so that every signer derives the same coefficients
+    let encoded: Vec<Vec<u8>> = public_keys
+        .iter()
+        .map(|&public_key| PublicKey::from_compressed(public_key)?.to_compressed())
+        .collect::<Result<_, Error>>()?;
+    let all_keys = encoded.concat();
+
+    encoded
+        .iter()
+        .map(|public_key| {
+            let input = [&public_key[..], &all_keys[..]].concat();
+            let coefficient =
+                hash_to_field::hash_to_field::<Fr, Sha256>(&input, DELINEARIZATION_DST, 1)?;
+            Ok(coefficient[0])
+        })
+        .collect()
+}
+
+/// The scalar used as private key
+pub struct PrivateKey(bn::Fr);
+
+/// The public key as point in G2
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PublicKey(bn::G2);
+
+/// The signature as point in G1
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature(bn::G1);
+
+/// A message hashed to G1 once, to be signed or verified by many signers (see `Bn256::hash`).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HashedMessage(bn::G1);
+
+/// A public key prepared for verifying many signatures (see `PublicKey::prepare`).
+///
+/// The `bn` library does not expose the precomputation of the Miller loop line functions, so the
+/// decompressed and subgroup-checked point is kept together with its compressed encoding, which is
+/// hashed with the message when the public key is bound.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PreparedPublicKey {
+    /// The validated point in G2
+    point: bn::G2,
+    /// The compressed encoding of the point
+    compressed: [u8; 65],
+}
+
+/// Encodings of points detected by `PublicKey::from_bytes` and `Signature::from_bytes`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PointFormat {
+    /// Compressed format (65 bytes for G2 and 33 bytes for G1)
+    Compressed,
+    /// Uncompressed format (128 bytes for G2 and 64 bytes for G1)
+    Uncompressed,
+    /// Compressed format with flags in the most significant bits (64 bytes for G2 and 32 bytes for
+    /// G1, see `flagged`)
+    Flagged,
+}
+
+/// Whether the point at infinity (the identity element) is accepted as a public key or signature.
+///
+/// The identity is encoded as all zeros in the compressed (65 and 33 bytes) and uncompressed
+/// (128 and 64 bytes) formats, as done by the bn256 precompiles of Ethereum. As the identity
+/// public key and signature verify any message, it is rejected by default.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum IdentityPolicy {
+    /// The identity is rejected with `Error::IdentityPublicKey` or `Error::IdentitySignature`.
+    #[default]
+    Reject,
+    /// The identity is accepted, e.g. for empty aggregates.
+    Accept,
+}
+
+impl ::core::fmt::Display for PointFormat {
+    fn fmt(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+        formatter.write_str(match self {
+            PointFormat::Compressed => "compressed",
+            PointFormat::Uncompressed => "uncompressed",
+            PointFormat::Flagged => "flagged",
+        })
+    }
+}
+
+impl PrivateKey {
+    /// Function to create a `PrivateKey` from its canonical encoding (see `validate_secret_key`).
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The secret key bytes (32 big endian bytes)
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `PrivateKey`; otherwise `Error::InvalidLength`,
+    ///   `Error::NonCanonicalSecretKey` or `Error::ZeroSecretKey`
+    pub fn from_slice(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != 32 {
+            return Err(Error::InvalidLength);
+        }
+        let scalar =
+            Fr::new(arith::U256::from_slice(bytes)?).ok_or(Error::NonCanonicalSecretKey)?;
+        if scalar.is_zero() {
+            return Err(Error::ZeroSecretKey);
+        }
+
+        Ok(PrivateKey(scalar))
+    }
+
+    /// Function to create a `PrivateKey` from bytes reduced modulo the order of the group, for
+    /// callers that derive keys from arbitrary bytes (e.g. the output of a hash function).
+    ///
+    /// Values are reduced as big endian integers, so a key encoded canonically is left unchanged.
+    /// The reduction of a uniformly random input is only close to uniform if it is at least 48
+    /// bytes long.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The bytes to reduce (at most 64 bytes)
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `PrivateKey`; otherwise `Error::InvalidLength` or
+    ///   `Error::ZeroSecretKey` if the bytes reduce to zero
+    pub fn from_slice_reduced(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() > 64 {
+            return Err(Error::InvalidLength);
+        }
+        let mut wide = [0u8; 64];
+        wide[64 - bytes.len()..].copy_from_slice(bytes);
+        let scalar = Fr::interpret(&wide);
+        wide.zeroize();
+        if scalar.is_zero() {
+            return Err(Error::ZeroSecretKey);
+        }
+
+        Ok(PrivateKey(scalar))
+    }
+
+    /// Function to sample a uniformly distributed nonzero private key.
+    ///
+    /// Every candidate is obtained by reducing 48 random bytes modulo the group order, so the bias
+    /// is negligible, and zero is rejected.
+    ///
+    /// # Arguments
+    ///
+    /// * `rng` - A cryptographically secure random number generator
+    ///
+    /// # Returns
+    ///
+    /// * The `PrivateKey`
+    #[cfg(feature = "rand")]
+    pub fn random<R: CryptoRng + RngCore>(rng: &mut R) -> Self {
+        loop {
+            let mut bytes = [0u8; 48];
+            rng.fill_bytes(&mut bytes);
+            let scalar = Fr::from_uniform_bytes(&bytes).expect("48 bytes are always a valid input");
+            bytes.zeroize();
+            if !scalar.is_zero() {
+                return PrivateKey(scalar);
+            }
+        }
+    }
+
+    /// Function to serialize the private key as 32 big endian bytes, as expected by the functions
+    /// taking secret key bytes.
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        let mut bytes = [0u8; 32];
+        self.0.into_u256().to_big_endian(&mut bytes)?;
+        let secret_key = bytes.to_vec();
+        bytes.zeroize();
+
+        Ok(secret_key)
+    }
+
+    /// Function to save the private key to a file encrypted with a password (see `keyfile`).
+    ///
+    /// The salt and the nonce are drawn from the random number generator of the operating system.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`     - The path of the file, which is overwritten if it exists
+    /// * `password` - The password
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    #[cfg(feature = "keyfile")]
+    pub fn save_encrypted<P: AsRef<Path>>(&self, path: P, password: &str) -> Result<(), Error> {
+        let mut salt = [0u8; keyfile::SALT_LENGTH];
+        let mut nonce = [0u8; keyfile::NONCE_LENGTH];
+        OsRng.fill_bytes(&mut salt);
+        OsRng.fill_bytes(&mut nonce);
+        let secret_key = Zeroizing::new(self.to_bytes()?);
+        let file = keyfile::encrypt(
+            &secret_key,
+            password,
+            keyfile::KeyFileParams::default(),
+            &salt,
+            &nonce,
+        )?;
+
+        std::fs::write(path, file).map_err(|_| Error::FileError)
+    }
+
+    /// Function to load a private key from a file saved with `save_encrypted`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`     - The path of the file
+    /// * `password` - The password
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `PrivateKey`; otherwise `Error`
+    #[cfg(feature = "keyfile")]
+    pub fn load_encrypted<P: AsRef<Path>>(path: P, password: &str) -> Result<Self, Error> {
+        let file = std::fs::read(path).map_err(|_| Error::FileError)?;
+        let secret_key = Zeroizing::new(keyfile::decrypt(&file, password)?);
+
+        PrivateKey::from_slice(&secret_key)
+    }
+
+    /// Function to add a tweak to the private key, deriving a related private key whose
+    /// signatures verify under the public key tweaked with `PublicKey::tweak_add`.
+    ///
+    /// # Arguments
+    ///
+    /// * `tweak` - The tweak scalar bytes (32 bytes)
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the tweaked `PrivateKey`; otherwise `Error`, which is
+    ///   `Error::ZeroSecretKey` if the tweak is the negation of the private key
+    pub fn tweak_add(&self, tweak: &[u8]) -> Result<Self, Error> {
+        let tweaked = self.0 + Fr::from_slice(tweak)?;
+        if tweaked.is_zero() {
+            return Err(Error::ZeroSecretKey);
+        }
+
+        Ok(PrivateKey(tweaked))
+    }
+
+    /// Function to derive the bn256 public key from the private key.
+    fn derive_public_key(self) -> Result<PublicKey, Error> {
+        let PrivateKey(sk) = self;
+
+        Ok(PublicKey(core::sk_to_pk(sk)))
+    }
+}
+
+impl Zeroize for PrivateKey {
+    fn zeroize(&mut self) {
+        // `Fr` does not implement `Zeroize`, but zero is represented with zero bytes, so a volatile
+        // write of `Fr::zero()` wipes the scalar without being optimized away
+        unsafe { ptr::write_volatile(&mut self.0, Fr::zero()) };
+        atomic::compiler_fence(atomic::Ordering::SeqCst);
+    }
+}
+
+impl Drop for PrivateKey {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for PrivateKey {}
+
+impl ConstantTimeEq for PrivateKey {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        let (_, equal) = ct_compare(&self.0.into_u256().0, &other.0.into_u256().0);
+
+        equal
+    }
+}
+
+impl PartialEq for PrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for PrivateKey {}
+
+/// A private key together with its compressed public key, which is derived only once.
+pub struct KeyPair {
+    /// The private key
+    private_key: PrivateKey,
+    /// The compressed public key (a point in G2)
+    public_key: Vec<u8>,
+}
+
+impl KeyPair {
+    /// Function to create a `KeyPair` from a private key, deriving its public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `private_key` - The private key
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `KeyPair`; otherwise `Error`
+    pub fn new(private_key: PrivateKey) -> Result<Self, Error> {
+        let public_key = PublicKey(core::sk_to_pk(private_key.0)).to_compressed()?;
+
+        Ok(KeyPair {
+            private_key,
+            public_key,
+        })
+    }
+
+    /// Function to create a `KeyPair` from secret key bytes, rejecting the zero scalar.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_key` - The secret key bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `KeyPair`; otherwise `Error`
+    pub fn from_secret_key(secret_key: &[u8]) -> Result<Self, Error> {
+        KeyPair::new(PrivateKey::from_slice(secret_key)?)
+    }
+
+    /// Function to get the compressed public key.
+    pub fn public(&self) -> &[u8] {
+        &self.public_key
+    }
+
+    /// Function to get the private key.
+    pub fn private_key(&self) -> &PrivateKey {
+        &self.private_key
+    }
+
+    /// Function to sign a message with the configuration (scheme and hash function) of a `Bn256`
+    /// instance, reusing the cached public key when it is bound into the hash.
+    ///
+    /// The signature is the same as the one of `Bn256::sign` with the secret key.
+    ///
+    /// # Arguments
+    ///
+    /// * `bn256`   - The `Bn256` instance
+    /// * `message` - The message bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the signature
+    pub fn sign<D: Digest<OutputSize = U32>, M: AsRef<[u8]>>(
+        &self,
+        bn256: &Bn256<D>,
+        message: M,
+    ) -> Result<Vec<u8>, Error> {
+        let message = message.as_ref();
+        // The cached public key is already in canonical form, so it is prepended as is
+        let hash_point = if bn256.binds_public_key() {
+            bn256.hash_input(&[&self.public_key[..], message].concat())?
+        } else {
+            bn256.hash_input(message)?
+        };
+
+        g1_to_compressed(core::core_sign(self.private_key.0, hash_point))
+    }
+}
+
+impl PublicKey {
+    /// Function to convert a complex coordinate (`Fq2`) to `U512`.
+    pub fn to_u512(&self, coord: Fq2) -> arith::U512 {
+        let c0: arith::U256 = (coord.real()).into_u256();
+        let c1: arith::U256 = (coord.imaginary()).into_u256();
+
+        arith::U512::new(&c1, &c0, &Fq::modulus())
+    }
+
+    /// Function to create a `PublicKey` from bytes in any supported format, which is detected by
+    /// length: compressed (65 bytes), uncompressed (128 bytes) or flagged (64 bytes).
+    ///
+    /// The public key is validated with `core::key_validate`.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `PublicKey`; otherwise `Error::InvalidLength` if no format has the
+    ///   length of `bytes`, or `Error::InvalidPointEncoding` with the detected format
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let (format, public_key) = match bytes.len() {
+            65 => (PointFormat::Compressed, PublicKey::from_compressed(bytes)),
+            128 => (
+                PointFormat::Uncompressed,
+                PublicKey::from_uncompressed(bytes),
+            ),
+            64 => (
+                PointFormat::Flagged,
+                flagged::public_key_from_flagged(bytes)
+                    .and_then(|public_key| PublicKey::from_compressed(&public_key)),
+            ),
+            _ => return Err(Error::InvalidLength),
+        };
+
+        public_key.map_err(|_| Error::InvalidPointEncoding(format))
+    }
+
+    /// Function to create a `PublicKey` from bytes representing a G2 point in compressed format.
+    ///
+    /// The public key is validated with `core::key_validate`.
+    pub fn from_compressed(bytes: &[u8]) -> Result<Self, Error> {
+        PublicKey::from_compressed_with_policy(bytes, IdentityPolicy::Reject)
+    }
+
+    /// Function to create a `PublicKey` from bytes representing a G2 point in compressed format,
+    /// accepting the point at infinity (65 zero bytes) depending on the policy.
+    ///
+    /// Any other public key is validated with `core::key_validate`.
+    pub fn from_compressed_with_policy(
+        bytes: &[u8],
+        policy: IdentityPolicy,
+    ) -> Result<Self, Error> {
+        check_length(bytes, 65)?;
+        if is_identity(bytes) {
+            return match policy {
+                IdentityPolicy::Accept => Ok(PublicKey(G2::zero())),
+                IdentityPolicy::Reject => Err(Error::IdentityPublicKey),
+            };
+        }
+        let uncompressed = g2_from_compressed(bytes)?;
+        core::key_validate(uncompressed)?;
+
+        Ok(PublicKey(uncompressed))
+    }
+
+    /// Function to create a `PublicKey` from bytes representing a G2 point in uncompressed format
+    /// (the real and imaginary parts of X followed by the ones of Y, 32 big endian bytes each).
+    ///
+    /// The public key is validated with `core::key_validate`.
+    pub fn from_uncompressed(bytes: &[u8]) -> Result<Self, Error> {
+        check_length(bytes, 128)?;
+        if is_identity(bytes) {
+            return Err(Error::IdentityPublicKey);
+        }
+        let x = Fq2::new(
+            fq_from_slice(&bytes[0..32])?,
+            fq_from_slice(&bytes[32..64])?,
+        );
+        let y = Fq2::new(
+            fq_from_slice(&bytes[64..96])?,
+            fq_from_slice(&bytes[96..128])?,
+        );
+        let pub_key = AffineG2::new(x, y)?.into();
+        core::key_validate(pub_key)?;
+
+        Ok(PublicKey(pub_key))
+    }
+
+    /// Function to validate the public key independently of any verification, e.g. when it is
+    /// registered.
+    ///
+    /// A public key is valid if it is a point of the twisted curve which is not the identity and
+    /// which belongs to the subgroup of prime order.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error::IdentityPublicKey` if the point is the
+    ///   identity, or `Error::InvalidGroupPoint` if it is not on the curve or not in the subgroup
+    pub fn validate(&self) -> Result<(), Error> {
+        let affine = AffineG2::from_jacobian(self.0).ok_or(Error::IdentityPublicKey)?;
+        AffineG2::new(affine.x(), affine.y())?;
+
+        core::key_validate(self.0)
+    }
+
+    /// Function to prepare the public key for verifying many signatures with
+    /// `Bn256::verify_prepared`, skipping the decompression and validation of every verification.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `PreparedPublicKey`; otherwise `Error`
+    pub fn prepare(&self) -> Result<PreparedPublicKey, Error> {
+        Ok(PreparedPublicKey {
+            point: self.0,
+            compressed: self.to_compressed_array()?,
+        })
+    }
+
+    /// Function to compute the fingerprint of the public key, the first bytes of the SHA-256
+    /// digest of its compressed encoding (see `fingerprint`).
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `Fingerprint`; otherwise `Error`
+    pub fn fingerprint(&self) -> Result<fingerprint::Fingerprint, Error> {
+        Ok(fingerprint::fingerprint_compressed(&self.to_compressed()?))
+    }
+
+    /// Function to add a tweak point to the public key, matching `PrivateKey::tweak_add`.
+    ///
+    /// The tweak point of a tweak scalar is its public key (`Bn256::derive_public_key`), so that
+    /// whoever knows only the tweak point and the public key can compute the tweaked public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `tweak_point` - The tweak point bytes (a point in G2 in compressed format)
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the tweaked `PublicKey`; otherwise `Error`, which is
+    ///   `Error::IdentityPublicKey` if the tweak point is the negation of the public key
+    pub fn tweak_add(&self, tweak_point: &[u8]) -> Result<Self, Error> {
+        let tweak_point = g2_from_compressed(tweak_point)?;
+        core::key_validate(tweak_point)?;
+        let tweaked = self.0 + tweak_point;
+        core::key_validate(tweaked)?;
+
+        Ok(PublicKey(tweaked))
+    }
+
+    /// Function to serialize the `PublicKey` to vector of bytes in compressed format.
+    pub fn to_compressed(&self) -> Result<Vec<u8>, Error> {
+        Ok(self.to_compressed_array()?.to_vec())
+    }
+
+    /// Function to serialize the `PublicKey` in compressed format without allocating.
+    pub fn to_compressed_array(&self) -> Result<[u8; 65], Error> {
+        // The point at infinity is encoded as all zeros
+        if self.0.is_zero() {
+            return Ok([0u8; 65]);
+        }
+        let modulus = Fq::modulus();
+        // From Jacobian to Affine first!
+        let affine_coords = AffineG2::from_jacobian(self.0).ok_or(Error::PointInJacobian)?;
+
+        // Get X real coordinate
+        let x_real = Fq::into_u256(affine_coords.x().real());
+        // Get X imaginary coordinate
+        let x_imaginary = Fq::into_u256(affine_coords.x().imaginary());
+        // Get Y and get sign
+        let y = affine_coords.y();
+        let y_neg = -y;
+        let (greater, _) = ct_compare(&self.to_u512(y).0, &self.to_u512(y_neg).0);
+        let sign = u8::conditional_select(&0x0a, &0x0b, greater);
+
+        // To U512 and its compressed representation
+        let compressed = arith::U512::new(&x_imaginary, &x_real, &modulus);
+
+        // Result = sign || compressed
+        let mut result = [0u8; 65];
+        result[0] = sign;
+        for (l, i) in (0..4).rev().zip((0..4).map(|i| 1 + i * 16)) {
+            BigEndian::write_u128(&mut result[i..], compressed.0[l]);
+        }
+
+        Ok(result)
+    }
+
+    /// Function to serialize the `PublicKey` to vector of bytes in uncompressed format.
+    pub fn to_uncompressed(&self) -> Result<Vec<u8>, Error> {
+        // The point at infinity is encoded as all zeros
+        if self.0.is_zero() {
+            return Ok(vec![0u8; 128]);
+        }
+        // From Jacobian to Affine first!
+        let affine_coords = AffineG2::from_jacobian(self.0).ok_or(Error::PointInJacobian)?;
+        let mut result: [u8; 32 * 4] = [0; (4 * 32)];
+
+        // Get X real coordinate
+        Fq::into_u256(affine_coords.x().real()).to_big_endian(&mut result[0..32])?;
+
+        // Get X imaginary coordinate
+        Fq::into_u256(affine_coords.x().imaginary()).to_big_endian(&mut result[32..64])?;
+
+        // Get Y real coordinate
+        Fq::into_u256(affine_coords.y().real()).to_big_endian(&mut result[64..96])?;
+
+        // Get Y imaginary coordinate
+        Fq::into_u256(affine_coords.y().imaginary()).to_big_endian(&mut result[96..128])?;
+
+        Ok(result.to_vec())
+    }
+}
+
+impl Signature {
+    /// Function to create a `Signature` from bytes in any supported format, which is detected by
+    /// length: compressed (33 bytes), uncompressed (64 bytes) or flagged (32 bytes).
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `Signature`; otherwise `Error::InvalidLength` if no format has the
+    ///   length of `bytes`, or `Error::InvalidPointEncoding` with the detected format
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        let (format, signature) = match bytes.len() {
+            33 => (PointFormat::Compressed, Signature::from_compressed(bytes)),
+            64 => (
+                PointFormat::Uncompressed,
+                g1_from_uncompressed(bytes).map(Signature),
+            ),
+            32 => (
+                PointFormat::Flagged,
+                flagged::signature_from_flagged(bytes)
+                    .and_then(|signature| Signature::from_compressed(&signature)),
+            ),
+            _ => return Err(Error::InvalidLength),
+        };
+
+        signature.map_err(|_| Error::InvalidPointEncoding(format))
+    }
+
+    /// Function to create a `Signature` from bytes representing a G1 point in compressed format.
+    pub fn from_compressed(bytes: &[u8]) -> Result<Self, Error> {
+        Ok(Signature(g1_from_compressed(bytes)?))
+    }
+
+    /// Function to create a `Signature` from bytes representing a G1 point in compressed format,
+    /// accepting the point at infinity (33 zero bytes) depending on the policy.
+    pub fn from_compressed_with_policy(
+        bytes: &[u8],
+        policy: IdentityPolicy,
+    ) -> Result<Self, Error> {
+        Ok(Signature(g1_from_compressed_with_policy(bytes, policy)?))
+    }
+
+    /// Function to serialize the `Signature` to vector of bytes in compressed format.
+    pub fn to_compressed(&self) -> Result<Vec<u8>, Error> {
+        g1_to_compressed(self.0)
+    }
+
+    /// Function to serialize the `Signature` in compressed format without allocating.
+    pub fn to_compressed_array(&self) -> Result<[u8; 33], Error> {
+        g1_to_compressed_array(self.0)
+    }
+}
+
+impl ConstantTimeEq for PublicKey {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        match (self.to_compressed_array(), other.to_compressed_array()) {
+            (Ok(a), Ok(b)) => a[..].ct_eq(&b[..]),
+            _ => Choice::from(0),
+        }
+    }
+}
+
+impl ConstantTimeEq for Signature {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        match (self.to_compressed_array(), other.to_compressed_array()) {
+            (Ok(a), Ok(b)) => a[..].ct_eq(&b[..]),
+            _ => Choice::from(0),
+        }
+    }
+}
+
+impl<D: Digest<OutputSize = U32>> MultiSignature<&[u8], &[u8], &[u8]> for Bn256<D> {
+    type Error = Error;
+
+    /// Function to derive public key (point in G2) given a secret key.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_key` - The secret key bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the public key
+    fn derive_public_key(&self, secret_key: &[u8]) -> Result<Vec<u8>, Error> {
+        let key = PrivateKey::from_slice(secret_key).map_err(|error| error.input("secret key"))?;
+        let public = key.derive_public_key()?;
+
+        public.to_compressed()
+    }
+
+    /// Function to sign a message given a private key (as a point in G1).
+    ///
+    /// # Arguments
+    ///
+    /// * `message`     - The message bytes
+    /// * `secret_key`  - The secret key bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the signature
+    fn sign<M: AsRef<[u8]>>(&self, secret_key: &[u8], message: M) -> Result<Vec<u8>, Self::Error> {
+        // 1. Hash_to_try_and_increment --> H(m) or H(PK||m) as point in G1 (only if it exists)
+        let hash_point = if self.binds_public_key() {
+            let public_key = self.derive_public_key(secret_key)?;
+            self.hash_message(message.as_ref(), &public_key)?
+        } else {
+            self.hash_input(message.as_ref())?
+        };
+
+        // 2. Multiply hash_point times secret_key and return the compressed signature
+        self.sign_hashed(secret_key, hash_point)
+    }
+
+    /// Function to verify a signature (point in G1) given a public key (point in G2).
+    ///
+    /// # Arguments
+    ///
+    /// * `signature`   - The signature bytes
+    /// * `message`     - The message to be signed
+    /// * `public_key`  - The public key bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    fn verify<M: AsRef<[u8]>>(
+        &self,
+        signature: &[u8],
+        message: M,
+        public_key: &[u8],
+    ) -> Result<(), Self::Error> {
+        let hash_point = self.hash_message(message.as_ref(), public_key)?;
+
+        self.verify_hashed(signature, hash_point, public_key)
+    }
+
+    /// Function to verify an aggregated signature of several messages by different signers.
+    ///
+    /// The rules of the selected scheme are enforced (see `aggregate_verify_set`): `Scheme::Basic` rejects repeated messages,
+    /// `Scheme::MessageAugmentation` hashes every message prepended with its public key and
+    /// `Scheme::ProofOfPossession` allows repeated messages. The check is performed as a single
+    /// batch of `n + 1` pairings: `e(H(m_1), PK_1) * ... * e(H(m_n), PK_n) = e(Signature, G2::one)`.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_keys` - The public key bytes of every signer
+    /// * `messages`    - The message signed by every signer (in the same order)
+    /// * `signature`   - The aggregated signature bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    fn aggregate_verify(
+        &self,
+        public_keys: &[&[u8]],
+        messages: &[&[u8]],
+        signature: &[u8],
+    ) -> Result<(), Self::Error> {
+        let set = SignatureSet::from_slices(public_keys, messages)?;
+
+        self.aggregate_verify_set(&set, signature)
+    }
+
+    /// Function to aggregate public keys (sum of points in G2).
+    ///
+    /// With the `parallel` feature, the public keys are decompressed and added in parallel chunks.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_keys`  - An array of public key bytes to be aggregated
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the aggregated public key
+    fn aggregate_public_keys(&self, public_keys: &[&[u8]]) -> Result<Vec<u8>, Self::Error> {
+        let aggregated = aggregate_points(public_keys, |compressed| {
+            PublicKey::from_compressed(compressed).map(|public_key| public_key.0)
+        })?;
+
+        PublicKey(aggregated).to_compressed()
+    }
+
+    /// Function to aggregate signatures (sum of points in G1).
+    ///
+    /// With the `parallel` feature, the signatures are decompressed and added in parallel chunks.
+    ///
+    /// # Arguments
+    ///
+    /// * `signatures`  - An array of signature bytes to be aggregated
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the aggregated signature
+    fn aggregate_signatures(&self, signatures: &[&[u8]]) -> Result<Vec<u8>, Self::Error> {
+        let aggregated = aggregate_points(signatures, g1_from_compressed)?;
+
+        self.to_compressed_g1(aggregated)
+    }
+}
+
+impl<D: Digest<OutputSize = U32>> crate::Bls for Bn256<D> {
+    type PrivateKey = PrivateKey;
+    type PublicKey = PublicKey;
+    type Signature = Signature;
+    type Scalar = Fr;
+    type PublicKeyPoint = G2;
+    type SignaturePoint = G1;
+    type Error = Error;
+
+    const PRIVATE_KEY_LENGTH: usize = 32;
+    const PUBLIC_KEY_LENGTH: usize = 65;
+    const SIGNATURE_LENGTH: usize = 33;
+    const CIPHERSUITE_ID: &'static [u8] = TRY_AND_INCREMENT_ID;
+
+    /// Function to derive the public key (point in G2) of a private key.
+    fn derive_public_key(&self, private_key: &PrivateKey) -> Result<PublicKey, Error> {
+        Ok(PublicKey(core::sk_to_pk(private_key.0)))
+    }
+
+    /// Function to sign a message, hashed as in `MultiSignature::sign`.
+    fn sign<M: AsRef<[u8]>>(
+        &self,
+        private_key: &PrivateKey,
+        message: M,
+    ) -> Result<Signature, Error> {
+        let hash_point = if self.binds_public_key() {
+            let public_key = PublicKey(core::sk_to_pk(private_key.0)).to_compressed_array()?;
+            self.hash_message(message.as_ref(), &public_key)?
+        } else {
+            self.hash_input(message.as_ref())?
+        };
+
+        Ok(Signature(core::core_sign(private_key.0, hash_point)))
+    }
+
+    /// Function to verify a signature, applying the identity policy as `MultiSignature::verify`.
+    fn verify<M: AsRef<[u8]>>(
+        &self,
+        signature: &Signature,
+        message: M,
+        public_key: &PublicKey,
+    ) -> Result<(), Error> {
+        if self.identity_policy == IdentityPolicy::Reject {
+            if public_key.0.is_zero() {
+                return Err(Error::IdentityPublicKey);
+            }
+            if signature.0.is_zero() {
+                return Err(Error::IdentitySignature);
+            }
+        }
+        let hash_point = self.hash_message(message.as_ref(), &public_key.to_compressed_array()?)?;
+
+        core::core_verify(public_key.0, hash_point, signature.0)
+    }
+
+    /// Function to verify an aggregated signature of several messages by different signers,
+    /// enforcing the rules of the selected scheme as `MultiSignature::aggregate_verify`.
+    fn aggregate_verify(
+        &self,
+        public_keys: &[PublicKey],
+        messages: &[&[u8]],
+        signature: &Signature,
+    ) -> Result<(), Error> {
+        let public_keys = public_keys
+            .iter()
+            .map(PublicKey::to_compressed_array)
+            .collect::<Result<Vec<_>, Error>>()?;
+        let public_keys: Vec<&[u8]> = public_keys
+            .iter()
+            .map(|public_key| &public_key[..])
+            .collect();
+        let set = SignatureSet::from_slices(&public_keys, messages)?;
+
+        self.aggregate_verify_set(&set, &signature.to_compressed_array()?)
+    }
+
+    /// Function to aggregate public keys (sum of points in G2).
+    fn aggregate_public_keys(&self, public_keys: &[PublicKey]) -> Result<PublicKey, Error> {
+        let points: Vec<G2> = public_keys.iter().map(|public_key| public_key.0).collect();
+
+        Ok(PublicKey(core::aggregate_public_keys(&points)))
+    }
+
+    /// Function to aggregate signatures (sum of points in G1).
+    fn aggregate_signatures(&self, signatures: &[Signature]) -> Result<Signature, Error> {
+        let points: Vec<G1> = signatures.iter().map(|signature| signature.0).collect();
+
+        Ok(Signature(core::aggregate_signatures(&points)))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    // Test vectors taken from https://asecuritysite.com/encryption/go_bn256. The public keys in G2 are changed in order in the website, i.e., imaginary goes first.
+    // In order to construct the test vectors we need to do the following
+    // Get the modulus of Fq
+    // Get the components (real, imaginary) of x and y
+    // perform (imaginary*modulus) +  real
+    // Compress with 0x0a or 0x0b depending on the value of y
+    #[test]
     fn test_compressed_public_key_1() {
         let compressed = hex::decode("0a023aed31b5a9e486366ea9988b05dba469c6206e58361d9c065bbea7d928204a761efc6e4fa08ed227650134b52c7f7dd0463963e8a4bf21f4899fe5da7f984a").unwrap();
         let public_key = PublicKey::from_compressed(&compressed).unwrap();
@@ -377,227 +2300,1519 @@ mod test {
     }
 
     #[test]
-    fn test_to_public_key_1() {
-        let secret_key =
-            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
-                .unwrap();
-        let expected = hex::decode(
-            "28fe26becbdc0384aa67bf734d08ec78ecc2330f0aa02ad9da00f56c37907f78\
-             2cd080d897822a95a0fb103c54f06e9bf445f82f10fe37efce69ecb59514abc8\
-             237faeb0351a693a45d5d54aa9759f52a71d76edae2132616d6085a9b2228bf9\
-             0f46bd1ef47552c3089604c65a3e7154e3976410be01149b60d5a41a6053e6c2",
-        )
-        .unwrap();
-        let mut curve = Bn256 {};
-        let public_key = curve.derive_public_key(&secret_key).unwrap();
-        let g2 = G2::from_compressed(&public_key).unwrap();
-        let expected_g2 = PublicKey::from_uncompressed(&expected).unwrap();
+    fn test_to_public_key_1() {
+        let secret_key =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let expected = hex::decode(
+            "28fe26becbdc0384aa67bf734d08ec78ecc2330f0aa02ad9da00f56c37907f78\
+             2cd080d897822a95a0fb103c54f06e9bf445f82f10fe37efce69ecb59514abc8\
+             237faeb0351a693a45d5d54aa9759f52a71d76edae2132616d6085a9b2228bf9\
+             0f46bd1ef47552c3089604c65a3e7154e3976410be01149b60d5a41a6053e6c2",
+        )
+        .unwrap();
+        let curve = Bn256::default();
+        let public_key = curve.derive_public_key(&secret_key).unwrap();
+        let g2 = G2::from_compressed(&public_key).unwrap();
+        let expected_g2 = PublicKey::from_uncompressed(&expected).unwrap();
+
+        let uncompressed_slice = expected_g2.to_uncompressed().unwrap();
+
+        assert_eq!(uncompressed_slice, expected);
+        assert_eq!(g2, expected_g2.0);
+    }
+
+    #[test]
+    fn test_to_public_key_2() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let expected = hex::decode(
+            "1cd5df38ed2f184b9830bfd3c2175d53c1455352307ead8cbd7c6201202f4aa8\
+             02ce1c4241143cc61d82589c9439c6dd60f81fa6f029625d58bc0f2e25e4ce89\
+             0ba19ae3b5a298b398b3b9d410c7e48c4c8c63a1d6b95b098289fbe1503d00fb\
+             2ec596e93402de0abc73ce741f37ed4984a0b59c96e20df8c9ea1c4e6ec04556",
+        )
+        .unwrap();
+
+        let curve = Bn256::default();
+        let public_key = curve.derive_public_key(&secret_key).unwrap();
+        let g2 = G2::from_compressed(&public_key).unwrap();
+        let expected_g2 = PublicKey::from_uncompressed(&expected).unwrap();
+
+        let uncompressed_slice = expected_g2.to_uncompressed().unwrap();
+
+        assert_eq!(uncompressed_slice, expected);
+        assert_eq!(g2, expected_g2.0);
+    }
+
+    #[test]
+    fn test_to_public_key_3() {
+        let secret_key =
+            hex::decode("26fb4d661491b0a623637a2c611e34b6641cdea1743bee94c17b67e5ef14a550")
+                .unwrap();
+        let expected = hex::decode(
+            "077dfcf14e940b69bf88fa1ad99b6c7e1a1d6d2cb8813ac53383bf505a17f8ff\
+             2d1a9b04a2c5674373353b5a25591292e69c37c0b84d9ef1c780a57bb98638e6\
+             2dc52f109b333c4125bccf55bc3a839ce57676514405656c79e577e231519273\
+             2410eee842807d9325f22d087fa6bc79d9bbea07f5fa8c345e1e57b28ad54f84",
+        )
+        .unwrap();
+
+        let public_key = Bn256::default().derive_public_key(&secret_key).unwrap();
+        let g2 = G2::from_compressed(&public_key).unwrap();
+        let expected_g2 = PublicKey::from_uncompressed(&expected).unwrap();
+
+        let uncompressed_slice = expected_g2.to_uncompressed().unwrap();
+
+        assert_eq!(uncompressed_slice, expected);
+        assert_eq!(g2, expected_g2.0);
+    }
+
+    #[test]
+    fn test_to_public_key_4() {
+        let secret_key =
+            hex::decode("0f6b8785374476a3b3e4bde2c64dfb12964c81c7930d32367c8e318609387872")
+                .unwrap();
+        let public_key = Bn256::default().derive_public_key(&secret_key).unwrap();
+        let expected = hex::decode(
+            "270567a05b56b02e813281d554f46ce0c1b742b622652ef5a41d69afb6eb8338\
+             1bab5671c5107de67fe06007dde240a84674c8ff13eeac6d64bad0caf2cfe53e\
+             0142f4e04fc1402e17ae7e624fd9bd15f1eae0a1d8eda4e26ab70fd4cd793338\
+             02b54a5deaaf86dc7f03d080c8373d62f03b3be06dac42b2d9426a8ebd0caf4a",
+        )
+        .unwrap();
+
+        let g2 = G2::from_compressed(&public_key).unwrap();
+        let expected_g2 = PublicKey::from_uncompressed(&expected).unwrap();
+        let uncompressed_slice = expected_g2.to_uncompressed().unwrap();
+
+        assert_eq!(uncompressed_slice, expected);
+        assert_eq!(g2, expected_g2.0);
+    }
+
+    /// Test for the `hash_to_try_and_increment` function with own test vector
+    #[test]
+    fn test_hash_to_try_and_increment_1() {
+        // Data to be hashed with TAI (ASCII "sample")
+        let data = hex::decode("73616d706c65").unwrap();
+        let hash_point = Bn256::default().hash_to_try_and_increment(&data).unwrap();
+        let hash_bytes = Bn256::default().to_compressed_g1(hash_point).unwrap();
+
+        let expected_hash = "022f314aad50eb30c15d7e61c0f99874a6aa0d773a5a9f4262b1cda997e3c8da07";
+        assert_eq!(hex::encode(hash_bytes), expected_hash);
+    }
+
+    /// Test for the `hash_to_try_and_increment` function using Keccak-256
+    #[test]
+    fn test_hash_to_try_and_increment_keccak256() {
+        let bn256 = Bn256::<sha3::Keccak256>::new();
+        assert_eq!(
+            hex::encode(bn256.calculate_hash(b"")),
+            "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+        );
+
+        // Data to be hashed with TAI (ASCII "sample")
+        let data = hex::decode("73616d706c65").unwrap();
+        let hash_point = bn256.hash_to_try_and_increment(&data).unwrap();
+        let hash_bytes = bn256.to_compressed_g1(hash_point).unwrap();
+
+        let expected_hash = "0222d59758e66bf18a6de4b1d15f1d0801284c3e047346ba8fb19bfc61870e7334";
+        assert_eq!(hex::encode(hash_bytes), expected_hash);
+    }
+
+    /// Test for the `sign`` function with own test vector
+    #[test]
+    fn test_sign_1() {
+        // Inputs: secret key and message "sample" in ASCII
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let data = hex::decode("73616d706c65").unwrap();
+
+        // Sign data with secret key
+        let signature = Bn256::default().sign(&secret_key, &data).unwrap();
+
+        let expected_signature =
+            "031a2752fd966c0f24ccaa684aa0c303f430e56cf9e0917d8d2841b2a83488cbba";
+
+        assert_eq!(hex::encode(signature), expected_signature);
+    }
+
+    /// Test `verify` function with own signed message
+    #[test]
+    fn test_verify_signed_msg() {
+        // Public key
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let public_key = Bn256::default().derive_public_key(&secret_key).unwrap();
+
+        // Signature
+        let signature =
+            hex::decode("031a2752fd966c0f24ccaa684aa0c303f430e56cf9e0917d8d2841b2a83488cbba")
+                .unwrap();
+
+        // Message signed
+        let msg = hex::decode("73616d706c65").unwrap();
+
+        // Verify signature
+        assert!(
+            Bn256::default()
+                .verify(&signature, &msg, &public_key)
+                .is_ok(),
+            "Verification failed"
+        );
+    }
+
+    /// Test `aggregate_public_keys`
+    #[test]
+    fn test_aggregate_public_keys_1() {
+        // Public keys
+        let public_key_1 = PublicKey(G2::one()).to_compressed().unwrap();
+        let public_key_2 = PublicKey(G2::one()).to_compressed().unwrap();
+        let public_keys = [&public_key_1[..], &public_key_2[..]];
+
+        // Aggregation
+        let agg_public_key = Bn256::default()
+            .aggregate_public_keys(&public_keys)
+            .unwrap();
+
+        // Check
+        let expected = hex::decode("0b061848379c6bccd9e821e63ff6932738835b78e1e10079a0866073eba5b8bb444afbb053d16542e2b839477434966e5a9099093b6b3351f84ac19fe28f096548").unwrap();
+        assert_eq!(agg_public_key, expected);
+    }
+
+    /// Test `aggregate_signatures`
+    #[test]
+    fn test_aggregate_signatures_1() {
+        // Signatures (as valid points on G1)
+        let sign_1 = Bn256::default().to_compressed_g1(G1::one()).unwrap();
+        let sign_2 = Bn256::default().to_compressed_g1(G1::one()).unwrap();
+        let signatures = [&sign_1[..], &sign_2[..]];
+
+        // Aggregation
+        let agg_signature = Bn256::default()
+            .aggregate_signatures(&signatures)
+            .expect("Signature aggregation should not fail if G1 points are valid.");
+
+        // Check
+        let expected =
+            hex::decode("02030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3")
+                .unwrap();
+        assert_eq!(agg_signature, expected);
+    }
+
+    /// Test aggregated signatures verification
+    #[test]
+    fn test_verify_aggregated_signatures_1() {
+        // Message
+        let msg = hex::decode("73616d706c65").unwrap();
+
+        // Signature 1
+        let secret_key1 =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let public_key1 = Bn256::default().derive_public_key(&secret_key1).unwrap();
+        let sign_1 = Bn256::default().sign(&secret_key1, &msg).unwrap();
+
+        // Signature 2
+        let secret_key2 =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let public_key2 = Bn256::default().derive_public_key(&secret_key2).unwrap();
+        let sign_2 = Bn256::default().sign(&secret_key2, &msg).unwrap();
+
+        // Public Key and Signature aggregation
+        let agg_public_key = Bn256::default()
+            .aggregate_public_keys(&[&public_key1, &public_key2])
+            .unwrap();
+        let agg_signature = Bn256::default()
+            .aggregate_signatures(&[&sign_1, &sign_2])
+            .unwrap();
+
+        // Verification single signatures
+        assert!(
+            Bn256::default().verify(&sign_1, &msg, &public_key1).is_ok(),
+            "Signature 1 verification failed"
+        );
+        assert!(
+            Bn256::default().verify(&sign_2, &msg, &public_key2).is_ok(),
+            "Signature 2 signature verification failed"
+        );
+
+        // Aggregated signature verification
+        assert!(
+            Bn256::default()
+                .verify(&agg_signature, &msg, &agg_public_key)
+                .is_ok(),
+            "Aggregated signature verification failed"
+        );
+    }
+
+    /// Test signing and verifying with a domain separation tag
+    #[test]
+    fn test_sign_verify_with_dst() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let msg = hex::decode("73616d706c65").unwrap();
+        let dst = b"BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_NUL_";
+        let bn256 = Bn256::default();
+
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign_with_dst(&secret_key, &msg, dst).unwrap();
+
+        assert!(bn256
+            .verify_with_dst(&signature, &msg, dst, &public_key)
+            .is_ok());
+        // Signatures are not valid with other DSTs nor with try and increment
+        assert!(bn256
+            .verify_with_dst(&signature, &msg, b"OTHER-APPLICATION-V01", &public_key)
+            .is_err());
+        assert!(bn256.verify(&signature, &msg, &public_key).is_err());
+    }
+
+    /// Test signing and verifying with the public key bound into the hash
+    #[test]
+    fn test_sign_verify_public_key_binding() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let msg = hex::decode("73616d706c65").unwrap();
+        let bn256 = Bn256::default().with_public_key_binding(true);
+
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, &msg).unwrap();
+        assert!(bn256.verify(&signature, &msg, &public_key).is_ok());
+
+        // The signature differs from the one created without binding
+        let unbound_signature = Bn256::default().sign(&secret_key, &msg).unwrap();
+        assert_ne!(signature, unbound_signature);
+        assert!(Bn256::default()
+            .verify(&signature, &msg, &public_key)
+            .is_err());
+    }
+
+    /// Test the try and increment configuration and the number of attempts
+    #[test]
+    fn test_try_and_increment_configuration() {
+        // Data to be hashed with TAI (ASCII "sample")
+        let data = hex::decode("73616d706c65").unwrap();
+
+        let (point, attempts) = Bn256::default().try_and_increment(&data).unwrap();
+        assert_eq!(
+            point,
+            Bn256::default().hash_to_try_and_increment(&data).unwrap()
+        );
+        assert!(attempts >= 1);
+
+        // A budget lower than the attempts required is reported as exhausted
+        match Bn256::default()
+            .with_try_and_increment(1, attempts - 1)
+            .try_and_increment(&data)
+        {
+            Err(Error::TryAndIncrementExhausted) => {}
+            _ => panic!("Expected TryAndIncrementExhausted error"),
+        }
+
+        // Wider counters hash a different input
+        let (wide_point, _) = Bn256::default()
+            .with_try_and_increment(4, 1024)
+            .try_and_increment(&data)
+            .unwrap();
+        assert_ne!(point, wide_point);
+
+        assert!(Bn256::default()
+            .with_try_and_increment(9, 255)
+            .try_and_increment(&data)
+            .is_err());
+
+        // Invalid widths are rejected before the counter is allocated
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        match Bn256::default()
+            .with_try_and_increment(usize::MAX, 10)
+            .sign(&secret_key, &data)
+        {
+            Err(Error::InvalidLength) => {}
+            _ => panic!("Expected InvalidLength error"),
+        }
+    }
+
+    /// Test signing and verifying messages already hashed to G1
+    #[test]
+    fn test_sign_verify_hashed() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let msg = hex::decode("73616d706c65").unwrap();
+        let bn256 = Bn256::default();
+
+        let hash_point = bn256.hash_to_try_and_increment(&msg).unwrap();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign_hashed(&secret_key, hash_point).unwrap();
+
+        assert_eq!(signature, bn256.sign(&secret_key, &msg).unwrap());
+        assert!(bn256
+            .verify_hashed(&signature, hash_point, &public_key)
+            .is_ok());
+        assert!(bn256
+            .verify_hashed(&signature, G1::one(), &public_key)
+            .is_err());
+    }
+
+    /// Test aggregate verification of different messages with every scheme
+    #[test]
+    fn test_aggregate_verify_schemes() {
+        let secret_key1 =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let secret_key2 =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let msg1: &[u8] = b"sample";
+        let msg2: &[u8] = b"test";
+
+        for &scheme in &[
+            Scheme::Basic,
+            Scheme::MessageAugmentation,
+            Scheme::ProofOfPossession,
+        ] {
+            let bn256 = Bn256::default().with_scheme(scheme);
+            let public_key1 = bn256.derive_public_key(&secret_key1).unwrap();
+            let public_key2 = bn256.derive_public_key(&secret_key2).unwrap();
+
+            // Different messages
+            let sign_1 = bn256.sign(&secret_key1, msg1).unwrap();
+            let sign_2 = bn256.sign(&secret_key2, msg2).unwrap();
+            let agg_signature = bn256.aggregate_signatures(&[&sign_1, &sign_2]).unwrap();
+            assert!(bn256
+                .aggregate_verify(&[&public_key1, &public_key2], &[msg1, msg2], &agg_signature)
+                .is_ok());
+            assert!(bn256
+                .aggregate_verify(&[&public_key1, &public_key2], &[msg2, msg1], &agg_signature)
+                .is_err());
+
+            // Same message, rejected only by the basic scheme
+            let sign_2 = bn256.sign(&secret_key2, msg1).unwrap();
+            let agg_signature = bn256.aggregate_signatures(&[&sign_1, &sign_2]).unwrap();
+            let result = bn256.aggregate_verify(
+                &[&public_key1, &public_key2],
+                &[msg1, msg1],
+                &agg_signature,
+            );
+            match scheme {
+                Scheme::Basic => match result {
+                    Err(Error::DuplicateMessages) => {}
+                    _ => panic!("Expected DuplicateMessages error"),
+                },
+                _ => assert!(result.is_ok()),
+            }
+        }
+    }
+
+    /// Test verifying a signature set with the distinct messages rule disabled
+    #[test]
+    fn test_aggregate_verify_set_duplicates() {
+        let secret_key1 =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let secret_key2 =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let msg: &[u8] = b"sample";
+        let bn256 = Bn256::default();
+        let public_key1 = bn256.derive_public_key(&secret_key1).unwrap();
+        let public_key2 = bn256.derive_public_key(&secret_key2).unwrap();
+        let sign_1 = bn256.sign(&secret_key1, msg).unwrap();
+        let sign_2 = bn256.sign(&secret_key2, msg).unwrap();
+        let agg_signature = bn256.aggregate_signatures(&[&sign_1, &sign_2]).unwrap();
+
+        let mut set = SignatureSet::new();
+        set.push(&public_key1, msg);
+        set.push(&public_key2, msg);
+        match bn256.aggregate_verify_set(&set, &agg_signature) {
+            Err(Error::DuplicateMessages) => {}
+            _ => panic!("Expected DuplicateMessages error"),
+        }
+
+        let bn256 = Bn256::default().with_duplicate_messages(true);
+        assert!(bn256.aggregate_verify_set(&set, &agg_signature).is_ok());
+        assert!(bn256
+            .aggregate_verify_set(&SignatureSet::new(), &agg_signature)
+            .is_err());
+    }
+
+    /// Test generating and verifying proofs of possession
+    #[test]
+    fn test_pop_prove_verify() {
+        let secret_key1 =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let secret_key2 =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let bn256 = Bn256::default().with_scheme(Scheme::ProofOfPossession);
+        let public_key1 = bn256.derive_public_key(&secret_key1).unwrap();
+        let public_key2 = bn256.derive_public_key(&secret_key2).unwrap();
+
+        let proof1 = bn256.pop_prove(&secret_key1).unwrap();
+        assert!(bn256.pop_verify(&public_key1, &proof1).is_ok());
+        assert!(bn256.pop_verify(&public_key2, &proof1).is_err());
+
+        // A proof is not a signature of the public key bytes
+        assert!(bn256.verify(&proof1, &public_key1, &public_key1).is_err());
+        assert!(bn256
+            .verify_with_dst(
+                &proof1,
+                &public_key1,
+                b"OTHER-APPLICATION-V01",
+                &public_key1
+            )
+            .is_err());
+    }
+
+    /// Test the message augmentation scheme defends against rogue-key attacks
+    #[test]
+    fn test_message_augmentation_rogue_key() {
+        let secret_key1 =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let msg: &[u8] = b"sample";
+        let basic = Bn256::default();
+        let aug = Bn256::default().with_scheme(Scheme::MessageAugmentation);
+        let public_key1 = basic.derive_public_key(&secret_key1).unwrap();
+
+        // Rogue key `PK' = x * G2 - PK_1`, so that `PK_1 + PK' = x * G2`
+        let x = hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+            .unwrap();
+        let rogue_point = G2::one() * Fr::from_slice(&x).unwrap()
+            - PublicKey::from_compressed(&public_key1).unwrap().0;
+        let rogue_key = PublicKey(rogue_point).to_compressed().unwrap();
+
+        // Forged "aggregated" signature of the message by both keys, computed with `x` only
+        let forged = basic.sign(&x, msg).unwrap();
+        let agg_public_key = basic
+            .aggregate_public_keys(&[&public_key1, &rogue_key])
+            .unwrap();
+        assert!(basic.verify(&forged, msg, &agg_public_key).is_ok());
+        let forged_aug = aug.sign(&x, msg).unwrap();
+        assert!(aug
+            .aggregate_verify(&[&public_key1, &rogue_key], &[msg, msg], &forged_aug)
+            .is_err());
+
+        // Honest signatures still verify with augmentation, also with a DST
+        let signature = aug.sign(&secret_key1, msg).unwrap();
+        assert!(aug.verify(&signature, msg, &public_key1).is_ok());
+        assert!(basic.verify(&signature, msg, &public_key1).is_err());
+        let dst = b"BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_AUG_";
+        let signature = aug.sign_with_dst(&secret_key1, msg, dst).unwrap();
+        assert!(aug
+            .verify_with_dst(&signature, msg, dst, &public_key1)
+            .is_ok());
+        assert!(basic
+            .verify_with_dst(&signature, msg, dst, &public_key1)
+            .is_err());
+    }
+
+    /// Test fast aggregate verification of the same message
+    #[test]
+    fn test_fast_aggregate_verify() {
+        let secret_key1 =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let secret_key2 =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let msg: &[u8] = b"sample";
+        let bn256 = Bn256::default().with_scheme(Scheme::ProofOfPossession);
+
+        let public_key1 = bn256.derive_public_key(&secret_key1).unwrap();
+        let public_key2 = bn256.derive_public_key(&secret_key2).unwrap();
+        let proof1 = bn256.pop_prove(&secret_key1).unwrap();
+        let proof2 = bn256.pop_prove(&secret_key2).unwrap();
+        assert!(bn256.pop_verify(&public_key1, &proof1).is_ok());
+        assert!(bn256.pop_verify(&public_key2, &proof2).is_ok());
+
+        let sign_1 = bn256.sign(&secret_key1, msg).unwrap();
+        let sign_2 = bn256.sign(&secret_key2, msg).unwrap();
+        let agg_signature = bn256.aggregate_signatures(&[&sign_1, &sign_2]).unwrap();
+        assert!(bn256
+            .fast_aggregate_verify(&[&public_key1, &public_key2], msg, &agg_signature)
+            .is_ok());
+        assert!(bn256
+            .fast_aggregate_verify(&[&public_key1], msg, &agg_signature)
+            .is_err());
+        assert!(bn256
+            .fast_aggregate_verify(&[&public_key1, &public_key2], b"test", &agg_signature)
+            .is_err());
+
+        // Not available without proofs of possession
+        match Bn256::default().fast_aggregate_verify(
+            &[&public_key1, &public_key2],
+            msg,
+            &agg_signature,
+        ) {
+            Err(Error::UnsupportedScheme) => {}
+            _ => panic!("Expected UnsupportedScheme error"),
+        }
+    }
+
+    /// Test batch verification of independent signatures
+    #[test]
+    fn test_batch_verify() {
+        let secret_key1 =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let secret_key2 =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let msg1: &[u8] = b"sample";
+        let msg2: &[u8] = b"test";
+        let bn256 = Bn256::default();
+
+        let public_key1 = bn256.derive_public_key(&secret_key1).unwrap();
+        let public_key2 = bn256.derive_public_key(&secret_key2).unwrap();
+        let sign_1 = bn256.sign(&secret_key1, msg1).unwrap();
+        let sign_2 = bn256.sign(&secret_key2, msg2).unwrap();
+
+        assert!(bn256
+            .batch_verify(
+                &[&public_key1, &public_key2],
+                &[msg1, msg2],
+                &[&sign_1, &sign_2]
+            )
+            .is_ok());
+        assert!(bn256
+            .batch_verify(
+                &[&public_key1, &public_key2],
+                &[msg1, msg2],
+                &[&sign_2, &sign_1]
+            )
+            .is_err());
+        assert!(bn256
+            .batch_verify(
+                &[&public_key1, &public_key2],
+                &[msg1, msg1],
+                &[&sign_1, &sign_2]
+            )
+            .is_err());
+        assert!(bn256
+            .batch_verify(&[&public_key1], &[msg1, msg2], &[&sign_1, &sign_2])
+            .is_err());
+    }
+
+    /// Test signing and verifying with the standard ciphersuites
+    #[test]
+    fn test_sign_verify_with_ciphersuite() {
+        use ciphersuite::*;
+
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let msg = hex::decode("73616d706c65").unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+
+        let suites = [
+            BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL,
+            BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_AUG,
+            BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_POP,
+        ];
+        for suite in suites.iter() {
+            let signature = bn256
+                .sign_with_ciphersuite(suite, &secret_key, &msg)
+                .unwrap();
+            for other in suites.iter() {
+                let result = bn256.verify_with_ciphersuite(other, &signature, &msg, &public_key);
+                assert_eq!(result.is_ok(), suite == other);
+            }
+        }
+
+        // The basic ciphersuite is equivalent to signing with its identifier as DST
+        let signature = bn256
+            .sign_with_ciphersuite(&BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL, &secret_key, &msg)
+            .unwrap();
+        assert_eq!(
+            signature,
+            bn256
+                .sign_with_dst(
+                    &secret_key,
+                    &msg,
+                    BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL.id
+                )
+                .unwrap()
+        );
+    }
+
+    /// Test the context of errors and the underlying kind
+    #[test]
+    fn test_error_context() {
+        use alloc::string::ToString;
+
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&[1u8; 32]).unwrap();
+        let signature = bn256.sign(&[1u8; 32], b"sample").unwrap();
+
+        let error = bn256
+            .verify(&signature, b"sample", &public_key[..64])
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid public key: Failed to create group or field due to invalid input length"
+        );
+        match error.kind() {
+            Error::InvalidLength => {}
+            _ => panic!("Expected InvalidLength error"),
+        }
+
+        let error = bn256
+            .aggregate_signatures(&[&signature, &public_key])
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Invalid item 1 of the input: Encoding has trailing bytes after the expected length"
+        );
+        #[cfg(feature = "std")]
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    /// Test malformed inputs of any length are rejected with an error instead of panicking
+    #[test]
+    fn test_malformed_inputs() {
+        let bn256 = Bn256::default();
+        let secret_key = [1u8; 32];
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+
+        for length in 0..=130 {
+            for &fill in &[0x00, 0x02, 0x0a, 0xff] {
+                let bytes = vec![fill; length];
+                if length != 32 {
+                    assert!(bn256.derive_public_key(&bytes).is_err());
+                    assert!(bn256.sign(&bytes, b"sample").is_err());
+                    assert!(bn256.pop_prove(&bytes).is_err());
+                }
+                if length != 33 {
+                    assert!(bn256.verify(&bytes, b"sample", &public_key).is_err());
+                    assert!(bn256.aggregate_signatures(&[&bytes]).is_err());
+                    assert!(Signature::from_compressed(&bytes).is_err());
+                }
+                if length != 65 {
+                    assert!(bn256.verify(&signature, b"sample", &bytes).is_err());
+                    assert!(bn256.aggregate_public_keys(&[&bytes]).is_err());
+                    assert!(PublicKey::from_compressed(&bytes).is_err());
+                }
+                if length != 128 {
+                    assert!(PublicKey::from_uncompressed(&bytes).is_err());
+                }
+                assert!(bn256.pop_verify(&bytes, &bytes).is_err());
+                // Some of these bytes are valid points, so only the absence of panics is checked
+                let _ = PublicKey::from_bytes(&bytes);
+                let _ = Signature::from_bytes(&bytes);
+            }
+        }
+    }
+
+    /// Test aggregating more points than fit in a chunk of the parallel aggregation
+    #[test]
+    fn test_aggregate_many_points() {
+        use alloc::string::ToString;
+
+        let bn256 = Bn256::default();
+        let scalars: Vec<Fr> = (1..=600u64)
+            .map(|i| Fr::from_str(&i.to_string()).unwrap())
+            .collect();
+        let signatures: Vec<Vec<u8>> = scalars
+            .iter()
+            .map(|&scalar| g1_to_compressed(G1::one() * scalar).unwrap())
+            .collect();
+        let public_keys: Vec<Vec<u8>> = scalars[..300]
+            .iter()
+            .map(|&scalar| PublicKey(G2::one() * scalar).to_compressed().unwrap())
+            .collect();
+
+        // The sum of 1..=n times the generator
+        let signature_refs: Vec<&[u8]> = signatures.iter().map(Vec::as_slice).collect();
+        assert_eq!(
+            bn256.aggregate_signatures(&signature_refs).unwrap(),
+            g1_to_compressed(G1::one() * Fr::from_str("180300").unwrap()).unwrap()
+        );
+        let public_key_refs: Vec<&[u8]> = public_keys.iter().map(Vec::as_slice).collect();
+        assert_eq!(
+            bn256.aggregate_public_keys(&public_key_refs).unwrap(),
+            PublicKey(G2::one() * Fr::from_str("45150").unwrap())
+                .to_compressed()
+                .unwrap()
+        );
 
-        let uncompressed_slice = expected_g2.to_uncompressed().unwrap();
+        // The first invalid point is reported, whatever chunk it is in
+        let mut signature_refs = signature_refs;
+        signature_refs[550] = &[0x02; 33];
+        signature_refs[290] = &[0x02; 32];
+        match bn256.aggregate_signatures(&signature_refs) {
+            Err(Error::InvalidItem { index, .. }) => assert_eq!(index, 290),
+            _ => panic!("expected the index of the invalid signature"),
+        }
+    }
 
-        assert_eq!(uncompressed_slice, expected);
-        assert_eq!(g2, expected_g2.0);
+    /// Test messages of any type implementing `AsRef<[u8]>`
+    #[test]
+    fn test_generic_messages() {
+        let bn256 = Bn256::default();
+        let secret_key = [1u8; 32];
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+
+        let vector: Vec<u8> = b"sample".to_vec();
+        assert_eq!(bn256.sign(&secret_key, "sample").unwrap(), signature);
+        assert_eq!(bn256.sign(&secret_key, vector).unwrap(), signature);
+        assert_eq!(bn256.sign(&secret_key, *b"sample").unwrap(), signature);
+        assert!(bn256.verify(&signature, "sample", &public_key).is_ok());
+        assert!(bn256
+            .verify(
+                &signature,
+                alloc::string::String::from("sample"),
+                &public_key
+            )
+            .is_ok());
+        assert!(bn256.verify(&signature, "other", &public_key).is_err());
+
+        let proof = bn256.vrf_prove(&secret_key, "alpha").unwrap();
+        assert!(bn256.vrf_verify(&public_key, &proof, b"alpha").is_ok());
     }
 
+    /// Test the constant time comparisons of keys and signatures
     #[test]
-    fn test_to_public_key_2() {
-        let secret_key =
-            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
-                .unwrap();
-        let expected = hex::decode(
-            "1cd5df38ed2f184b9830bfd3c2175d53c1455352307ead8cbd7c6201202f4aa8\
-             02ce1c4241143cc61d82589c9439c6dd60f81fa6f029625d58bc0f2e25e4ce89\
-             0ba19ae3b5a298b398b3b9d410c7e48c4c8c63a1d6b95b098289fbe1503d00fb\
-             2ec596e93402de0abc73ce741f37ed4984a0b59c96e20df8c9ea1c4e6ec04556",
-        )
-        .unwrap();
+    fn test_constant_time_eq() {
+        let bn256 = Bn256::default();
+        let private_key = PrivateKey::from_slice(&[1u8; 32]).unwrap();
+        let other_key = PrivateKey::from_slice(&[2u8; 32]).unwrap();
+        assert!(bool::from(
+            private_key.ct_eq(&PrivateKey::from_slice(&[1u8; 32]).unwrap())
+        ));
+        assert!(!bool::from(private_key.ct_eq(&other_key)));
+        assert!(private_key != other_key);
+
+        let public_key = PublicKey(core::sk_to_pk(private_key.0));
+        let other_public_key = PublicKey(core::sk_to_pk(other_key.0));
+        assert!(bool::from(public_key.ct_eq(&public_key.clone())));
+        assert!(!bool::from(public_key.ct_eq(&other_public_key)));
 
-        let mut curve = Bn256 {};
-        let public_key = curve.derive_public_key(&secret_key).unwrap();
-        let g2 = G2::from_compressed(&public_key).unwrap();
-        let expected_g2 = PublicKey::from_uncompressed(&expected).unwrap();
+        let signature =
+            Signature::from_compressed(&bn256.sign(&[1u8; 32], b"sample").unwrap()).unwrap();
+        let other_signature =
+            Signature::from_compressed(&bn256.sign(&[2u8; 32], b"sample").unwrap()).unwrap();
+        assert!(bool::from(signature.ct_eq(&signature.clone())));
+        assert!(!bool::from(signature.ct_eq(&other_signature)));
+
+        // Integers are compared from the most significant limb
+        let (greater, equal) = ct_compare(&[1, u128::MAX], &[2, u128::MAX - 1]);
+        assert!(bool::from(greater) && !bool::from(equal));
+        let (greater, equal) = ct_compare(&[1, 2], &[1, 2]);
+        assert!(!bool::from(greater) && bool::from(equal));
+    }
 
-        let uncompressed_slice = expected_g2.to_uncompressed().unwrap();
+    /// Test zero secret keys are rejected
+    #[test]
+    fn test_zero_secret_key() {
+        let secret_key = [0u8; 32];
+        let bn256 = Bn256::default();
+
+        match bn256
+            .derive_public_key(&secret_key)
+            .as_ref()
+            .map_err(Error::kind)
+        {
+            Err(Error::ZeroSecretKey) => {}
+            _ => panic!("Expected ZeroSecretKey error"),
+        }
+        match bn256
+            .sign(&secret_key, b"sample")
+            .as_ref()
+            .map_err(Error::kind)
+        {
+            Err(Error::ZeroSecretKey) => {}
+            _ => panic!("Expected ZeroSecretKey error"),
+        }
+    }
 
-        assert_eq!(uncompressed_slice, expected);
-        assert_eq!(g2, expected_g2.0);
+    /// Test delinearized aggregation of signatures of the same message
+    #[test]
+    fn test_delinearized_aggregation() {
+        let secret_key1 =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let secret_key2 =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let msg: &[u8] = b"sample";
+        let bn256 = Bn256::default();
+        let public_key1 = bn256.derive_public_key(&secret_key1).unwrap();
+        let public_key2 = bn256.derive_public_key(&secret_key2).unwrap();
+        let sign_1 = bn256.sign(&secret_key1, msg).unwrap();
+        let sign_2 = bn256.sign(&secret_key2, msg).unwrap();
+
+        let public_keys: [&[u8]; 2] = [&public_key1, &public_key2];
+        let agg_public_key = bn256
+            .aggregate_public_keys_delinearized(&public_keys)
+            .unwrap();
+        let agg_signature = bn256
+            .aggregate_signatures_delinearized(&public_keys, &[&sign_1, &sign_2])
+            .unwrap();
+        assert!(bn256.verify(&agg_signature, msg, &agg_public_key).is_ok());
+
+        // Plain aggregation does not match the delinearized public key
+        let plain_signature = bn256.aggregate_signatures(&[&sign_1, &sign_2]).unwrap();
+        assert!(bn256
+            .verify(&plain_signature, msg, &agg_public_key)
+            .is_err());
+
+        // A rogue key `x * G2 - PK_1` no longer cancels the honest public key
+        let x = Fr::from_slice(&secret_key2).unwrap();
+        let rogue_point = G2::one() * x - PublicKey::from_compressed(&public_key1).unwrap().0;
+        let rogue_key = PublicKey(rogue_point).to_compressed().unwrap();
+        let agg_public_key = bn256
+            .aggregate_public_keys_delinearized(&[&public_key1, &rogue_key])
+            .unwrap();
+        let forged = bn256.sign(&secret_key2, msg).unwrap();
+        assert!(bn256.verify(&forged, msg, &agg_public_key).is_err());
     }
 
+    /// Test signing with a key pair under the different schemes
     #[test]
-    fn test_to_public_key_3() {
+    fn test_key_pair() {
         let secret_key =
-            hex::decode("26fb4d661491b0a623637a2c611e34b6641cdea1743bee94c17b67e5ef14a550")
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
                 .unwrap();
-        let expected = hex::decode(
-            "077dfcf14e940b69bf88fa1ad99b6c7e1a1d6d2cb8813ac53383bf505a17f8ff\
-             2d1a9b04a2c5674373353b5a25591292e69c37c0b84d9ef1c780a57bb98638e6\
-             2dc52f109b333c4125bccf55bc3a839ce57676514405656c79e577e231519273\
-             2410eee842807d9325f22d087fa6bc79d9bbea07f5fa8c345e1e57b28ad54f84",
-        )
-        .unwrap();
+        let msg = hex::decode("73616d706c65").unwrap();
+        let key_pair = KeyPair::from_secret_key(&secret_key).unwrap();
+
+        for bn256 in [
+            Bn256::default(),
+            Bn256::default().with_public_key_binding(true),
+            Bn256::default().with_scheme(Scheme::MessageAugmentation),
+        ]
+        .iter_mut()
+        {
+            let public_key = bn256.derive_public_key(&secret_key).unwrap();
+            assert_eq!(key_pair.public(), &public_key[..]);
+            let signature = key_pair.sign(bn256, &msg).unwrap();
+            assert_eq!(signature, bn256.sign(&secret_key, &msg).unwrap());
+            assert!(bn256.verify(&signature, &msg, key_pair.public()).is_ok());
+        }
+        assert!(KeyPair::from_secret_key(&[0u8; 32]).is_err());
+    }
 
-        let public_key = Bn256.derive_public_key(&secret_key).unwrap();
-        let g2 = G2::from_compressed(&public_key).unwrap();
-        let expected_g2 = PublicKey::from_uncompressed(&expected).unwrap();
+    /// Test validating secret keys and reducing them explicitly
+    #[test]
+    fn test_validate_secret_key() {
+        // Order of the group
+        let order = hex::decode("30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001")
+            .unwrap();
+        let mut max_secret_key = order.clone();
+        max_secret_key[31] = 0;
+        assert!(validate_secret_key(&max_secret_key).is_ok());
+
+        let mut above_order = order.clone();
+        above_order[31] = 2;
+        for (invalid, expected) in [
+            (&order[..], "NonCanonicalSecretKey"),
+            (&above_order[..], "NonCanonicalSecretKey"),
+            (&[0xffu8; 32][..], "NonCanonicalSecretKey"),
+            (&[0u8; 32][..], "ZeroSecretKey"),
+            (&order[1..], "InvalidLength"),
+        ]
+        .iter()
+        {
+            match (validate_secret_key(invalid), *expected) {
+                (Err(Error::NonCanonicalSecretKey), "NonCanonicalSecretKey")
+                | (Err(Error::ZeroSecretKey), "ZeroSecretKey")
+                | (Err(Error::InvalidLength), "InvalidLength") => {}
+                (result, expected) => panic!("Expected {} error, got {:?}", expected, result),
+            }
+            assert!(Bn256::default().sign(invalid, b"sample").is_err());
+        }
 
-        let uncompressed_slice = expected_g2.to_uncompressed().unwrap();
+        // Reduction leaves canonical keys unchanged and wraps around the order
+        assert_eq!(
+            PrivateKey::from_slice_reduced(&max_secret_key)
+                .unwrap()
+                .to_bytes()
+                .unwrap(),
+            max_secret_key
+        );
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        assert_eq!(
+            PrivateKey::from_slice_reduced(&above_order)
+                .unwrap()
+                .to_bytes()
+                .unwrap(),
+            one
+        );
+        assert_eq!(
+            PrivateKey::from_slice_reduced(&[&[0u8; 32][..], &above_order].concat())
+                .unwrap()
+                .to_bytes()
+                .unwrap(),
+            one
+        );
+        assert!(PrivateKey::from_slice_reduced(&order).is_err());
+        assert!(PrivateKey::from_slice_reduced(&[1u8; 65]).is_err());
+    }
 
-        assert_eq!(uncompressed_slice, expected);
-        assert_eq!(g2, expected_g2.0);
+    /// Test serializing points in compressed format without allocating
+    #[test]
+    fn test_compressed_array() {
+        let bn256 = Bn256::default();
+        for secret_key in [
+            "1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565",
+            "2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c",
+        ]
+        .iter()
+        {
+            let secret_key = hex::decode(secret_key).unwrap();
+            let public_key = bn256.derive_public_key(&secret_key).unwrap();
+            let signature = bn256.sign(&secret_key, b"sample").unwrap();
+
+            let array = PublicKey::from_compressed(&public_key)
+                .unwrap()
+                .to_compressed_array()
+                .unwrap();
+            assert_eq!(&array[..], &public_key[..]);
+            let array = Signature::from_compressed(&signature)
+                .unwrap()
+                .to_compressed_array()
+                .unwrap();
+            assert_eq!(&array[..], &signature[..]);
+        }
+        assert_eq!(
+            Signature(G1::zero()).to_compressed_array().unwrap(),
+            [0u8; 33]
+        );
     }
 
+    /// Test signing and verifying with typed keys and signatures
     #[test]
-    fn test_to_public_key_4() {
+    fn test_bls_trait() {
+        use crate::{Bls, ByteEncoding};
+
         let secret_key =
-            hex::decode("0f6b8785374476a3b3e4bde2c64dfb12964c81c7930d32367c8e318609387872")
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
                 .unwrap();
-        let public_key = Bn256.derive_public_key(&secret_key).unwrap();
-        let expected = hex::decode(
-            "270567a05b56b02e813281d554f46ce0c1b742b622652ef5a41d69afb6eb8338\
-             1bab5671c5107de67fe06007dde240a84674c8ff13eeac6d64bad0caf2cfe53e\
-             0142f4e04fc1402e17ae7e624fd9bd15f1eae0a1d8eda4e26ab70fd4cd793338\
-             02b54a5deaaf86dc7f03d080c8373d62f03b3be06dac42b2d9426a8ebd0caf4a",
-        )
-        .unwrap();
-
-        let g2 = G2::from_compressed(&public_key).unwrap();
-        let expected_g2 = PublicKey::from_uncompressed(&expected).unwrap();
-        let uncompressed_slice = expected_g2.to_uncompressed().unwrap();
+        for scheme in [Scheme::Basic, Scheme::MessageAugmentation].iter() {
+            let bn256 = Bn256::default().with_scheme(*scheme);
+            let private_key = PrivateKey::decode(&secret_key).unwrap();
+            let public_key = Bls::derive_public_key(&bn256, &private_key).unwrap();
+            let signature = Bls::sign(&bn256, &private_key, b"sample").unwrap();
+
+            // Same keys and signatures as with bytes
+            assert_eq!(
+                public_key.encode().unwrap(),
+                MultiSignature::derive_public_key(&bn256, &secret_key).unwrap()
+            );
+            assert_eq!(
+                signature.encode().unwrap(),
+                MultiSignature::sign(&bn256, &secret_key, b"sample").unwrap()
+            );
+            assert!(Bls::verify(&bn256, &signature, b"sample", &public_key).is_ok());
+            assert!(Bls::verify(&bn256, &signature, b"other", &public_key).is_err());
+
+            let other_key = PrivateKey::decode(&[1u8; 32]).unwrap();
+            let other_public_key = Bls::derive_public_key(&bn256, &other_key).unwrap();
+            let other_signature = Bls::sign(&bn256, &other_key, b"other").unwrap();
+            let aggregated =
+                Bls::aggregate_signatures(&bn256, &[signature, other_signature]).unwrap();
+            let public_keys = [public_key, other_public_key];
+            assert!(Bls::aggregate_verify(
+                &bn256,
+                &public_keys,
+                &[b"sample", b"other"],
+                &aggregated
+            )
+            .is_ok());
+            assert!(Bls::aggregate_public_keys(&bn256, &public_keys).is_ok());
+        }
 
-        assert_eq!(uncompressed_slice, expected);
-        assert_eq!(g2, expected_g2.0);
+        // Invalid lengths are caught when decoding
+        match Signature::decode(&[2u8; 32]) {
+            Err(Error::InvalidLength) => {}
+            _ => panic!("short signature accepted"),
+        }
     }
 
-    /// Test for the `hash_to_try_and_increment` function with own test vector
+    /// Test sizing and validating encodings generically with the constants of the `Bls` trait
     #[test]
-    fn test_hash_to_try_and_increment_1() {
-        // Data to be hashed with TAI (ASCII "sample")
-        let data = hex::decode("73616d706c65").unwrap();
-        let hash_point = Bn256.hash_to_try_and_increment(&data).unwrap();
-        let hash_bytes = Bn256.to_compressed_g1(hash_point).unwrap();
+    fn test_bls_constants() {
+        use crate::{Bls, ByteEncoding};
+        use ::core::convert::TryFrom;
+
+        fn sign_and_check<B: Bls>(engine: &B, scalar: B::Scalar) -> B::SignaturePoint {
+            let private_key = B::PrivateKey::try_from(scalar).ok().unwrap();
+            let public_key = engine.derive_public_key(&private_key).ok().unwrap();
+            let signature = engine.sign(&private_key, b"sample").ok().unwrap();
+            assert_eq!(
+                private_key.encode().ok().unwrap().len(),
+                B::PRIVATE_KEY_LENGTH
+            );
+            assert_eq!(
+                public_key.encode().ok().unwrap().len(),
+                B::PUBLIC_KEY_LENGTH
+            );
+            assert_eq!(signature.encode().ok().unwrap().len(), B::SIGNATURE_LENGTH);
+
+            signature.into()
+        }
 
-        let expected_hash = "022f314aad50eb30c15d7e61c0f99874a6aa0d773a5a9f4262b1cda997e3c8da07";
-        assert_eq!(hex::encode(hash_bytes), expected_hash);
+        let bn256 = Bn256::default();
+        let point = sign_and_check(&bn256, Fr::one());
+        let hash_point = bn256.hash_to_try_and_increment(b"sample").unwrap();
+        assert_eq!(point, hash_point);
+        assert_eq!(<Bn256 as Bls>::CIPHERSUITE_ID, &b"BLS_SIG_BN254G1_TAI_"[..]);
+        match PrivateKey::try_from(Fr::zero()) {
+            Err(Error::ZeroSecretKey) => {}
+            _ => panic!("zero scalar accepted"),
+        }
     }
 
-    /// Test for the `sign`` function with own test vector
+    /// Test verifying signatures concurrently with a shared engine
+    #[cfg(feature = "std")]
     #[test]
-    fn test_sign_1() {
-        // Inputs: secret key and message "sample" in ASCII
+    fn test_shared_engine() {
+        use std::sync::Arc;
+        use std::thread;
+
+        let bn256 = Arc::new(Bn256::default());
         let secret_key =
             hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
                 .unwrap();
-        let data = hex::decode("73616d706c65").unwrap();
-
-        // Sign data with secret key
-        let signature = Bn256.sign(&secret_key, &data).unwrap();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+
+        let handles: Vec<_> = (0..4)
+            .map(|_| {
+                let bn256 = Arc::clone(&bn256);
+                let public_key = public_key.clone();
+                let signature = signature.clone();
+                thread::spawn(move || bn256.verify(&signature, b"sample", &public_key).is_ok())
+            })
+            .collect();
+        for handle in handles {
+            assert!(handle.join().unwrap());
+        }
+    }
 
-        let expected_signature =
-            "031a2752fd966c0f24ccaa684aa0c303f430e56cf9e0917d8d2841b2a83488cbba";
+    /// Test signing and verifying a message hashed once
+    #[test]
+    fn test_hashed_message() {
+        let bn256 = Bn256::default();
+        let secret_keys = [[1u8; 32], [2u8; 32]];
+        let public_keys: Vec<Vec<u8>> = secret_keys
+            .iter()
+            .map(|secret_key| bn256.derive_public_key(secret_key).unwrap())
+            .collect();
+        let hashed = bn256.hash(b"sample").unwrap();
+
+        let signatures: Vec<Vec<u8>> = secret_keys
+            .iter()
+            .map(|secret_key| bn256.sign_hashed(secret_key, hashed).unwrap())
+            .collect();
+        for i in 0..secret_keys.len() {
+            assert_eq!(
+                signatures[i],
+                bn256.sign(&secret_keys[i], b"sample").unwrap()
+            );
+            assert!(bn256
+                .verify_hashed(&signatures[i], hashed, &public_keys[i])
+                .is_ok());
+        }
 
-        assert_eq!(hex::encode(signature), expected_signature);
+        let public_keys: Vec<&[u8]> = public_keys.iter().map(Vec::as_slice).collect();
+        let signatures: Vec<&[u8]> = signatures.iter().map(Vec::as_slice).collect();
+        assert!(bn256
+            .batch_verify_hashed(&public_keys, &[hashed, hashed], &signatures)
+            .is_ok());
+        let other = bn256.hash(b"other").unwrap();
+        assert!(bn256
+            .batch_verify_hashed(&public_keys, &[hashed, other], &signatures)
+            .is_err());
+
+        let bn256 = Bn256::default().with_scheme(Scheme::MessageAugmentation);
+        assert!(bn256.hash(b"sample").is_err());
     }
 
-    /// Test `verify` function with own signed message
+    /// Test verifying signatures with a prepared public key
     #[test]
-    fn test_verify_signed_msg() {
-        // Public key
+    fn test_verify_prepared() {
         let secret_key =
             hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
                 .unwrap();
-        let public_key = Bn256.derive_public_key(&secret_key).unwrap();
+        for binding in [false, true].iter() {
+            let bn256 = Bn256::default().with_public_key_binding(*binding);
+            let public_key = bn256.derive_public_key(&secret_key).unwrap();
+            let prepared = PublicKey::from_compressed(&public_key)
+                .unwrap()
+                .prepare()
+                .unwrap();
+            for message in [&b"sample"[..], &b"test"[..]].iter() {
+                let signature = bn256.sign(&secret_key, message).unwrap();
+                assert!(bn256
+                    .verify_prepared(&signature, message, &prepared)
+                    .is_ok());
+            }
+            let signature = bn256.sign(&secret_key, b"sample").unwrap();
+            assert!(bn256
+                .verify_prepared(&signature, b"test", &prepared)
+                .is_err());
+        }
+    }
 
-        // Signature
+    /// Test encoding the point at infinity and the accept/reject policy
+    #[test]
+    fn test_identity_policy() {
+        let identity_signature = Signature(G1::zero()).to_compressed().unwrap();
+        let identity_public_key = PublicKey(G2::zero()).to_compressed().unwrap();
+        assert_eq!(identity_signature, vec![0u8; 33]);
+        assert_eq!(identity_public_key, vec![0u8; 65]);
+        assert_eq!(
+            PublicKey(G2::zero()).to_uncompressed().unwrap(),
+            vec![0u8; 128]
+        );
+        assert_eq!(g1_to_uncompressed(G1::zero()).unwrap(), vec![0u8; 64]);
+
+        // Round trip when accepted
         let signature =
-            hex::decode("031a2752fd966c0f24ccaa684aa0c303f430e56cf9e0917d8d2841b2a83488cbba")
+            Signature::from_compressed_with_policy(&identity_signature, IdentityPolicy::Accept)
+                .unwrap();
+        assert_eq!(signature, Signature(G1::zero()));
+        let public_key =
+            PublicKey::from_compressed_with_policy(&identity_public_key, IdentityPolicy::Accept)
                 .unwrap();
+        assert_eq!(public_key, PublicKey(G2::zero()));
 
-        // Message signed
-        let msg = hex::decode("73616d706c65").unwrap();
+        // Rejected by default
+        match Signature::from_compressed(&identity_signature) {
+            Err(Error::IdentitySignature) => {}
+            _ => panic!("identity signature accepted"),
+        }
+        match PublicKey::from_compressed(&identity_public_key) {
+            Err(Error::IdentityPublicKey) => {}
+            _ => panic!("identity public key accepted"),
+        }
+        let bn256 = Bn256::default();
+        match bn256
+            .verify(&identity_signature, b"sample", &identity_public_key)
+            .as_ref()
+            .map_err(Error::kind)
+        {
+            Err(Error::IdentityPublicKey) => {}
+            _ => panic!("identity accepted by verification"),
+        }
 
-        // Verify signature
-        assert!(
-            Bn256.verify(&signature, &msg, &public_key).is_ok(),
-            "Verification failed"
-        );
+        // The identity verifies any message if accepted
+        let bn256 = Bn256::default().with_identity_policy(IdentityPolicy::Accept);
+        assert!(bn256
+            .verify(&identity_signature, b"sample", &identity_public_key)
+            .is_ok());
     }
 
-    /// Test `aggregate_public_keys`
+    /// Test converting signatures and public keys between compressed and uncompressed formats
     #[test]
-    fn test_aggregate_public_keys_1() {
-        // Public keys
-        let public_key_1 = PublicKey(G2::one()).to_compressed().unwrap();
-        let public_key_2 = PublicKey(G2::one()).to_compressed().unwrap();
-        let public_keys = [&public_key_1[..], &public_key_2[..]];
+    fn test_uncompressed_conversion() {
+        let secret_key =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+
+        let uncompressed = public_key_to_uncompressed(&public_key).unwrap();
+        assert_eq!(
+            hex::encode(&uncompressed[..32]),
+            "28fe26becbdc0384aa67bf734d08ec78ecc2330f0aa02ad9da00f56c37907f78"
+        );
+        assert_eq!(
+            public_key_from_uncompressed(&uncompressed).unwrap(),
+            public_key
+        );
 
-        // Aggregation
-        let agg_public_key = Bn256.aggregate_public_keys(&public_keys).unwrap();
+        let uncompressed = signature_to_uncompressed(&signature).unwrap();
+        assert_eq!(uncompressed.len(), 64);
+        assert_eq!(&uncompressed[..32], &signature[1..]);
+        assert_eq!(
+            uncompressed[63] & 1 == 1,
+            signature[0] == 3,
+            "Parity of Y must match the compressed tag"
+        );
+        assert_eq!(
+            signature_from_uncompressed(&uncompressed).unwrap(),
+            signature
+        );
 
-        // Check
-        let expected = hex::decode("0b061848379c6bccd9e821e63ff6932738835b78e1e10079a0866073eba5b8bb444afbb053d16542e2b839477434966e5a9099093b6b3351f84ac19fe28f096548").unwrap();
-        assert_eq!(agg_public_key, expected);
+        // Not on the curve, and wrong length
+        let mut off_curve = uncompressed.clone();
+        off_curve[63] ^= 1;
+        assert!(signature_from_uncompressed(&off_curve).is_err());
+        assert!(signature_from_uncompressed(&uncompressed[1..]).is_err());
+        assert!(public_key_from_uncompressed(&[0u8; 128]).is_err());
     }
 
-    /// Test `aggregate_signatures`
+    /// Test that non-canonical encodings of the same points are rejected
     #[test]
-    fn test_aggregate_signatures_1() {
-        // Signatures (as valid points on G1)
-        let sign_1 = Bn256.to_compressed_g1(G1::one()).unwrap();
-        let sign_2 = Bn256.to_compressed_g1(G1::one()).unwrap();
-        let signatures = [&sign_1[..], &sign_2[..]];
+    fn test_canonical_decoding() {
+        let bn256 = Bn256::default();
+        let secret_key =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+        let mut modulus = [0u8; 32];
+        Fq::modulus().to_big_endian(&mut modulus).unwrap();
+
+        // Trailing bytes
+        let extended = [&signature[..], &[0]].concat();
+        match Signature::from_compressed(&extended) {
+            Err(Error::TrailingBytes) => {}
+            _ => panic!("signature with trailing bytes accepted"),
+        }
+        let extended = [&public_key[..], &[0]].concat();
+        match PublicKey::from_compressed(&extended) {
+            Err(Error::TrailingBytes) => {}
+            _ => panic!("public key with trailing bytes accepted"),
+        }
 
-        // Aggregation
-        let agg_signature = Bn256
-            .aggregate_signatures(&signatures)
-            .expect("Signature aggregation should not fail if G1 points are valid.");
+        // X not smaller than the field modulus
+        let x = [&[2], &modulus[..]].concat();
+        match Signature::from_compressed(&x) {
+            Err(Error::NonCanonicalCoordinate) => {}
+            _ => panic!("non-canonical X accepted"),
+        }
+        let uncompressed = [&modulus[..], &[0u8; 32]].concat();
+        match signature_from_uncompressed(&uncompressed) {
+            Err(Error::NonCanonicalCoordinate) => {}
+            _ => panic!("non-canonical X accepted"),
+        }
 
-        // Check
-        let expected =
-            hex::decode("02030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3")
+        // Imaginary part of X not smaller than the field modulus
+        let x = [&[10], &[0xff; 64][..]].concat();
+        match PublicKey::from_compressed(&x) {
+            Err(Error::G2CoordinateOutOfRange) => {}
+            _ => panic!("out of range X accepted"),
+        }
+    }
+
+    /// Test detecting the format of public keys and signatures
+    #[test]
+    fn test_from_bytes() {
+        let secret_key =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
                 .unwrap();
-        assert_eq!(agg_signature, expected);
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+        let expected_public_key = PublicKey::from_compressed(&public_key).unwrap();
+        let expected_signature = Signature::from_compressed(&signature).unwrap();
+
+        for encoded in [
+            public_key.clone(),
+            public_key_to_uncompressed(&public_key).unwrap(),
+            flagged::public_key_to_flagged(&public_key).unwrap(),
+        ]
+        .iter()
+        {
+            assert_eq!(PublicKey::from_bytes(encoded).unwrap(), expected_public_key);
+        }
+        for encoded in [
+            signature.clone(),
+            signature_to_uncompressed(&signature).unwrap(),
+            flagged::signature_to_flagged(&signature).unwrap(),
+        ]
+        .iter()
+        {
+            assert_eq!(Signature::from_bytes(encoded).unwrap(), expected_signature);
+        }
+
+        let mut invalid = public_key_to_uncompressed(&public_key).unwrap();
+        invalid[127] ^= 1;
+        match PublicKey::from_bytes(&invalid) {
+            Err(Error::InvalidPointEncoding(PointFormat::Uncompressed)) => {}
+            _ => panic!("Expected InvalidPointEncoding(Uncompressed) error"),
+        }
+        let mut invalid = flagged::signature_to_flagged(&signature).unwrap();
+        invalid[0] &= 0x3f;
+        match Signature::from_bytes(&invalid) {
+            Err(Error::InvalidPointEncoding(PointFormat::Flagged)) => {}
+            _ => panic!("Expected InvalidPointEncoding(Flagged) error"),
+        }
+        match Signature::from_bytes(&public_key) {
+            Err(Error::InvalidLength) => {}
+            _ => panic!("Expected InvalidLength error"),
+        }
     }
 
-    /// Test aggregated signatures verification
+    /// Test validating public keys at the identity, off the curve and out of the subgroup
     #[test]
-    fn test_verify_aggregated_signatures_1() {
-        // Message
-        let msg = hex::decode("73616d706c65").unwrap();
+    fn test_validate_public_key() {
+        let compressed = hex::decode("0a023aed31b5a9e486366ea9988b05dba469c6206e58361d9c065bbea7d928204a761efc6e4fa08ed227650134b52c7f7dd0463963e8a4bf21f4899fe5da7f984a").unwrap();
+        assert!(validate_public_key(&compressed).is_ok());
+        assert!(PublicKey::from_compressed(&compressed)
+            .unwrap()
+            .validate()
+            .is_ok());
+        assert!(validate_public_key(&compressed[1..]).is_err());
+
+        match PublicKey(G2::zero()).validate() {
+            Err(Error::IdentityPublicKey) => {}
+            _ => panic!("Expected IdentityPublicKey error"),
+        }
+        let off_curve = G2::new(Fq2::one(), Fq2::one(), Fq2::one());
+        let out_of_subgroup = hash_to_g2::map_to_curve_g2(Fq2::one()).unwrap();
+        for point in [off_curve, out_of_subgroup].iter() {
+            match PublicKey(*point).validate() {
+                Err(Error::InvalidGroupPoint) => {}
+                _ => panic!("Expected InvalidGroupPoint error"),
+            }
+        }
+    }
 
-        // Signature 1
-        let secret_key1 =
-            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+    /// Test signing with tweaked private keys and verifying with tweaked public keys
+    #[test]
+    fn test_tweak_add() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
                 .unwrap();
-        let public_key1 = Bn256.derive_public_key(&secret_key1).unwrap();
-        let sign_1 = Bn256.sign(&secret_key1, &msg).unwrap();
+        let tweak = hex::decode("0000000000000000000000000000000000000000000000000000000000000007")
+            .unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let tweak_point = bn256.derive_public_key(&tweak).unwrap();
+
+        let tweaked_secret_key = PrivateKey::from_slice(&secret_key)
+            .unwrap()
+            .tweak_add(&tweak)
+            .unwrap()
+            .to_bytes()
+            .unwrap();
+        let tweaked_public_key = PublicKey::from_compressed(&public_key)
+            .unwrap()
+            .tweak_add(&tweak_point)
+            .unwrap()
+            .to_compressed()
+            .unwrap();
+        assert_eq!(
+            bn256.derive_public_key(&tweaked_secret_key).unwrap(),
+            tweaked_public_key
+        );
+        let signature = bn256.sign(&tweaked_secret_key, b"sample").unwrap();
+        assert!(bn256
+            .verify(&signature, b"sample", &tweaked_public_key)
+            .is_ok());
+        assert!(bn256.verify(&signature, b"sample", &public_key).is_err());
+
+        // Tweaking by the negation of the key
+        let private_key = PrivateKey::from_slice(&secret_key).unwrap();
+        let negation = (-private_key.0).into_u256();
+        let mut negation_bytes = [0u8; 32];
+        negation.to_big_endian(&mut negation_bytes).unwrap();
+        match private_key.tweak_add(&negation_bytes) {
+            Err(Error::ZeroSecretKey) => {}
+            _ => panic!("Expected ZeroSecretKey error"),
+        }
+        let negation_point = bn256.derive_public_key(&negation_bytes).unwrap();
+        match PublicKey::from_compressed(&public_key)
+            .unwrap()
+            .tweak_add(&negation_point)
+        {
+            Err(Error::IdentityPublicKey) => {}
+            _ => panic!("Expected IdentityPublicKey error"),
+        }
+    }
 
-        // Signature 2
-        let secret_key2 =
+    /// Test wiping private keys and key shares
+    #[test]
+    fn test_zeroize() {
+        let secret_key =
             hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
                 .unwrap();
-        let public_key2 = Bn256.derive_public_key(&secret_key2).unwrap();
-        let sign_2 = Bn256.sign(&secret_key2, &msg).unwrap();
+        let mut private_key = PrivateKey::from_slice(&secret_key).unwrap();
+        private_key.zeroize();
+        assert!(private_key.0.is_zero());
 
-        // Public Key and Signature aggregation
-        let agg_public_key = Bn256
-            .aggregate_public_keys(&[&public_key1, &public_key2])
+        let mut share = threshold::KeyShare {
+            index: 1,
+            secret_key: secret_key.clone(),
+        };
+        share.zeroize();
+        assert!(share.secret_key.is_empty());
+    }
+
+    /// Test saving and loading encrypted key files
+    #[cfg(feature = "keyfile")]
+    #[test]
+    fn test_save_load_encrypted() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let path = std::env::temp_dir().join("bls-signatures-rs-test.key");
+        PrivateKey::from_slice(&secret_key)
+            .unwrap()
+            .save_encrypted(&path, "password")
             .unwrap();
-        let agg_signature = Bn256.aggregate_signatures(&[&sign_1, &sign_2]).unwrap();
 
-        // Verification single signatures
-        assert!(
-            Bn256.verify(&sign_1, &msg, &public_key1).is_ok(),
-            "Signature 1 verification failed"
-        );
-        assert!(
-            Bn256.verify(&sign_2, &msg, &public_key2).is_ok(),
-            "Signature 2 signature verification failed"
-        );
+        let loaded = PrivateKey::load_encrypted(&path, "password").unwrap();
+        assert_eq!(loaded.to_bytes().unwrap(), secret_key);
+        assert!(PrivateKey::load_encrypted(&path, "wrong password").is_err());
+        std::fs::remove_file(&path).unwrap();
+        match PrivateKey::load_encrypted(&path, "password") {
+            Err(Error::FileError) => {}
+            _ => panic!("Expected FileError error"),
+        }
+    }
 
-        // Aggregated signature verification
-        assert!(
-            Bn256.verify(&agg_signature, &msg, &agg_public_key).is_ok(),
-            "Aggregated signature verification failed"
-        );
+    /// Deterministic generator for testing `PrivateKey::random` (not secure)
+    #[cfg(feature = "rand")]
+    struct TestRng(u64);
+
+    #[cfg(feature = "rand")]
+    impl rand_core::RngCore for TestRng {
+        fn next_u32(&mut self) -> u32 {
+            self.next_u64() as u32
+        }
+
+        fn next_u64(&mut self) -> u64 {
+            self.0 = self.0.wrapping_mul(6364136223846793005).wrapping_add(1);
+            self.0
+        }
+
+        fn fill_bytes(&mut self, dest: &mut [u8]) {
+            rand_core::impls::fill_bytes_via_next(self, dest)
+        }
+
+        fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+            self.fill_bytes(dest);
+            Ok(())
+        }
+    }
+
+    #[cfg(feature = "rand")]
+    impl rand_core::CryptoRng for TestRng {}
+
+    /// Test sampling random private keys
+    #[cfg(feature = "rand")]
+    #[test]
+    fn test_private_key_random() {
+        let mut rng = TestRng(42);
+        let secret_key = PrivateKey::random(&mut rng).to_bytes().unwrap();
+        let other_key = PrivateKey::random(&mut rng).to_bytes().unwrap();
+        assert_eq!(secret_key.len(), 32);
+        assert!(secret_key != other_key);
+
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+        assert!(bn256.verify(&signature, b"sample", &public_key).is_ok());
     }
 }
@@ -0,0 +1,169 @@
+//! Pairings of the typed points of the crate.
+//!
+//! BLS verification is a check of a product of pairings, `e(H(m), PK) * e(-S, G2::one) = 1`. This
+//! module exposes the pairing and the multi-pairing over the typed points (`Signature` and
+//! `HashedMessage` in G1, `PublicKey` in G2, and the generators `G1Generator` and `G2Generator`),
+//! so custom pairing equations, such as the ones of bespoke proofs, can be checked without
+//! depending on the `bn` crate. The points of `bn` are accepted as well.
+//!
+//! `pairing_equation` checks `e(P_1, Q_1) * ... * e(P_n, Q_n) = e(R_1, T_1) * ... * e(R_m, T_m)` as
+//! a single multi-pairing, negating the points of the right-hand side.
+use alloc::vec::Vec;
+use bn::{Group, Gt, G1, G2};
+use core::ops::Mul;
+
+use super::error::Error;
+
+/// Generator of G1, the group of signatures.
+#[derive(Clone, Copy, Debug)]
+pub struct G1Generator;
+
+/// Generator of G2, the group of public keys.
+#[derive(Clone, Copy, Debug)]
+pub struct G2Generator;
+
+impl From<G1Generator> for G1 {
+    fn from(_generator: G1Generator) -> Self {
+        G1::one()
+    }
+}
+
+impl From<G2Generator> for G2 {
+    fn from(_generator: G2Generator) -> Self {
+        G2::one()
+    }
+}
+
+/// Element of the target group of the pairing.
+#[derive(Clone, Copy, PartialEq, Eq)]
+pub struct PairingOutput(Gt);
+
+impl PairingOutput {
+    /// Function to get the identity of the target group.
+    pub fn one() -> Self {
+        PairingOutput(Gt::one())
+    }
+
+    /// Function to check whether the element is the identity of the target group.
+    pub fn is_one(&self) -> bool {
+        self.0 == Gt::one()
+    }
+}
+
+impl Mul for PairingOutput {
+    type Output = PairingOutput;
+
+    fn mul(self, other: PairingOutput) -> PairingOutput {
+        PairingOutput(self.0 * other.0)
+    }
+}
+
+/// Function to compute the pairing `e(P, Q)`.
+///
+/// # Arguments
+///
+/// * `p` - A point in G1, e.g. a `Signature`
+/// * `q` - A point in G2, e.g. a `PublicKey`
+///
+/// # Returns
+///
+/// * The `PairingOutput`
+pub fn pairing<P: Into<G1>, Q: Into<G2>>(p: P, q: Q) -> PairingOutput {
+    PairingOutput(bn::pairing(p.into(), q.into()))
+}
+
+/// Function to compute the product of pairings `e(P_1, Q_1) * ... * e(P_n, Q_n)`, sharing the
+/// final exponentiation.
+///
+/// # Arguments
+///
+/// * `pairs` - The pairs of points in G1 and G2
+///
+/// # Returns
+///
+/// * The `PairingOutput` (the identity if there are no pairs)
+pub fn multi_pairing<P, Q, I>(pairs: I) -> PairingOutput
+where
+    P: Into<G1>,
+    Q: Into<G2>,
+    I: IntoIterator<Item = (P, Q)>,
+{
+    let pairs: Vec<(G1, G2)> = pairs
+        .into_iter()
+        .map(|(p, q)| (p.into(), q.into()))
+        .collect();
+    if pairs.is_empty() {
+        return PairingOutput::one();
+    }
+
+    PairingOutput(bn::pairing_batch(&pairs))
+}
+
+/// Function to check the pairing equation `e(P_1, Q_1) * ... * e(P_n, Q_n) = e(R_1, T_1) * ... *
+/// e(R_m, T_m)` with a single multi-pairing.
+///
+/// # Arguments
+///
+/// * `lhs` - The pairs of points of the left-hand side
+/// * `rhs` - The pairs of points of the right-hand side
+///
+/// # Returns
+///
+/// * If the equation holds, `Ok(())`; otherwise `Error::VerificationFailed`
+pub fn pairing_equation<P, Q, R, T>(lhs: &[(P, Q)], rhs: &[(R, T)]) -> Result<(), Error>
+where
+    P: Into<G1> + Clone,
+    Q: Into<G2> + Clone,
+    R: Into<G1> + Clone,
+    T: Into<G2> + Clone,
+{
+    let pairs = lhs
+        .iter()
+        .map(|(p, q)| (p.clone().into(), q.clone().into()))
+        .chain(
+            rhs.iter()
+                .map(|(r, t)| (-r.clone().into(), t.clone().into())),
+        );
+
+    if multi_pairing::<G1, G2, _>(pairs).is_one() {
+        Ok(())
+    } else {
+        Err(Error::VerificationFailed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::{Bn256, PublicKey, Signature};
+    use crate::MultiSignature;
+
+    /// Test checking BLS verification as a custom pairing equation
+    #[test]
+    fn test_pairing_equation() {
+        let bn256 = Bn256::default();
+        let secret_key = [1u8; 32];
+        let public_key =
+            PublicKey::from_compressed(&bn256.derive_public_key(&secret_key).unwrap()).unwrap();
+        let signature =
+            Signature::from_compressed(&bn256.sign(&secret_key, b"sample").unwrap()).unwrap();
+        let hash = bn256.hash(b"sample").unwrap();
+
+        // e(H(m), PK) = e(S, G2::one)
+        assert!(pairing(hash, public_key.clone()) == pairing(signature.clone(), G2Generator));
+        assert!(pairing_equation(
+            &[(hash, public_key.clone())],
+            &[(signature.clone(), G2Generator)]
+        )
+        .is_ok());
+        assert!(pairing_equation(&[(hash, G2Generator)], &[(signature, G2Generator)]).is_err());
+
+        // Bilinearity: e(P, Q) * e(P, Q) = e(2P, Q)
+        let point = G1::one() + G1::one();
+        assert!(
+            pairing(G1Generator, public_key.clone()) * pairing(G1Generator, public_key.clone())
+                == pairing(point, public_key.clone())
+        );
+        assert!(multi_pairing(Vec::<(G1, G2)>::new()).is_one());
+    }
+}
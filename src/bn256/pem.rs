@@ -0,0 +1,361 @@
+//! DER and PEM encodings of keys and signatures, so they can be stored with existing PKI tooling
+//! and in configuration files.
+//!
+//! The structures follow the ones used for Ed25519 and X25519 keys ([RFC 8410](https://tools.ietf.org/html/rfc8410)):
+//!
+//! - public keys are encoded as `SubjectPublicKeyInfo`, with the compressed point as bit string
+//! - private keys are encoded as PKCS #8 `PrivateKeyInfo` (version 0), with the 32-byte secret key
+//!   wrapped in an octet string
+//! - signatures are encoded as the algorithm identifier followed by the compressed point as bit
+//!   string
+//!
+//! As no object identifier has been registered for BLS signatures over bn256, the algorithm is
+//! identified by the UUID-based OID `2.25.272212461013155649128280242853694704051` (ITU-T X.667),
+//! without parameters. PEM documents are labeled `PUBLIC KEY`, `PRIVATE KEY` and `BLS SIGNATURE`.
+use alloc::string::String;
+use alloc::vec::Vec;
+
+use super::error::Error;
+use super::{g1_from_compressed, PrivateKey, PublicKey};
+use zeroize::Zeroizing;
+
+/// DER encoding of the algorithm identifier OID, `2.25.272212461013155649128280242853694704051`.
+pub const ALGORITHM_OID: &[u8] = &[
+    0x69, 0x83, 0x99, 0xca, 0x9b, 0xa1, 0xd0, 0xc3, 0xaa, 0x84, 0xb1, 0xa5, 0xae, 0xfb, 0xdd, 0xe9,
+    0xcf, 0xc2, 0x93, 0x33,
+];
+
+/// Label of PEM public keys.
+pub const PUBLIC_KEY_LABEL: &str = "PUBLIC KEY";
+
+/// Label of PEM private keys.
+pub const PRIVATE_KEY_LABEL: &str = "PRIVATE KEY";
+
+/// Label of PEM signatures.
+pub const SIGNATURE_LABEL: &str = "BLS SIGNATURE";
+
+/// Label of PEM exported key shares (see `share_export`).
+pub const KEY_SHARE_LABEL: &str = "BLS KEY SHARE";
+
+/// Length of the base64 lines of PEM documents.
+const PEM_LINE_LENGTH: usize = 64;
+
+/// DER tags.
+const TAG_INTEGER: u8 = 0x02;
+const TAG_BIT_STRING: u8 = 0x03;
+const TAG_OCTET_STRING: u8 = 0x04;
+const TAG_OID: u8 = 0x06;
+const TAG_SEQUENCE: u8 = 0x30;
+
+/// Function to encode a public key as a DER `SubjectPublicKeyInfo`.
+///
+/// # Arguments
+///
+/// * `public_key` - The public key bytes (a point in G2 in compressed format)
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the DER encoding; otherwise `Error`
+pub fn public_key_to_der(public_key: &[u8]) -> Result<Vec<u8>, Error> {
+    PublicKey::from_compressed(public_key)?;
+
+    Ok(encode_public_key(public_key))
+}
+
+/// Function to decode a public key from a DER `SubjectPublicKeyInfo`.
+///
+/// # Arguments
+///
+/// * `der` - The DER encoding
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the compressed public key; otherwise
+///   `Error::InvalidPem` if the encoding is malformed or has another algorithm, or the error of
+///   the public key validation
+pub fn public_key_from_der(der: &[u8]) -> Result<Vec<u8>, Error> {
+    let public_key = decode_suffix(der, 65, encode_public_key)?;
+    PublicKey::from_compressed(&public_key)?;
+
+    Ok(public_key)
+}
+
+/// Function to encode a secret key as a DER PKCS #8 `PrivateKeyInfo`.
+///
+/// # Arguments
+///
+/// * `secret_key` - The secret key bytes (32 bytes)
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the DER encoding; otherwise `Error`
+pub fn secret_key_to_der(secret_key: &[u8]) -> Result<Vec<u8>, Error> {
+    PrivateKey::from_slice(secret_key)?;
+
+    Ok(encode_secret_key(secret_key))
+}
+
+/// Function to decode a secret key from a DER PKCS #8 `PrivateKeyInfo`.
+///
+/// # Arguments
+///
+/// * `der` - The DER encoding
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the secret key; otherwise `Error::InvalidPem` if the
+///   encoding is malformed or has another algorithm, or the error of the secret key validation
+pub fn secret_key_from_der(der: &[u8]) -> Result<Vec<u8>, Error> {
+    let secret_key = decode_suffix(der, 32, encode_secret_key)?;
+    PrivateKey::from_slice(&secret_key)?;
+
+    Ok(secret_key)
+}
+
+/// Function to encode a signature in DER.
+///
+/// # Arguments
+///
+/// * `signature` - The signature bytes (a point in G1 in compressed format)
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the DER encoding; otherwise `Error`
+pub fn signature_to_der(signature: &[u8]) -> Result<Vec<u8>, Error> {
+    g1_from_compressed(signature)?;
+
+    Ok(encode_signature(signature))
+}
+
+/// Function to decode a signature from DER.
+///
+/// # Arguments
+///
+/// * `der` - The DER encoding
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the compressed signature; otherwise `Error`
+pub fn signature_from_der(der: &[u8]) -> Result<Vec<u8>, Error> {
+    let signature = decode_suffix(der, 33, encode_signature)?;
+    g1_from_compressed(&signature)?;
+
+    Ok(signature)
+}
+
+/// Function to armor a DER encoding as a PEM document.
+///
+/// # Arguments
+///
+/// * `label` - The label of the document, e.g. `PUBLIC_KEY_LABEL`
+/// * `der`   - The DER encoding
+///
+/// # Returns
+///
+/// * A string with the PEM document, ending with a newline
+pub fn to_pem(label: &str, der: &[u8]) -> String {
+    let encoded = base64::encode(der);
+    let mut pem = String::new();
+    pem.push_str("-----BEGIN ");
+    pem.push_str(label);
+    pem.push_str("-----\n");
+    for line in encoded.as_bytes().chunks(PEM_LINE_LENGTH) {
+        pem.push_str(::core::str::from_utf8(line).expect("base64 is ASCII"));
+        pem.push('\n');
+    }
+    pem.push_str("-----END ");
+    pem.push_str(label);
+    pem.push_str("-----\n");
+
+    pem
+}
+
+/// Function to remove the PEM armor of a document, checking its label.
+///
+/// # Arguments
+///
+/// * `label` - The expected label of the document, e.g. `PUBLIC_KEY_LABEL`
+/// * `pem`   - The PEM document
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the DER encoding; otherwise `Error::InvalidPem`
+pub fn from_pem(label: &str, pem: &str) -> Result<Vec<u8>, Error> {
+    let mut lines = pem.trim().lines().map(str::trim);
+    let begin = lines.next().ok_or(Error::InvalidPem)?;
+    if begin
+        .strip_prefix("-----BEGIN ")
+        .and_then(|l| l.strip_suffix("-----"))
+        != Some(label)
+    {
+        return Err(Error::InvalidPem);
+    }
+
+    let mut encoded = String::new();
+    for line in lines {
+        if let Some(end) = line.strip_prefix("-----END ") {
+            if end.strip_suffix("-----") != Some(label) {
+                return Err(Error::InvalidPem);
+            }
+            return base64::decode(&encoded).map_err(|_| Error::InvalidPem);
+        }
+        encoded.push_str(line);
+    }
+
+    Err(Error::InvalidPem)
+}
+
+/// Function to encode a public key as `SubjectPublicKeyInfo` without validating it.
+fn encode_public_key(public_key: &[u8]) -> Vec<u8> {
+    tlv(
+        TAG_SEQUENCE,
+        &[algorithm_identifier(), bit_string(public_key)].concat(),
+    )
+}
+
+/// Function to encode a secret key as `PrivateKeyInfo` without validating it.
+fn encode_secret_key(secret_key: &[u8]) -> Vec<u8> {
+    let version = tlv(TAG_INTEGER, &[0]);
+    let private_key = Zeroizing::new(tlv(
+        TAG_OCTET_STRING,
+        &Zeroizing::new(tlv(TAG_OCTET_STRING, secret_key)),
+    ));
+    let contents = Zeroizing::new([&version[..], &algorithm_identifier(), &private_key].concat());
+
+    tlv(TAG_SEQUENCE, &contents)
+}
+
+/// Function to encode a signature without validating it.
+fn encode_signature(signature: &[u8]) -> Vec<u8> {
+    tlv(
+        TAG_SEQUENCE,
+        &[algorithm_identifier(), bit_string(signature)].concat(),
+    )
+}
+
+/// Function to decode the fixed-length item at the end of a DER encoding.
+///
+/// DER encodings are unique, so the encoding is valid if and only if it is equal to the encoding
+/// of its last `length` bytes.
+fn decode_suffix(
+    der: &[u8],
+    length: usize,
+    encode: fn(&[u8]) -> Vec<u8>,
+) -> Result<Vec<u8>, Error> {
+    if der.len() < length {
+        return Err(Error::InvalidPem);
+    }
+    let item = &der[der.len() - length..];
+    if *Zeroizing::new(encode(item)) != der {
+        return Err(Error::InvalidPem);
+    }
+
+    Ok(item.to_vec())
+}
+
+/// Function to encode the `AlgorithmIdentifier` (without parameters).
+fn algorithm_identifier() -> Vec<u8> {
+    tlv(TAG_SEQUENCE, &tlv(TAG_OID, ALGORITHM_OID))
+}
+
+/// Function to encode a bit string without unused bits.
+fn bit_string(bytes: &[u8]) -> Vec<u8> {
+    tlv(TAG_BIT_STRING, &[&[0u8][..], bytes].concat())
+}
+
+/// Function to encode a tag, the length of the contents and the contents.
+fn tlv(tag: u8, contents: &[u8]) -> Vec<u8> {
+    // Contents are always shorter than 128 bytes, so the length fits in a single byte
+    let mut encoded = Vec::with_capacity(contents.len() + 2);
+    encoded.push(tag);
+    encoded.push(contents.len() as u8);
+    encoded.extend_from_slice(contents);
+
+    encoded
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::Bn256;
+    use crate::MultiSignature;
+
+    /// Test encoding and decoding keys and signatures in DER
+    #[test]
+    fn test_der() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+
+        let der = secret_key_to_der(&secret_key).unwrap();
+        assert_eq!(
+            hex::encode(&der),
+            "303f02010030160614698399ca9ba1d0c3aa84b1a5aefbdde9cfc29333\
+             042204202009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c"
+        );
+        assert_eq!(secret_key_from_der(&der).unwrap(), secret_key);
+
+        let der = public_key_to_der(&public_key).unwrap();
+        assert_eq!(&der[..4], &[0x30, 0x5c, 0x30, 0x16]);
+        assert_eq!(public_key_from_der(&der).unwrap(), public_key);
+        // Public keys are not secret keys
+        assert!(secret_key_from_der(&der).is_err());
+
+        let der = signature_to_der(&signature).unwrap();
+        assert_eq!(signature_from_der(&der).unwrap(), signature);
+
+        // Another algorithm, a trailing byte and a truncated encoding
+        let mut other_algorithm = der.clone();
+        other_algorithm[7] ^= 1;
+        for invalid in [
+            &other_algorithm[..],
+            &[&der[..], &[0u8][..]].concat()[..],
+            &der[..der.len() - 1],
+        ]
+        .iter()
+        {
+            match signature_from_der(invalid) {
+                Err(Error::InvalidPem) => {}
+                _ => panic!("Expected InvalidPem error"),
+            }
+        }
+        assert!(secret_key_to_der(&[0u8; 32]).is_err());
+    }
+
+    /// Test armoring DER encodings as PEM documents
+    #[test]
+    fn test_pem() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let public_key = Bn256::default().derive_public_key(&secret_key).unwrap();
+        let der = public_key_to_der(&public_key).unwrap();
+
+        let pem = to_pem(PUBLIC_KEY_LABEL, &der);
+        assert!(pem.starts_with("-----BEGIN PUBLIC KEY-----\nMFwwFgYUaYOZypuh0MOqhLGlrvvd6c/CkzMD"));
+        assert!(pem.ends_with("\n-----END PUBLIC KEY-----\n"));
+        assert!(pem.lines().all(|line| line.len() <= 64));
+        assert_eq!(from_pem(PUBLIC_KEY_LABEL, &pem).unwrap(), der);
+        // Surrounding whitespace and CRLF line endings
+        let crlf = alloc::format!("\n{}\n", pem.replace('\n', "\r\n"));
+        assert_eq!(from_pem(PUBLIC_KEY_LABEL, &crlf).unwrap(), der);
+
+        for invalid in [
+            pem.replace("BEGIN PUBLIC KEY", "BEGIN PRIVATE KEY"),
+            pem.replace("END PUBLIC KEY", "END PRIVATE KEY"),
+            pem.replace("MFww", "MFw*"),
+            pem.lines().take(2).collect::<Vec<&str>>().join("\n"),
+        ]
+        .iter()
+        {
+            match from_pem(PUBLIC_KEY_LABEL, invalid) {
+                Err(Error::InvalidPem) => {}
+                _ => panic!("Expected InvalidPem error"),
+            }
+        }
+        assert!(from_pem(PRIVATE_KEY_LABEL, &pem).is_err());
+    }
+}
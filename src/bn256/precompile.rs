@@ -0,0 +1,391 @@
+//! Encoding of the inputs and outputs of the bn256 precompiles of Ethereum (EIP-196 and EIP-197),
+//! so that curve operations can be offloaded to an EVM node or checked by a smart contract.
+//!
+//! - `bn256Add` (`ADD_ADDRESS`): two G1 points, 128 bytes, returns a G1 point
+//! - `bn256ScalarMul` (`SCALAR_MUL_ADDRESS`): a G1 point and a 32-byte scalar, 96 bytes, returns a
+//!   G1 point
+//! - `bn256Pairing` (`PAIRING_ADDRESS`): pairs of a G1 and a G2 point, 192 bytes each, returns 32
+//!   bytes with `1` if the product of the pairings is one and `0` otherwise
+//!
+//! `Signature::to_solidity_coordinates` and `PublicKey::to_solidity_coordinates` give the same
+//! coordinates as 32-byte words, ready to be ABI-encoded as `uint256[2]` and `uint256[2][2]`.
+//!
+//! Points use the encoding of the `geth` module: `X || Y` for G1 and `X.imaginary || X.real ||
+//! Y.imaginary || Y.real` for G2, with the point at infinity encoded as zeros.
+use alloc::vec::Vec;
+use bn::{Group, G1, G2};
+use digest::generic_array::typenum::U32;
+use digest::Digest;
+
+use super::error::Error;
+use super::geth::{marshal_g2, G1_LENGTH, G2_LENGTH};
+use super::{
+    g1_from_compressed, g1_from_uncompressed, g1_to_compressed, g1_to_uncompressed,
+    g2_from_compressed, Bn256, PublicKey, Signature,
+};
+
+/// Address of the `bn256Add` precompile.
+pub const ADD_ADDRESS: u8 = 0x06;
+
+/// Address of the `bn256ScalarMul` precompile.
+pub const SCALAR_MUL_ADDRESS: u8 = 0x07;
+
+/// Address of the `bn256Pairing` precompile.
+pub const PAIRING_ADDRESS: u8 = 0x08;
+
+/// Length of the scalars of `bn256ScalarMul` in bytes.
+pub const SCALAR_LENGTH: usize = 32;
+
+/// Length of the output of `bn256Pairing` in bytes.
+pub const PAIRING_OUTPUT_LENGTH: usize = 32;
+
+/// A big endian 256-bit word, as a `uint256` in Solidity.
+pub type Word = [u8; 32];
+
+/// Function to format the input of `bn256Add`.
+///
+/// # Arguments
+///
+/// * `a` - The first point (a point in G1 in compressed format)
+/// * `b` - The second point (a point in G1 in compressed format)
+///
+/// # Returns
+///
+/// * If successful, a vector of 128 bytes with the input; otherwise `Error`
+pub fn add_input(a: &[u8], b: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut input = Vec::with_capacity(2 * G1_LENGTH);
+    for point in [a, b].iter() {
+        input.extend_from_slice(&marshal_g1(g1_from_compressed(point)?)?);
+    }
+
+    Ok(input)
+}
+
+/// Function to format the input of `bn256ScalarMul`.
+///
+/// # Arguments
+///
+/// * `point`  - The point (a point in G1 in compressed format)
+/// * `scalar` - The scalar (32 big endian bytes)
+///
+/// # Returns
+///
+/// * If successful, a vector of 96 bytes with the input; otherwise `Error`
+pub fn scalar_mul_input(point: &[u8], scalar: &[u8]) -> Result<Vec<u8>, Error> {
+    if scalar.len() != SCALAR_LENGTH {
+        return Err(Error::InvalidLength);
+    }
+    let mut input = marshal_g1(g1_from_compressed(point)?)?;
+    input.extend_from_slice(scalar);
+
+    Ok(input)
+}
+
+/// Function to format the input of `bn256Pairing`.
+///
+/// # Arguments
+///
+/// * `pairs` - The pairs of points (a point in G1 and a point in G2, both in compressed format)
+///
+/// # Returns
+///
+/// * If successful, a vector of 192 bytes per pair with the input; otherwise `Error`
+pub fn pairing_input(pairs: &[(&[u8], &[u8])]) -> Result<Vec<u8>, Error> {
+    let mut input = Vec::with_capacity(pairs.len() * (G1_LENGTH + G2_LENGTH));
+    for (g1, g2) in pairs {
+        push_pair(&mut input, g1_from_compressed(g1)?, g2_from_compressed(g2)?)?;
+    }
+
+    Ok(input)
+}
+
+/// Function to parse the output of `bn256Add` or `bn256ScalarMul`.
+///
+/// # Arguments
+///
+/// * `output` - The output of the precompile (64 bytes)
+///
+/// # Returns
+///
+/// * If successful, the point in G1 in compressed format, or `None` for the point at infinity;
+///   otherwise `Error`
+pub fn parse_point_output(output: &[u8]) -> Result<Option<Vec<u8>>, Error> {
+    if output.len() != G1_LENGTH {
+        return Err(Error::InvalidLength);
+    }
+    if output.iter().all(|&byte| byte == 0) {
+        return Ok(None);
+    }
+
+    Ok(Some(g1_to_compressed(g1_from_uncompressed(output)?)?))
+}
+
+/// Function to parse the output of `bn256Pairing`.
+///
+/// # Arguments
+///
+/// * `output` - The output of the precompile (32 bytes)
+///
+/// # Returns
+///
+/// * If successful, whether the product of the pairings is one; otherwise `Error`
+pub fn parse_pairing_output(output: &[u8]) -> Result<bool, Error> {
+    if output.len() != PAIRING_OUTPUT_LENGTH {
+        return Err(Error::InvalidLength);
+    }
+    let (padding, last) = output.split_at(PAIRING_OUTPUT_LENGTH - 1);
+    if padding.iter().any(|&byte| byte != 0) || last[0] > 1 {
+        return Err(Error::InvalidEncoding);
+    }
+
+    Ok(last[0] == 1)
+}
+
+/// Function to check the output of `bn256Pairing` for the input of
+/// `Bn256::verify_precompile_input`.
+///
+/// # Arguments
+///
+/// * `output` - The output of the precompile (32 bytes)
+///
+/// # Returns
+///
+/// * If the signature is valid, `Ok(())`; otherwise `Error::VerificationFailed`, or another
+///   `Error` if the output is malformed
+pub fn verify_pairing_output(output: &[u8]) -> Result<(), Error> {
+    if parse_pairing_output(output)? {
+        Ok(())
+    } else {
+        Err(Error::VerificationFailed)
+    }
+}
+
+impl<D: Digest<OutputSize = U32>> Bn256<D> {
+    /// Function to format the input of `bn256Pairing` that verifies a signature, which returns
+    /// `1` if the signature is valid, i.e. `e(H(m), PK) * e(-Signature, G2::one()) = 1`.
+    ///
+    /// The input is the complete calldata of a call to the precompile at `PAIRING_ADDRESS`, whose
+    /// output can be checked with `verify_pairing_output`. The message is hashed as in
+    /// `Bn256::sign`, including the public key for the schemes that bind it.
+    ///
+    /// # Arguments
+    ///
+    /// * `signature`   - The signature bytes (a point in G1 in compressed format)
+    /// * `message`     - The message bytes
+    /// * `public_key`  - The public key bytes (a point in G2 in compressed format)
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of 384 bytes with the input; otherwise `Error`
+    pub fn verify_precompile_input(
+        &self,
+        signature: &[u8],
+        message: &[u8],
+        public_key: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        let PublicKey(public_key_point) = PublicKey::from_compressed(public_key)?;
+        let signature_point = g1_from_compressed(signature)?;
+        let hash_point = self.hash_message(message, public_key)?;
+
+        let mut input = Vec::with_capacity(2 * (G1_LENGTH + G2_LENGTH));
+        push_pair(&mut input, hash_point, public_key_point)?;
+        push_pair(&mut input, -signature_point, G2::one())?;
+
+        Ok(input)
+    }
+}
+
+impl Signature {
+    /// Function to get the affine coordinates of the signature as the big endian words
+    /// `(x, y)` of a `uint256[2]` in Solidity.
+    pub fn to_solidity_coordinates(&self) -> Result<(Word, Word), Error> {
+        let bytes = marshal_g1(self.0)?;
+
+        Ok((word(&bytes[..32]), word(&bytes[32..])))
+    }
+}
+
+impl PublicKey {
+    /// Function to get the affine coordinates of the public key as the big endian words
+    /// `([x_im, x_re], [y_im, y_re])` of a `uint256[2][2]` in Solidity, the order expected by
+    /// Solidity pairing libraries and by `bn256Pairing`.
+    pub fn to_solidity_coordinates(&self) -> Result<([Word; 2], [Word; 2]), Error> {
+        let bytes = marshal_g2(self.0)?;
+
+        Ok((
+            [word(&bytes[..32]), word(&bytes[32..64])],
+            [word(&bytes[64..96]), word(&bytes[96..])],
+        ))
+    }
+}
+
+/// Function to copy a 32-byte word.
+fn word(bytes: &[u8]) -> Word {
+    let mut word = [0u8; 32];
+    word.copy_from_slice(bytes);
+
+    word
+}
+
+/// Function to marshal a `G1` point, encoding the point at infinity as zeros.
+fn marshal_g1(point: G1) -> Result<Vec<u8>, Error> {
+    if point.is_zero() {
+        Ok([0u8; G1_LENGTH].to_vec())
+    } else {
+        g1_to_uncompressed(point)
+    }
+}
+
+/// Function to append a pair of points to the input of `bn256Pairing`.
+fn push_pair(input: &mut Vec<u8>, g1: G1, g2: G2) -> Result<(), Error> {
+    input.extend_from_slice(&marshal_g1(g1)?);
+    input.extend_from_slice(&marshal_g2(g2)?);
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::geth::{marshal_public_key, unmarshal_public_key};
+    use crate::bn256::signature_to_uncompressed;
+    use crate::MultiSignature;
+    use bn::{pairing_batch, Fr, Gt};
+
+    /// Evaluates `bn256Pairing` as the precompile does
+    fn pairing(input: &[u8]) -> Vec<u8> {
+        let mut pairs = Vec::new();
+        for chunk in input.chunks(G1_LENGTH + G2_LENGTH) {
+            let g1 = g1_from_uncompressed(&chunk[..G1_LENGTH]).unwrap();
+            let g2 = unmarshal_public_key(&chunk[G1_LENGTH..]).unwrap();
+            pairs.push((g1, PublicKey::from_compressed(&g2).unwrap().0));
+        }
+        let mut output = [0u8; PAIRING_OUTPUT_LENGTH];
+        output[31] = (pairing_batch(&pairs) == Gt::one()) as u8;
+
+        output.to_vec()
+    }
+
+    /// Test formatting the inputs of `bn256Add` and `bn256ScalarMul` with the EIP-196 generator
+    #[test]
+    fn test_add_and_scalar_mul_input() {
+        let generator = g1_to_compressed(G1::one()).unwrap();
+        let input = add_input(&generator, &generator).unwrap();
+        assert_eq!(
+            hex::encode(&input),
+            "0000000000000000000000000000000000000000000000000000000000000001\
+             0000000000000000000000000000000000000000000000000000000000000002\
+             0000000000000000000000000000000000000000000000000000000000000001\
+             0000000000000000000000000000000000000000000000000000000000000002"
+        );
+
+        let mut scalar = [0u8; SCALAR_LENGTH];
+        scalar[31] = 2;
+        let input = scalar_mul_input(&generator, &scalar).unwrap();
+        assert_eq!(
+            &input[..G1_LENGTH],
+            &add_input(&generator, &generator).unwrap()[..G1_LENGTH]
+        );
+        assert_eq!(&input[G1_LENGTH..], &scalar[..]);
+        assert!(scalar_mul_input(&generator, &scalar[1..]).is_err());
+
+        // Output of both precompiles for the inputs above: 2 * G1::one()
+        let double = G1::one() * Fr::from_str("2").unwrap();
+        let output = g1_to_uncompressed(double).unwrap();
+        assert_eq!(
+            hex::encode(&output),
+            "030644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd3\
+             15ed738c0e0a7c92e7845f96b2ae9c0a68a6a449e3538fc7ff3ebf7a5a18a2c4"
+        );
+        assert_eq!(
+            parse_point_output(&output).unwrap(),
+            Some(g1_to_compressed(double).unwrap())
+        );
+        assert_eq!(parse_point_output(&[0u8; G1_LENGTH]).unwrap(), None);
+        assert!(parse_point_output(&output[1..]).is_err());
+    }
+
+    /// Test offloading the verification of a signature to `bn256Pairing`
+    #[test]
+    fn test_verify_precompile_input() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+
+        let input = bn256
+            .verify_precompile_input(&signature, b"sample", &public_key)
+            .unwrap();
+        assert_eq!(input.len(), 2 * (G1_LENGTH + G2_LENGTH));
+        assert!(parse_pairing_output(&pairing(&input)).unwrap());
+        assert!(verify_pairing_output(&pairing(&input)).is_ok());
+
+        let input = bn256
+            .verify_precompile_input(&signature, b"other", &public_key)
+            .unwrap();
+        assert!(!parse_pairing_output(&pairing(&input)).unwrap());
+        match verify_pairing_output(&pairing(&input)) {
+            Err(Error::VerificationFailed) => {}
+            _ => panic!("Expected VerificationFailed error"),
+        }
+        assert!(verify_pairing_output(&[]).is_err());
+
+        // Public key binding changes the hashed message and thus the calldata
+        let bound = Bn256::default()
+            .with_public_key_binding(true)
+            .verify_precompile_input(&signature, b"sample", &public_key)
+            .unwrap();
+        let unbound = bn256
+            .verify_precompile_input(&signature, b"sample", &public_key)
+            .unwrap();
+        assert_ne!(&bound[..G1_LENGTH], &unbound[..G1_LENGTH]);
+        assert_eq!(&bound[G1_LENGTH..], &unbound[G1_LENGTH..]);
+
+        let generator = PublicKey(G2::one()).to_compressed().unwrap();
+        let input = pairing_input(&[(&signature, &generator)]).unwrap();
+        assert_eq!(
+            input,
+            [
+                signature_to_uncompressed(&signature).unwrap(),
+                marshal_public_key(&generator).unwrap()
+            ]
+            .concat()
+        );
+
+        let mut output = [0u8; PAIRING_OUTPUT_LENGTH];
+        output[31] = 2;
+        assert!(parse_pairing_output(&output).is_err());
+        assert!(parse_pairing_output(&output[1..]).is_err());
+    }
+
+    /// Test exporting the coordinates of the generators for Solidity
+    #[test]
+    fn test_solidity_coordinates() {
+        let (x, y) = Signature(G1::one()).to_solidity_coordinates().unwrap();
+        let mut one = [0u8; 32];
+        one[31] = 1;
+        let mut two = [0u8; 32];
+        two[31] = 2;
+        assert_eq!((x, y), (one, two));
+
+        let (x, y) = PublicKey(G2::one()).to_solidity_coordinates().unwrap();
+        assert_eq!(
+            hex::encode(x[0]),
+            "198e9393920d483a7260bfb731fb5d25f1aa493335a9e71297e485b7aef312c2"
+        );
+        assert_eq!(
+            hex::encode(x[1]),
+            "1800deef121f1e76426a00665e5c4479674322d4f75edadd46debd5cd992f6ed"
+        );
+        assert_eq!(
+            hex::encode(y[0]),
+            "090689d0585ff075ec9e99ad690c3395bc4b313370b38ef355acdadcd122975b"
+        );
+        assert_eq!(
+            hex::encode(y[1]),
+            "12c85ea5db8c6deb4aab71808dcb408fe3d1e7690c43d37b4ce6cc0166fa7daa"
+        );
+    }
+}
@@ -0,0 +1,308 @@
+//! Proactive refresh of threshold BLS keys, keeping the group public key unchanged.
+//!
+//! Every member of the committee deals a random sharing of zero with `deal_refresh`, i.e. a
+//! polynomial `g(x) = b_1 * x + ... + b_(t-1) * x^(t-1)`, publishing the verification vector
+//! `[b_1 * G2, ..., b_(t-1) * G2]` (the constant term is always the identity, so it is omitted).
+//! Every member checks and adds the shares it receives to its key share with
+//! `RefreshingMember`, which also updates the verification vector.
+//!
+//! The new shares are a sharing of the same secret key, but they cannot be combined with the old
+//! ones. Shares stolen before a refresh therefore become useless once the refresh completes, as
+//! long as fewer than `t` shares were stolen between two consecutive refreshes. The old shares
+//! should be erased after the refresh.
+use alloc::vec::Vec;
+use bn::{Fr, Group, G2};
+use sha2::Sha256;
+
+use super::dkg::Deal;
+use super::error::Error;
+use super::hash_to_field::hash_to_field;
+use super::threshold::{
+    evaluate_polynomial, index_to_scalar, scalar_to_bytes, KeyShare, ThresholdParameters,
+};
+use super::{core, g2_from_compressed, PublicKey};
+
+/// Domain separation tag used for deriving the coefficients of the refresh polynomial.
+const REFRESH_DST: &[u8] = b"BLS_THRESHOLD_REFRESH_BN254_XMD:SHA-256_";
+
+/// Function to deal a sharing of zero among the members of the committee.
+///
+/// # Arguments
+///
+/// * `dealer`       - The index of the dealer
+/// * `seed`         - The secret bytes from which the polynomial is derived, which must be chosen
+///   uniformly at random for every refresh
+/// * `threshold`    - The number of shares required to sign
+/// * `participants` - The number of members of the committee
+///
+/// # Returns
+///
+/// * If successful, the `Deal` of the member, without the constant term of the verification vector
+pub fn deal_refresh(
+    dealer: u64,
+    seed: &[u8],
+    threshold: usize,
+    participants: usize,
+) -> Result<Deal, Error> {
+    ThresholdParameters::new(threshold, participants)?;
+    if seed.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+    let mut coefficients = vec![Fr::zero()];
+    if threshold > 1 {
+        coefficients.extend(hash_to_field::<Fr, Sha256>(
+            seed,
+            REFRESH_DST,
+            threshold - 1,
+        )?);
+    }
+
+    let shares = (1..=participants as u64)
+        .map(|index| {
+            let share = evaluate_polynomial(&coefficients, index_to_scalar(index));
+            Ok(KeyShare {
+                index,
+                secret_key: scalar_to_bytes(share)?,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let verification_vector = coefficients[1..]
+        .iter()
+        .map(|&coefficient| PublicKey(core::sk_to_pk(coefficient)).to_compressed())
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok(Deal {
+        dealer,
+        verification_vector,
+        shares,
+    })
+}
+
+/// A refresh share received and verified by a member.
+struct ReceivedDeal {
+    /// Index of the dealer
+    dealer: u64,
+    /// Share of zero of the member
+    share: Fr,
+    /// Points committing to the non-constant coefficients of the polynomial of the dealer
+    verification_vector: Vec<G2>,
+}
+
+/// The state of a member of the committee during a refresh.
+pub struct RefreshingMember {
+    /// Current key share of the member
+    share: KeyShare,
+    /// Current verification vector of the committee
+    verification_vector: Vec<G2>,
+    /// Deals verified so far
+    received: Vec<ReceivedDeal>,
+}
+
+impl RefreshingMember {
+    /// Function to create the state of a member from its current key share.
+    ///
+    /// # Arguments
+    ///
+    /// * `share`               - The current key share of the member
+    /// * `verification_vector` - The current verification vector of the committee
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `RefreshingMember`; otherwise `Error`
+    pub fn new(share: &KeyShare, verification_vector: &[&[u8]]) -> Result<Self, Error> {
+        if verification_vector.is_empty() {
+            return Err(Error::InvalidLength);
+        }
+        let verification_vector = verification_vector
+            .iter()
+            .map(|&commitment| g2_from_compressed(commitment))
+            .collect::<Result<Vec<G2>, Error>>()?;
+
+        Ok(RefreshingMember {
+            share: share.clone(),
+            verification_vector,
+            received: Vec::new(),
+        })
+    }
+
+    /// Function to verify and store the share of zero received from a dealer.
+    ///
+    /// # Arguments
+    ///
+    /// * `dealer`              - The index of the dealer
+    /// * `verification_vector` - The verification vector broadcast by the dealer
+    /// * `share`               - The share sent by the dealer to this member
+    ///
+    /// # Returns
+    ///
+    /// * If the share is valid, `Ok(())`; otherwise `Error` (the dealer should be excluded)
+    pub fn receive_deal(
+        &mut self,
+        dealer: u64,
+        verification_vector: &[&[u8]],
+        share: &KeyShare,
+    ) -> Result<(), Error> {
+        if self.received.iter().any(|deal| deal.dealer == dealer) {
+            return Err(Error::DuplicateShareIndex);
+        }
+        if verification_vector.len() + 1 != self.verification_vector.len()
+            || share.index != self.share.index
+        {
+            return Err(Error::InvalidShare);
+        }
+        let verification_vector = verification_vector
+            .iter()
+            .map(|&commitment| g2_from_compressed(commitment))
+            .collect::<Result<Vec<G2>, Error>>()?;
+        // g(i) * G2 = i * (B_1 + i * (B_2 + ...)), as the constant term is zero
+        let x = index_to_scalar(share.index);
+        let expected = verification_vector
+            .iter()
+            .rev()
+            .fold(G2::zero(), |acc, &commitment| (acc + commitment) * x);
+        let secret = Fr::from_slice(&share.secret_key)?;
+        if core::sk_to_pk(secret) != expected {
+            return Err(Error::InvalidShare);
+        }
+
+        self.received.push(ReceivedDeal {
+            dealer,
+            share: secret,
+            verification_vector,
+        });
+
+        Ok(())
+    }
+
+    /// Function to compute the refreshed key share of the member and verification vector.
+    ///
+    /// All the members must use the same set of dealers.
+    ///
+    /// # Arguments
+    ///
+    /// * `dealers` - The indices of the dealers whose deals are used
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the refreshed key share of the member and the refreshed verification
+    ///   vector, whose first entry is the (unchanged) group public key
+    pub fn finalize(&self, dealers: &[u64]) -> Result<(KeyShare, Vec<Vec<u8>>), Error> {
+        if dealers.is_empty() {
+            return Err(Error::InvalidLength);
+        }
+        let mut sorted = dealers.to_vec();
+        sorted.sort_unstable();
+        if sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(Error::DuplicateShareIndex);
+        }
+
+        let mut secret = Fr::from_slice(&self.share.secret_key)?;
+        let mut verification_vector = self.verification_vector.clone();
+        for &dealer in dealers {
+            let deal = self
+                .received
+                .iter()
+                .find(|deal| deal.dealer == dealer)
+                .ok_or(Error::MissingDeal)?;
+            secret = secret + deal.share;
+            for (sum, &commitment) in verification_vector[1..]
+                .iter_mut()
+                .zip(&deal.verification_vector)
+            {
+                *sum = *sum + commitment;
+            }
+        }
+        let verification_vector = verification_vector
+            .into_iter()
+            .map(|commitment| PublicKey(commitment).to_compressed())
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok((
+            KeyShare {
+                index: self.share.index,
+                secret_key: scalar_to_bytes(secret)?,
+            },
+            verification_vector,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::threshold::{combine, split, verify_key_share};
+    use crate::bn256::Bn256;
+    use crate::MultiSignature;
+
+    /// Test refreshing the shares of a 2-of-3 key
+    #[test]
+    fn test_refresh() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let (shares, verification_vector) = split(&secret_key, b"seed", 2, 3).unwrap();
+        let verification_vector: Vec<&[u8]> = verification_vector.iter().map(|v| &v[..]).collect();
+
+        let mut members: Vec<RefreshingMember> = shares
+            .iter()
+            .map(|share| RefreshingMember::new(share, &verification_vector).unwrap())
+            .collect();
+        let seeds: [&[u8]; 3] = [
+            b"seed of member 1",
+            b"seed of member 2",
+            b"seed of member 3",
+        ];
+        let deals: Vec<Deal> = (1..=3)
+            .zip(&seeds)
+            .map(|(dealer, seed)| deal_refresh(dealer, seed, 2, 3).unwrap())
+            .collect();
+        for deal in &deals {
+            assert_eq!(deal.verification_vector.len(), 1);
+            let deal_vector: Vec<&[u8]> = deal.verification_vector.iter().map(|v| &v[..]).collect();
+            for (member, share) in members.iter_mut().zip(&deal.shares) {
+                assert!(member
+                    .receive_deal(deal.dealer, &deal_vector, share)
+                    .is_ok());
+            }
+        }
+        // A share of a nonzero secret is rejected
+        let mut forged = deals[0].shares[0].clone();
+        forged.secret_key = deals[1].shares[0].secret_key.clone();
+        let deal_vector: Vec<&[u8]> = deals[0]
+            .verification_vector
+            .iter()
+            .map(|v| &v[..])
+            .collect();
+        assert!(RefreshingMember::new(&shares[0], &verification_vector)
+            .unwrap()
+            .receive_deal(1, &deal_vector, &forged)
+            .is_err());
+
+        let outputs: Vec<(KeyShare, Vec<Vec<u8>>)> = members
+            .iter()
+            .map(|member| member.finalize(&[1, 2, 3]).unwrap())
+            .collect();
+        assert!(outputs.iter().all(|output| output.1 == outputs[0].1));
+        assert_eq!(outputs[0].1[0], verification_vector[0]);
+        let new_verification_vector: Vec<&[u8]> = outputs[0].1.iter().map(|v| &v[..]).collect();
+        for (output, share) in outputs.iter().zip(&shares) {
+            assert!(output.0 != *share);
+            assert!(verify_key_share(&output.0, &new_verification_vector).is_ok());
+        }
+
+        // New shares sign under the same key, but cannot be combined with old ones
+        let msg: &[u8] = b"sample";
+        let bn256 = Bn256::default();
+        let new_1 = bn256.sign_share(&outputs[0].0, msg).unwrap();
+        let new_3 = bn256.sign_share(&outputs[2].0, msg).unwrap();
+        let old_3 = bn256.sign_share(&shares[2], msg).unwrap();
+        let signature = combine(&[new_1.clone(), new_3]).unwrap();
+        assert!(bn256
+            .verify(&signature, msg, verification_vector[0])
+            .is_ok());
+        let signature = combine(&[new_1, old_3]).unwrap();
+        assert!(bn256
+            .verify(&signature, msg, verification_vector[0])
+            .is_err());
+    }
+}
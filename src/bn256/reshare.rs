@@ -0,0 +1,301 @@
+//! Resharing of threshold BLS keys to a new committee, keeping the group public key unchanged.
+//!
+//! Given a `t`-of-`n` sharing of a secret key (see `threshold` and `dkg`), at least `t` members of
+//! the old committee share their own key shares among the `n'` members of the new committee with
+//! a new threshold `t'`:
+//!
+//! 1. **Deal**: every old member `i` calls `reshare` with its key share and a fresh random seed,
+//!    broadcasts the verification vector and sends every sub-share privately to its recipient.
+//! 2. **Verify deals**: every new member checks the sub-shares it receives with
+//!    `NewMember::receive_deal`, which also checks that the dealer reshared its actual key share
+//!    (the first entry of the verification vector must be the public key of the share of `i`
+//!    under the old verification vector).
+//! 3. **Aggregate**: once the new members agree on a set of at least `t` valid dealers,
+//!    `NewMember::finalize` interpolates the sub-shares with the Lagrange coefficients of those
+//!    dealers, producing a `t'`-of-`n'` sharing of the same secret key.
+//!
+//! The shares of the old committee should be erased afterwards, as they remain valid for signing
+//! under the same group public key.
+use alloc::vec::Vec;
+use bn::{Fr, Group, G2};
+
+use super::dkg::Deal;
+use super::error::Error;
+use super::threshold::{
+    lagrange_coefficients, scalar_to_bytes, share_public_key_point, split, verify_key_share,
+    KeyShare, ThresholdParameters,
+};
+use super::{g2_from_compressed, PublicKey};
+
+/// Function to reshare the key share of an old member among the members of a new committee.
+///
+/// # Arguments
+///
+/// * `share`        - The key share of the old member
+/// * `seed`         - The secret bytes from which the polynomial is derived, which must be chosen
+///   uniformly at random for every resharing
+/// * `threshold`    - The number of shares required to sign in the new committee
+/// * `participants` - The number of members of the new committee
+///
+/// # Returns
+///
+/// * If successful, the `Deal` of the old member
+pub fn reshare(
+    share: &KeyShare,
+    seed: &[u8],
+    threshold: usize,
+    participants: usize,
+) -> Result<Deal, Error> {
+    let (shares, verification_vector) = split(&share.secret_key, seed, threshold, participants)?;
+
+    Ok(Deal {
+        dealer: share.index,
+        verification_vector,
+        shares,
+    })
+}
+
+/// A sub-share received and verified by a new member.
+struct ReceivedDeal {
+    /// Index of the old member
+    dealer: u64,
+    /// Sub-share of the new member
+    share: Fr,
+    /// Points committing to the polynomial of the old member
+    verification_vector: Vec<G2>,
+}
+
+/// The state of a member of the new committee during resharing.
+pub struct NewMember {
+    /// Index of the member in the new committee (starting at `1`)
+    index: u64,
+    /// Number of shares required to sign in the new committee
+    threshold: usize,
+    /// Compressed verification vector of the old committee
+    old_verification_vector: Vec<Vec<u8>>,
+    /// Deals verified so far
+    received: Vec<ReceivedDeal>,
+}
+
+impl NewMember {
+    /// Function to create the state of a member of the new committee.
+    ///
+    /// # Arguments
+    ///
+    /// * `index`                   - The index of the member (between 1 and `participants`)
+    /// * `threshold`               - The number of shares required to sign in the new committee
+    /// * `participants`            - The number of members of the new committee
+    /// * `old_verification_vector` - The verification vector of the old committee
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `NewMember`; otherwise `Error`
+    pub fn new(
+        index: u64,
+        threshold: usize,
+        participants: usize,
+        old_verification_vector: &[&[u8]],
+    ) -> Result<Self, Error> {
+        ThresholdParameters::new(threshold, participants)?.check_index(index)?;
+        if old_verification_vector.is_empty() {
+            return Err(Error::InvalidLength);
+        }
+
+        Ok(NewMember {
+            index,
+            threshold,
+            old_verification_vector: old_verification_vector.iter().map(|v| v.to_vec()).collect(),
+            received: Vec::new(),
+        })
+    }
+
+    /// Function to verify and store the sub-share received from an old member.
+    ///
+    /// # Arguments
+    ///
+    /// * `dealer`              - The index of the old member
+    /// * `verification_vector` - The verification vector broadcast by the old member
+    /// * `share`               - The sub-share sent by the old member to this member
+    ///
+    /// # Returns
+    ///
+    /// * If the sub-share is valid, `Ok(())`; otherwise `Error` (the dealer should be excluded)
+    pub fn receive_deal(
+        &mut self,
+        dealer: u64,
+        verification_vector: &[&[u8]],
+        share: &KeyShare,
+    ) -> Result<(), Error> {
+        if self.received.iter().any(|deal| deal.dealer == dealer) {
+            return Err(Error::DuplicateShareIndex);
+        }
+        if verification_vector.len() != self.threshold || share.index != self.index {
+            return Err(Error::InvalidShare);
+        }
+        // The dealer must reshare its own key share of the old committee
+        let old_verification_vector: Vec<&[u8]> = self
+            .old_verification_vector
+            .iter()
+            .map(|v| &v[..])
+            .collect();
+        let old_public_key = share_public_key_point(&old_verification_vector, dealer)?;
+        if g2_from_compressed(verification_vector[0])? != old_public_key {
+            return Err(Error::InvalidShare);
+        }
+        verify_key_share(share, verification_vector)?;
+
+        let verification_vector = verification_vector
+            .iter()
+            .map(|&commitment| g2_from_compressed(commitment))
+            .collect::<Result<Vec<G2>, Error>>()?;
+        self.received.push(ReceivedDeal {
+            dealer,
+            share: Fr::from_slice(&share.secret_key)?,
+            verification_vector,
+        });
+
+        Ok(())
+    }
+
+    /// Function to compute the new key share of the member and the new verification vector.
+    ///
+    /// All the members must use the same set of dealers, which must contain at least as many old
+    /// members as the threshold of the old committee.
+    ///
+    /// # Arguments
+    ///
+    /// * `dealers` - The indices of the old members whose deals are used
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the new key share of the member and the new verification vector, whose
+    ///   first entry is the (unchanged) group public key
+    pub fn finalize(&self, dealers: &[u64]) -> Result<(KeyShare, Vec<Vec<u8>>), Error> {
+        if dealers.len() < self.old_verification_vector.len() {
+            return Err(Error::InvalidThreshold);
+        }
+        let coefficients = lagrange_coefficients(dealers)?;
+
+        let mut secret = Fr::zero();
+        let mut verification_vector = vec![G2::zero(); self.threshold];
+        for (&dealer, &coefficient) in dealers.iter().zip(&coefficients) {
+            let deal = self
+                .received
+                .iter()
+                .find(|deal| deal.dealer == dealer)
+                .ok_or(Error::MissingDeal)?;
+            secret = secret + deal.share * coefficient;
+            for (sum, &commitment) in verification_vector
+                .iter_mut()
+                .zip(&deal.verification_vector)
+            {
+                *sum = *sum + commitment * coefficient;
+            }
+        }
+        let verification_vector = verification_vector
+            .into_iter()
+            .map(|commitment| PublicKey(commitment).to_compressed())
+            .collect::<Result<Vec<_>, Error>>()?;
+
+        Ok((
+            KeyShare {
+                index: self.index,
+                secret_key: scalar_to_bytes(secret)?,
+            },
+            verification_vector,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::threshold::combine;
+    use crate::bn256::Bn256;
+    use crate::MultiSignature;
+
+    /// Test resharing a 2-of-3 key to a 3-of-4 committee
+    #[test]
+    fn test_reshare() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let (old_shares, old_verification_vector) = split(&secret_key, b"seed", 2, 3).unwrap();
+        let old_verification_vector: Vec<&[u8]> =
+            old_verification_vector.iter().map(|v| &v[..]).collect();
+
+        let mut members: Vec<NewMember> = (1..=4)
+            .map(|index| NewMember::new(index, 3, 4, &old_verification_vector).unwrap())
+            .collect();
+        let deals: Vec<Deal> = [&old_shares[0], &old_shares[2]]
+            .iter()
+            .map(|&share| reshare(share, b"seed", 3, 4).unwrap())
+            .collect();
+        for deal in &deals {
+            let verification_vector: Vec<&[u8]> =
+                deal.verification_vector.iter().map(|v| &v[..]).collect();
+            for (member, share) in members.iter_mut().zip(&deal.shares) {
+                assert!(member
+                    .receive_deal(deal.dealer, &verification_vector, share)
+                    .is_ok());
+            }
+        }
+
+        // Old member 2 reshares a key different from its share
+        let forged = reshare(&old_shares[0], b"seed", 3, 4).unwrap();
+        let verification_vector: Vec<&[u8]> =
+            forged.verification_vector.iter().map(|v| &v[..]).collect();
+        match members[0].receive_deal(2, &verification_vector, &forged.shares[0]) {
+            Err(Error::InvalidShare) => {}
+            _ => panic!("Expected InvalidShare error"),
+        }
+        assert!(members[0].finalize(&[1]).is_err());
+
+        let outputs: Vec<(KeyShare, Vec<Vec<u8>>)> = members
+            .iter()
+            .map(|member| member.finalize(&[1, 3]).unwrap())
+            .collect();
+        assert!(outputs.iter().all(|output| output.1 == outputs[0].1));
+        assert_eq!(outputs[0].1.len(), 3);
+        assert_eq!(outputs[0].1[0], old_verification_vector[0]);
+
+        // Any 3 new members sign under the unchanged group public key
+        let msg: &[u8] = b"sample";
+        let bn256 = Bn256::default();
+        let signature_shares: Vec<_> = [&outputs[0].0, &outputs[1].0, &outputs[3].0]
+            .iter()
+            .map(|share| bn256.sign_share(share, msg).unwrap())
+            .collect();
+        let signature = combine(&signature_shares).unwrap();
+        assert!(bn256
+            .verify(&signature, msg, old_verification_vector[0])
+            .is_ok());
+    }
+
+    /// Test that resharing the same share twice deals unrelated sub-shares
+    #[test]
+    fn test_reshare_fresh_shares() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let (old_shares, old_verification_vector) = split(&secret_key, b"seed", 2, 3).unwrap();
+        let old_verification_vector: Vec<&[u8]> =
+            old_verification_vector.iter().map(|v| &v[..]).collect();
+
+        let first = reshare(&old_shares[0], b"first seed", 2, 3).unwrap();
+        let second = reshare(&old_shares[0], b"second seed", 2, 3).unwrap();
+        assert_eq!(first.verification_vector[0], second.verification_vector[0]);
+        assert_ne!(first.verification_vector[1], second.verification_vector[1]);
+        for deal in [&first, &second].iter() {
+            let verification_vector: Vec<&[u8]> =
+                deal.verification_vector.iter().map(|v| &v[..]).collect();
+            let mut member = NewMember::new(1, 2, 3, &old_verification_vector).unwrap();
+            assert!(member
+                .receive_deal(1, &verification_vector, &deal.shares[0])
+                .is_ok());
+        }
+        for (first_share, second_share) in first.shares.iter().zip(&second.shares) {
+            assert_ne!(first_share.secret_key, second_share.secret_key);
+        }
+    }
+}
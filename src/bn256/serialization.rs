@@ -0,0 +1,181 @@
+//! `Serialize` and `Deserialize` for `PrivateKey`, `PublicKey` and `Signature` (`serde` feature).
+//!
+//! Human-readable formats (e.g. JSON or TOML) use lowercase hexadecimal strings, while binary
+//! formats (e.g. bincode) use the raw bytes. In both cases the encoding is the one of the
+//! functions taking key and signature bytes: 32 big endian bytes for private keys and the
+//! compressed format for public keys and signatures, which are validated when deserialized.
+use ::core::fmt;
+use ::core::marker::PhantomData;
+use alloc::vec::Vec;
+use serde::de::{self, Deserializer, Visitor};
+use serde::ser::{self, Serializer};
+use serde::{Deserialize, Serialize};
+use zeroize::Zeroizing;
+
+use super::encoding::{from_hex, to_hex};
+use super::error::Error;
+use super::{PrivateKey, PublicKey, Signature};
+use crate::ByteEncoding;
+
+/// Types encoded as bytes, with their name for error messages.
+trait Expecting: ByteEncoding<Error = Error> {
+    /// Name of the type for error messages
+    const EXPECTING: &'static str;
+}
+
+impl Expecting for PrivateKey {
+    const EXPECTING: &'static str = "a BN256 private key";
+}
+
+impl Expecting for PublicKey {
+    const EXPECTING: &'static str = "a compressed BN256 public key";
+}
+
+impl Expecting for Signature {
+    const EXPECTING: &'static str = "a compressed BN256 signature";
+}
+
+/// Function to serialize a value as hexadecimal or bytes, depending on the format.
+fn serialize<T: Expecting, S: Serializer>(value: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    let bytes = Zeroizing::new(value.encode().map_err(ser::Error::custom)?);
+    if serializer.is_human_readable() {
+        serializer.serialize_str(&Zeroizing::new(to_hex(&bytes)))
+    } else {
+        serializer.serialize_bytes(&bytes)
+    }
+}
+
+/// Function to deserialize a value from hexadecimal or bytes, depending on the format.
+fn deserialize<'de, T: Expecting, D: Deserializer<'de>>(deserializer: D) -> Result<T, D::Error> {
+    if deserializer.is_human_readable() {
+        deserializer.deserialize_str(ByteVisitor(PhantomData))
+    } else {
+        deserializer.deserialize_bytes(ByteVisitor(PhantomData))
+    }
+}
+
+/// Visitor of hexadecimal strings and bytes.
+struct ByteVisitor<T>(PhantomData<T>);
+
+impl<'de, T: Expecting> Visitor<'de> for ByteVisitor<T> {
+    type Value = T;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str(T::EXPECTING)
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<T, E> {
+        let bytes = Zeroizing::new(from_hex(value).map_err(E::custom)?);
+        self.visit_bytes(&bytes)
+    }
+
+    fn visit_bytes<E: de::Error>(self, value: &[u8]) -> Result<T, E> {
+        T::decode(value).map_err(E::custom)
+    }
+
+    fn visit_seq<A: de::SeqAccess<'de>>(self, mut seq: A) -> Result<T, A::Error> {
+        let mut bytes = Zeroizing::new(Vec::new());
+        while let Some(byte) = seq.next_element()? {
+            bytes.push(byte);
+        }
+        self.visit_bytes(&bytes)
+    }
+}
+
+impl Serialize for PrivateKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PrivateKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+impl Serialize for PublicKey {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for PublicKey {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+impl Serialize for Signature {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Signature {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize(deserializer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::{Bn256, KeyPair};
+    use crate::MultiSignature;
+
+    /// Test serializing keys and signatures to JSON (hexadecimal) and bincode (bytes)
+    #[test]
+    fn test_serde() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let key_pair = KeyPair::from_secret_key(&secret_key).unwrap();
+        let public_key = PublicKey::from_compressed(key_pair.public()).unwrap();
+        let signature =
+            Signature::from_compressed(&bn256.sign(&secret_key, b"sample").unwrap()).unwrap();
+
+        let json = serde_json::to_string(key_pair.private_key()).unwrap();
+        assert_eq!(json, format!("\"{}\"", hex::encode(&secret_key)));
+        let private_key: PrivateKey = serde_json::from_str(&json).unwrap();
+        assert_eq!(private_key.to_bytes().unwrap(), secret_key);
+
+        let json = serde_json::to_string(&public_key).unwrap();
+        assert_eq!(json, format!("\"{}\"", hex::encode(key_pair.public())));
+        assert_eq!(
+            serde_json::from_str::<PublicKey>(&json.to_uppercase()).unwrap(),
+            public_key
+        );
+        let json = serde_json::to_string(&signature).unwrap();
+        assert_eq!(serde_json::from_str::<Signature>(&json).unwrap(), signature);
+
+        let bytes = bincode::serialize(&public_key).unwrap();
+        assert_eq!(&bytes[8..], key_pair.public());
+        assert_eq!(
+            bincode::deserialize::<PublicKey>(&bytes).unwrap(),
+            public_key
+        );
+        let bytes = bincode::serialize(&signature).unwrap();
+        assert_eq!(
+            bincode::deserialize::<Signature>(&bytes).unwrap(),
+            signature
+        );
+        let bytes = bincode::serialize(&private_key).unwrap();
+        let private_key: PrivateKey = bincode::deserialize(&bytes).unwrap();
+        assert_eq!(private_key.to_bytes().unwrap(), secret_key);
+    }
+
+    /// Test that invalid encodings are rejected
+    #[test]
+    fn test_serde_invalid() {
+        // Not hexadecimal, odd length, unknown prefix and the order of the group
+        assert!(serde_json::from_str::<PublicKey>("\"zz\"").is_err());
+        assert!(serde_json::from_str::<Signature>("\"0\"").is_err());
+        assert!(serde_json::from_str::<Signature>(&format!("\"04{}\"", "00".repeat(32))).is_err());
+        assert!(serde_json::from_str::<PrivateKey>(
+            "\"30644e72e131a029b85045b68181585d2833e84879b9709143e1f593f0000001\""
+        )
+        .is_err());
+    }
+}
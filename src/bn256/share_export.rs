@@ -0,0 +1,343 @@
+//! Self-describing export format for threshold key shares, so that dealers can hand the shares
+//! over to the members of a committee.
+//!
+//! An exported share holds the index and the secret key of the share together with the group
+//! public key and the SHA-256 digest of the verification vector, so that its holder can check it
+//! belongs to the expected committee. The secret key can be encrypted with a password, with the
+//! same scrypt and AES-256-GCM construction as key files (`keyfile` feature):
+//!
+//! | Field        | Size (bytes) | Description                                     |
+//! |--------------|--------------|-------------------------------------------------|
+//! | magic        | 4            | `BLSS`                                          |
+//! | version      | 1            | `1`                                             |
+//! | encryption   | 1            | `0` (plain) or `1` (scrypt and AES-256-GCM)     |
+//! | index        | 8            | index of the participant (big endian)           |
+//! | group key    | 65           | compressed group public key                     |
+//! | vector hash  | 32           | SHA-256 of the compressed verification vector   |
+//! | secret key   | 32           | plain secret key of the share                   |
+//!
+//! Encrypted shares replace the secret key with the scrypt parameters (`log_n` in 1 byte, `r`
+//! and `p` in 4 big endian bytes each), the salt (32 bytes), the nonce (12 bytes) and the
+//! ciphertext with its tag (48 bytes). All the preceding fields are authenticated as associated
+//! data. The scrypt parameters are bounded as in key files (`keyfile::check_params`). Exported
+//! shares are armored as PEM with the label `pem::KEY_SHARE_LABEL`.
+use alloc::vec::Vec;
+use byteorder::{BigEndian, ByteOrder};
+use sha2::{Digest, Sha256};
+#[cfg(feature = "keyfile")]
+use zeroize::Zeroizing;
+
+use super::error::Error;
+#[cfg(feature = "keyfile")]
+use super::keyfile::{self, KeyFileParams};
+use super::threshold::{verify_key_share, KeyShare, PUBLIC_KEY_LENGTH, SECRET_KEY_LENGTH};
+
+/// Magic bytes identifying exported shares.
+pub const MAGIC: &[u8] = b"BLSS";
+
+/// Version of the export format.
+pub const VERSION: u8 = 1;
+
+/// Encryption of plain shares.
+const ENCRYPTION_NONE: u8 = 0;
+
+/// Encryption of password-protected shares.
+#[cfg(feature = "keyfile")]
+const ENCRYPTION_SCRYPT_AES_GCM: u8 = 1;
+
+/// Length of the fields shared by plain and encrypted shares in bytes.
+const HEADER_LENGTH: usize = 4 + 1 + 1 + 8 + PUBLIC_KEY_LENGTH + 32;
+
+/// Length of the encryption parameters in bytes.
+#[cfg(feature = "keyfile")]
+const ENCRYPTION_LENGTH: usize = 1 + 4 + 4 + keyfile::SALT_LENGTH + keyfile::NONCE_LENGTH;
+
+/// A key share together with the description of the threshold key it belongs to.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExportedShare {
+    /// The key share
+    pub share: KeyShare,
+    /// The compressed group public key
+    pub group_public_key: Vec<u8>,
+    /// The SHA-256 digest of the concatenated compressed points of the verification vector
+    pub verification_vector_hash: [u8; 32],
+}
+
+impl ExportedShare {
+    /// Function to describe a key share, which is checked against the verification vector.
+    ///
+    /// # Arguments
+    ///
+    /// * `share`               - The key share
+    /// * `verification_vector` - The compressed points committing to the sharing polynomial
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `ExportedShare`; otherwise `Error`
+    pub fn new(share: KeyShare, verification_vector: &[&[u8]]) -> Result<Self, Error> {
+        verify_key_share(&share, verification_vector)?;
+
+        Ok(ExportedShare {
+            share,
+            group_public_key: verification_vector[0].to_vec(),
+            verification_vector_hash: hash_verification_vector(verification_vector),
+        })
+    }
+
+    /// Function to check that the share belongs to the threshold key of a verification vector.
+    ///
+    /// # Arguments
+    ///
+    /// * `verification_vector` - The compressed points committing to the sharing polynomial
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error::InvalidShare`
+    pub fn verify(&self, verification_vector: &[&[u8]]) -> Result<(), Error> {
+        if verification_vector.first() != Some(&&self.group_public_key[..])
+            || hash_verification_vector(verification_vector) != self.verification_vector_hash
+        {
+            return Err(Error::InvalidShare);
+        }
+
+        verify_key_share(&self.share, verification_vector)
+    }
+
+    /// Function to serialize the share without encryption.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the exported share; otherwise `Error`
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        if self.share.secret_key.len() != SECRET_KEY_LENGTH {
+            return Err(Error::InvalidLength);
+        }
+
+        Ok([&self.header(ENCRYPTION_NONE)?[..], &self.share.secret_key].concat())
+    }
+
+    /// Function to serialize the share with its secret key encrypted with a password.
+    ///
+    /// # Arguments
+    ///
+    /// * `password` - The password
+    /// * `params`   - The scrypt parameters, within the bounds of `keyfile::check_params`
+    /// * `salt`     - 32 bytes chosen uniformly at random for every export
+    /// * `nonce`    - 12 bytes chosen uniformly at random for every export
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the exported share; otherwise `Error`
+    #[cfg(feature = "keyfile")]
+    pub fn to_encrypted_bytes(
+        &self,
+        password: &str,
+        params: KeyFileParams,
+        salt: &[u8],
+        nonce: &[u8],
+    ) -> Result<Vec<u8>, Error> {
+        if salt.len() != keyfile::SALT_LENGTH || nonce.len() != keyfile::NONCE_LENGTH {
+            return Err(Error::InvalidLength);
+        }
+        keyfile::check_params(params).map_err(|_| Error::InvalidExportedShare)?;
+        let mut bytes = self.header(ENCRYPTION_SCRYPT_AES_GCM)?;
+        bytes.push(params.log_n);
+        bytes.extend_from_slice(&params.r.to_be_bytes());
+        bytes.extend_from_slice(&params.p.to_be_bytes());
+        bytes.extend_from_slice(salt);
+        bytes.extend_from_slice(nonce);
+        let ciphertext = keyfile::seal(
+            password,
+            params,
+            salt,
+            nonce,
+            &self.share.secret_key,
+            &bytes,
+        )?;
+        bytes.extend_from_slice(&ciphertext);
+
+        Ok(bytes)
+    }
+
+    /// Function to deserialize an exported share.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes`    - The exported share
+    /// * `password` - The password, required if the share is encrypted
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `ExportedShare`; otherwise `Error::InvalidExportedShare` if the format
+    ///   is unknown or the scrypt cost exceeds the bounds of `keyfile::check_params`, or
+    ///   `Error::DecryptionFailed` if the password is missing or wrong
+    pub fn from_bytes(bytes: &[u8], password: Option<&str>) -> Result<Self, Error> {
+        if bytes.len() < HEADER_LENGTH || &bytes[0..4] != MAGIC || bytes[4] != VERSION {
+            return Err(Error::InvalidExportedShare);
+        }
+        let secret_key = match bytes[5] {
+            ENCRYPTION_NONE if bytes.len() == HEADER_LENGTH + SECRET_KEY_LENGTH => {
+                bytes[HEADER_LENGTH..].to_vec()
+            }
+            #[cfg(feature = "keyfile")]
+            ENCRYPTION_SCRYPT_AES_GCM
+                if bytes.len() == HEADER_LENGTH + ENCRYPTION_LENGTH + SECRET_KEY_LENGTH + 16 =>
+            {
+                let password = password.ok_or(Error::DecryptionFailed)?;
+                let encryption = &bytes[HEADER_LENGTH..HEADER_LENGTH + ENCRYPTION_LENGTH];
+                let params = KeyFileParams {
+                    log_n: encryption[0],
+                    r: BigEndian::read_u32(&encryption[1..5]),
+                    p: BigEndian::read_u32(&encryption[5..9]),
+                };
+                keyfile::check_params(params).map_err(|_| Error::InvalidExportedShare)?;
+                let salt = &encryption[9..9 + keyfile::SALT_LENGTH];
+                let nonce = &encryption[9 + keyfile::SALT_LENGTH..];
+                let (aad, ciphertext) = bytes.split_at(HEADER_LENGTH + ENCRYPTION_LENGTH);
+                let secret_key = Zeroizing::new(keyfile::open(
+                    password, params, salt, nonce, ciphertext, aad,
+                )?);
+                secret_key.to_vec()
+            }
+            _ => return Err(Error::InvalidExportedShare),
+        };
+        #[cfg(not(feature = "keyfile"))]
+        let _ = password;
+
+        let mut verification_vector_hash = [0u8; 32];
+        verification_vector_hash.copy_from_slice(&bytes[HEADER_LENGTH - 32..HEADER_LENGTH]);
+
+        Ok(ExportedShare {
+            share: KeyShare {
+                index: BigEndian::read_u64(&bytes[6..14]),
+                secret_key,
+            },
+            group_public_key: bytes[14..14 + PUBLIC_KEY_LENGTH].to_vec(),
+            verification_vector_hash,
+        })
+    }
+
+    /// Function to serialize the fields shared by plain and encrypted shares.
+    fn header(&self, encryption: u8) -> Result<Vec<u8>, Error> {
+        if self.group_public_key.len() != PUBLIC_KEY_LENGTH {
+            return Err(Error::InvalidLength);
+        }
+        let mut header = Vec::with_capacity(HEADER_LENGTH);
+        header.extend_from_slice(MAGIC);
+        header.push(VERSION);
+        header.push(encryption);
+        header.extend_from_slice(&self.share.index.to_be_bytes());
+        header.extend_from_slice(&self.group_public_key);
+        header.extend_from_slice(&self.verification_vector_hash);
+
+        Ok(header)
+    }
+}
+
+/// Function to hash the concatenated compressed points of a verification vector.
+fn hash_verification_vector(verification_vector: &[&[u8]]) -> [u8; 32] {
+    let mut hasher = Sha256::new();
+    for commitment in verification_vector {
+        hasher.input(commitment);
+    }
+    let mut hash = [0u8; 32];
+    hash.copy_from_slice(&hasher.result());
+
+    hash
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::threshold::split;
+
+    /// Test exporting and importing plain and encrypted shares
+    #[test]
+    fn test_export_share() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let (shares, verification_vector) = split(&secret_key, b"seed", 2, 3).unwrap();
+        let verification_vector: Vec<&[u8]> =
+            verification_vector.iter().map(|c| c.as_slice()).collect();
+        let exported = ExportedShare::new(shares[1].clone(), &verification_vector).unwrap();
+        assert!(exported.verify(&verification_vector).is_ok());
+        assert!(exported.verify(&verification_vector[..1]).is_err());
+
+        let bytes = exported.to_bytes().unwrap();
+        assert_eq!(bytes.len(), 143);
+        assert_eq!(&bytes[0..6], b"BLSS\x01\x00");
+        assert_eq!(ExportedShare::from_bytes(&bytes, None).unwrap(), exported);
+        let mut unknown_version = bytes.clone();
+        unknown_version[4] = 2;
+        for invalid in [&unknown_version[..], &bytes[1..]].iter() {
+            match ExportedShare::from_bytes(invalid, None) {
+                Err(Error::InvalidExportedShare) => {}
+                _ => panic!("Expected InvalidExportedShare error"),
+            }
+        }
+
+        // A share of another key
+        let mut other_share = shares[1].clone();
+        other_share.index = 3;
+        assert!(ExportedShare::new(other_share, &verification_vector).is_err());
+    }
+
+    /// Test encrypting exported shares with a password
+    #[cfg(feature = "keyfile")]
+    #[test]
+    fn test_export_encrypted_share() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let (shares, verification_vector) = split(&secret_key, b"seed", 2, 3).unwrap();
+        let verification_vector: Vec<&[u8]> =
+            verification_vector.iter().map(|c| c.as_slice()).collect();
+        let exported = ExportedShare::new(shares[0].clone(), &verification_vector).unwrap();
+        // Cheap scrypt parameters to keep the test fast
+        let params = KeyFileParams {
+            log_n: 4,
+            r: 8,
+            p: 1,
+        };
+
+        let bytes = exported
+            .to_encrypted_bytes("password", params, &[1u8; 32], &[2u8; 12])
+            .unwrap();
+        assert_eq!(bytes.len(), 212);
+        assert_eq!(bytes[5], 1);
+        assert_eq!(
+            ExportedShare::from_bytes(&bytes, Some("password")).unwrap(),
+            exported
+        );
+        // The metadata is authenticated
+        let mut tampered = bytes.clone();
+        tampered[13] ^= 1;
+        for (bytes, password) in [
+            (&bytes, None),
+            (&bytes, Some("wrong password")),
+            (&tampered, Some("password")),
+        ]
+        .iter()
+        {
+            match ExportedShare::from_bytes(bytes, *password) {
+                Err(Error::DecryptionFailed) => {}
+                _ => panic!("Expected DecryptionFailed error"),
+            }
+        }
+        // The scrypt cost is bounded before deriving the key
+        let mut tampered = bytes.clone();
+        tampered[HEADER_LENGTH] = 60;
+        match ExportedShare::from_bytes(&tampered, Some("password")) {
+            Err(Error::InvalidExportedShare) => {}
+            _ => panic!("Expected InvalidExportedShare error"),
+        }
+        let params = KeyFileParams {
+            log_n: 40,
+            ..params
+        };
+        assert!(exported
+            .to_encrypted_bytes("password", params, &[1u8; 32], &[2u8; 12])
+            .is_err());
+    }
+}
@@ -0,0 +1,117 @@
+//! Sets of `(public key, message)` pairs covered by an aggregated signature.
+//!
+//! The basic scheme of the [BLS IRTF draft](https://tools.ietf.org/html/draft-irtf-cfrg-bls-signature) (section 3.1)
+//! requires all the messages of an aggregate verification to be distinct, as otherwise rogue key
+//! attacks become possible. `SignatureSet::validate_distinct_messages` performs this check, which
+//! `Bn256::aggregate_verify_set` enforces for `Scheme::Basic` unless explicitly disabled with
+//! `Bn256::with_duplicate_messages`.
+use alloc::vec::Vec;
+
+use super::error::Error;
+
+/// The signers and messages covered by an aggregated signature.
+#[derive(Clone, Debug, Default)]
+pub struct SignatureSet<'a> {
+    /// Public key bytes of every signer
+    public_keys: Vec<&'a [u8]>,
+    /// Message signed by every signer (in the same order)
+    messages: Vec<&'a [u8]>,
+}
+
+impl<'a> SignatureSet<'a> {
+    /// Function to create an empty signature set.
+    pub fn new() -> Self {
+        SignatureSet::default()
+    }
+
+    /// Function to create a signature set from the public keys and messages of the signers.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_keys` - The public key bytes of every signer
+    /// * `messages`    - The message signed by every signer (in the same order)
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `SignatureSet`; otherwise `Error`
+    pub fn from_slices(public_keys: &[&'a [u8]], messages: &[&'a [u8]]) -> Result<Self, Error> {
+        if public_keys.len() != messages.len() {
+            return Err(Error::InvalidLength);
+        }
+
+        Ok(SignatureSet {
+            public_keys: public_keys.to_vec(),
+            messages: messages.to_vec(),
+        })
+    }
+
+    /// Function to add the message signed by a signer to the set.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key` - The public key bytes of the signer
+    /// * `message`    - The message signed
+    pub fn push(&mut self, public_key: &'a [u8], message: &'a [u8]) {
+        self.public_keys.push(public_key);
+        self.messages.push(message);
+    }
+
+    /// Function to get the number of signers in the set.
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Function to check whether the set is empty.
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Function to get the public keys of the signers.
+    pub fn public_keys(&self) -> &[&'a [u8]] {
+        &self.public_keys
+    }
+
+    /// Function to get the messages signed (in the same order as the public keys).
+    pub fn messages(&self) -> &[&'a [u8]] {
+        &self.messages
+    }
+
+    /// Function to check that no message appears more than once in the set.
+    ///
+    /// # Returns
+    ///
+    /// * If all messages are distinct, `Ok(())`; otherwise `Error::DuplicateMessages`
+    pub fn validate_distinct_messages(&self) -> Result<(), Error> {
+        let mut sorted = self.messages.clone();
+        sorted.sort_unstable();
+        if sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+            return Err(Error::DuplicateMessages);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Test the detection of duplicate messages
+    #[test]
+    fn test_validate_distinct_messages() {
+        let mut set = SignatureSet::new();
+        set.push(b"pk1", b"message 1");
+        set.push(b"pk2", b"message 2");
+        assert!(set.validate_distinct_messages().is_ok());
+
+        set.push(b"pk3", b"message 1");
+        assert_eq!(set.len(), 3);
+        match set.validate_distinct_messages() {
+            Err(Error::DuplicateMessages) => {}
+            _ => panic!("Expected DuplicateMessages error"),
+        }
+
+        let public_key: &[u8] = b"pk1";
+        assert!(SignatureSet::from_slices(&[public_key], &[]).is_err());
+    }
+}
@@ -0,0 +1,112 @@
+//! Signing with keys that may live outside of the process, such as in an HSM or a secure enclave.
+//!
+//! A `Signer` only has to multiply a point of G1 by its secret key and to tell its public key, so
+//! `Bn256::sign_with_signer` hashes the message (binding the public key if required by the scheme)
+//! and delegates the rest to the signer. The signatures are the same as the ones of `Bn256::sign`,
+//! so they can be verified and aggregated with the rest of the crate. `PrivateKey` and `KeyPair`
+//! implement `Signer` for keys held in memory.
+use alloc::vec::Vec;
+use bn::G1;
+
+use super::error::Error;
+use super::{core, g1_to_compressed, KeyPair, PrivateKey};
+
+/// The `Signer` trait specifies an interface for signing with a secret key that is not exposed.
+pub trait Signer {
+    /// Function to get the public key of the signer.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the compressed public key (a point in G2)
+    fn public_key(&self) -> Result<Vec<u8>, Error>;
+
+    /// Function to sign a message already hashed to G1 (`CoreSign`).
+    ///
+    /// # Arguments
+    ///
+    /// * `hash_point` - The message hashed to G1
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the compressed signature (a point in G1)
+    fn sign_hashed(&self, hash_point: G1) -> Result<Vec<u8>, Error>;
+}
+
+impl Signer for PrivateKey {
+    fn public_key(&self) -> Result<Vec<u8>, Error> {
+        super::PublicKey(core::sk_to_pk(self.0)).to_compressed()
+    }
+
+    fn sign_hashed(&self, hash_point: G1) -> Result<Vec<u8>, Error> {
+        g1_to_compressed(core::core_sign(self.0, hash_point))
+    }
+}
+
+impl Signer for KeyPair {
+    fn public_key(&self) -> Result<Vec<u8>, Error> {
+        Ok(self.public().to_vec())
+    }
+
+    fn sign_hashed(&self, hash_point: G1) -> Result<Vec<u8>, Error> {
+        self.private_key().sign_hashed(hash_point)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::{Bn256, Scheme};
+    use crate::MultiSignature;
+    use ::core::cell::Cell;
+
+    /// Signer standing for a key held by a device, which counts the signatures it produces
+    struct DeviceSigner {
+        key_pair: KeyPair,
+        signatures: Cell<usize>,
+    }
+
+    impl Signer for DeviceSigner {
+        fn public_key(&self) -> Result<Vec<u8>, Error> {
+            self.key_pair.public_key()
+        }
+
+        fn sign_hashed(&self, hash_point: G1) -> Result<Vec<u8>, Error> {
+            self.signatures.set(self.signatures.get() + 1);
+            self.key_pair.sign_hashed(hash_point)
+        }
+    }
+
+    /// Test signing with signers under the different schemes
+    #[test]
+    fn test_sign_with_signer() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let msg = hex::decode("73616d706c65").unwrap();
+        let private_key = PrivateKey::from_slice(&secret_key).unwrap();
+        let device = DeviceSigner {
+            key_pair: KeyPair::from_secret_key(&secret_key).unwrap(),
+            signatures: Cell::new(0),
+        };
+
+        for bn256 in [
+            Bn256::default(),
+            Bn256::default().with_scheme(Scheme::MessageAugmentation),
+        ]
+        .iter_mut()
+        {
+            let public_key = bn256.derive_public_key(&secret_key).unwrap();
+            assert_eq!(device.public_key().unwrap(), public_key);
+            assert_eq!(private_key.public_key().unwrap(), public_key);
+
+            let signature = bn256.sign(&secret_key, &msg).unwrap();
+            assert_eq!(
+                bn256.sign_with_signer(&private_key, &msg).unwrap(),
+                signature
+            );
+            assert_eq!(bn256.sign_with_signer(&device, &msg).unwrap(), signature);
+            assert!(bn256.verify(&signature, &msg, &public_key).is_ok());
+        }
+        assert_eq!(device.signatures.get(), 2);
+    }
+}
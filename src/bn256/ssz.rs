@@ -0,0 +1,181 @@
+//! [SSZ](https://github.com/ethereum/consensus-specs/blob/dev/ssz/simple-serialize.md) encoding and
+//! hash tree roots of public keys and signatures (`ssz` feature).
+//!
+//! Public keys and signatures are SSZ vectors of bytes (`ByteVector[65]` and `ByteVector[33]`)
+//! holding their compressed format, so they can be embedded in Eth2-style containers. As fixed
+//! size types, their encoding is the bytes themselves, and their hash tree root is the Merkle root
+//! of the bytes packed into 32-byte chunks, padded with zero chunks to a power of two.
+use alloc::vec::Vec;
+use sha2::{Digest, Sha256};
+
+use super::error::Error;
+use super::{check_length, PublicKey, Signature};
+
+/// Length of the SSZ encoding of a public key in bytes.
+pub const PUBLIC_KEY_LENGTH: usize = 65;
+
+/// Length of the SSZ encoding of a signature in bytes.
+pub const SIGNATURE_LENGTH: usize = 33;
+
+/// Length of the chunks of the hash tree root in bytes.
+const CHUNK_LENGTH: usize = 32;
+
+/// Function to encode a public key in SSZ.
+///
+/// # Arguments
+///
+/// * `public_key` - The public key bytes (a point in G2 in compressed format)
+///
+/// # Returns
+///
+/// * If successful, the 65-byte SSZ encoding; otherwise `Error`
+pub fn public_key_to_ssz(public_key: &[u8]) -> Result<Vec<u8>, Error> {
+    PublicKey::from_compressed(public_key)?.to_compressed()
+}
+
+/// Function to decode a public key from SSZ, validating it.
+///
+/// # Arguments
+///
+/// * `bytes` - The SSZ encoding of the public key (65 bytes)
+///
+/// # Returns
+///
+/// * If successful, the public key bytes (a point in G2 in compressed format); otherwise `Error`
+pub fn public_key_from_ssz(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    check_length(bytes, PUBLIC_KEY_LENGTH)?;
+
+    public_key_to_ssz(bytes)
+}
+
+/// Function to compute the hash tree root of a public key.
+///
+/// # Arguments
+///
+/// * `public_key` - The public key bytes (a point in G2 in compressed format)
+///
+/// # Returns
+///
+/// * If successful, the 32-byte hash tree root; otherwise `Error`
+pub fn public_key_hash_tree_root(public_key: &[u8]) -> Result<[u8; 32], Error> {
+    Ok(hash_tree_root(&public_key_to_ssz(public_key)?))
+}
+
+/// Function to encode a signature in SSZ.
+///
+/// # Arguments
+///
+/// * `signature` - The signature bytes (a point in G1 in compressed format)
+///
+/// # Returns
+///
+/// * If successful, the 33-byte SSZ encoding; otherwise `Error`
+pub fn signature_to_ssz(signature: &[u8]) -> Result<Vec<u8>, Error> {
+    Signature::from_compressed(signature)?.to_compressed()
+}
+
+/// Function to decode a signature from SSZ, validating it.
+///
+/// # Arguments
+///
+/// * `bytes` - The SSZ encoding of the signature (33 bytes)
+///
+/// # Returns
+///
+/// * If successful, the signature bytes (a point in G1 in compressed format); otherwise `Error`
+pub fn signature_from_ssz(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    check_length(bytes, SIGNATURE_LENGTH)?;
+
+    signature_to_ssz(bytes)
+}
+
+/// Function to compute the hash tree root of a signature.
+///
+/// # Arguments
+///
+/// * `signature` - The signature bytes (a point in G1 in compressed format)
+///
+/// # Returns
+///
+/// * If successful, the 32-byte hash tree root; otherwise `Error`
+pub fn signature_hash_tree_root(signature: &[u8]) -> Result<[u8; 32], Error> {
+    Ok(hash_tree_root(&signature_to_ssz(signature)?))
+}
+
+/// Function to compute the hash tree root of a vector of bytes (`merkleize(pack(bytes))`).
+fn hash_tree_root(bytes: &[u8]) -> [u8; 32] {
+    let mut layer: Vec<[u8; 32]> = bytes
+        .chunks(CHUNK_LENGTH)
+        .map(|chunk| {
+            let mut padded = [0u8; CHUNK_LENGTH];
+            padded[..chunk.len()].copy_from_slice(chunk);
+            padded
+        })
+        .collect();
+    layer.resize(layer.len().next_power_of_two(), [0u8; CHUNK_LENGTH]);
+
+    while layer.len() > 1 {
+        layer = layer
+            .chunks(2)
+            .map(|pair| {
+                let mut node = [0u8; 32];
+                node.copy_from_slice(&Sha256::new().chain(pair[0]).chain(pair[1]).result());
+                node
+            })
+            .collect();
+    }
+
+    layer[0]
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::Bn256;
+    use crate::MultiSignature;
+
+    fn sha256(left: &[u8], right: &[u8]) -> Vec<u8> {
+        Sha256::new().chain(left).chain(right).result().to_vec()
+    }
+
+    /// Test the encoding and hash tree roots of public keys and signatures
+    #[test]
+    fn test_ssz() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let signature = bn256.sign(&secret_key, b"sample").unwrap();
+
+        let encoded = public_key_to_ssz(&public_key).unwrap();
+        assert_eq!(encoded, public_key);
+        assert_eq!(public_key_from_ssz(&encoded).unwrap(), public_key);
+        let encoded = signature_to_ssz(&signature).unwrap();
+        assert_eq!(encoded, signature);
+        assert_eq!(signature_from_ssz(&encoded).unwrap(), signature);
+        assert!(public_key_from_ssz(&signature).is_err());
+        assert!(signature_from_ssz(&public_key[..33]).is_err());
+
+        // Three chunks padded to four
+        let mut last = [0u8; 32];
+        last[0] = public_key[64];
+        let expected = sha256(
+            &sha256(&public_key[..32], &public_key[32..64]),
+            &sha256(&last, &[0u8; 32]),
+        );
+        assert_eq!(
+            public_key_hash_tree_root(&public_key).unwrap()[..],
+            expected[..]
+        );
+
+        // Two chunks
+        let mut last = [0u8; 32];
+        last[0] = signature[32];
+        let expected = sha256(&signature[..32], &last);
+        assert_eq!(
+            signature_hash_tree_root(&signature).unwrap()[..],
+            expected[..]
+        );
+    }
+}
@@ -0,0 +1,252 @@
+//! Signing and verifying messages fed in chunks.
+//!
+//! Try and increment hashes the message followed by a counter until the digest is a valid point.
+//! `StreamingSigner` and `StreamingVerifier` feed the message through the digest `D` as it arrives
+//! and then hash the counter on copies of the digest state, so the message is never held in
+//! memory and multi-gigabyte payloads can be signed and verified. The signatures are the same as
+//! the ones of `sign`, and can be verified with `verify` and vice versa.
+//!
+//! With the `std` feature, both implement `std::io::Write`, so a file can be fed with
+//! `std::io::copy`.
+use alloc::vec::Vec;
+use bn::{G1, G2};
+use digest::generic_array::typenum::U32;
+use digest::Digest;
+
+use super::error::Error;
+use super::{core, g1_to_compressed, Bn256, PrivateKey, PublicKey};
+
+/// Digest of a message being fed in chunks, to be hashed to G1 with try and increment.
+struct MessageDigest<'a, D> {
+    /// Engine whose try and increment settings are used
+    engine: &'a Bn256<D>,
+    /// State of the digest after the input fed so far
+    digest: D,
+}
+
+impl<'a, D: Digest<OutputSize = U32> + Clone> MessageDigest<'a, D> {
+    /// Function to start the digest of a message.
+    fn new(engine: &'a Bn256<D>) -> Self {
+        MessageDigest {
+            engine,
+            digest: D::new(),
+        }
+    }
+
+    /// Function to hash the message to G1 as `hash_to_try_and_increment` does.
+    fn finalize(self) -> Result<G1, Error> {
+        let digest = self.digest;
+        let (point, _attempts) = self.engine.try_and_increment_with(|counter| {
+            let mut attempt = digest.clone();
+            attempt.input(counter);
+            let mut hash = [0; 32];
+            hash.copy_from_slice(&attempt.result());
+
+            hash
+        })?;
+
+        Ok(point)
+    }
+}
+
+/// Signer of a message fed in chunks (see `Bn256::streaming_signer`).
+pub struct StreamingSigner<'a, D> {
+    /// Digest of the message fed so far
+    message: MessageDigest<'a, D>,
+    /// Secret key of the signer
+    private_key: PrivateKey,
+}
+
+impl<'a, D: Digest<OutputSize = U32> + Clone> StreamingSigner<'a, D> {
+    /// Function to feed the next chunk of the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - The next bytes of the message
+    pub fn update(&mut self, chunk: &[u8]) -> &mut Self {
+        self.message.digest.input(chunk);
+
+        self
+    }
+
+    /// Function to sign the message fed so far.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the signature, equal to the one of `sign` for the
+    ///   whole message
+    pub fn finalize(self) -> Result<Vec<u8>, Error> {
+        let hash_point = self.message.finalize()?;
+
+        g1_to_compressed(core::core_sign(self.private_key.0, hash_point))
+    }
+}
+
+/// Verifier of a signature of a message fed in chunks (see `Bn256::streaming_verifier`).
+pub struct StreamingVerifier<'a, D> {
+    /// Digest of the message fed so far
+    message: MessageDigest<'a, D>,
+    /// Public key of the signer
+    public_key: G2,
+    /// Signature to verify
+    signature: G1,
+}
+
+impl<'a, D: Digest<OutputSize = U32> + Clone> StreamingVerifier<'a, D> {
+    /// Function to feed the next chunk of the message.
+    ///
+    /// # Arguments
+    ///
+    /// * `chunk` - The next bytes of the message
+    pub fn update(&mut self, chunk: &[u8]) -> &mut Self {
+        self.message.digest.input(chunk);
+
+        self
+    }
+
+    /// Function to verify the signature of the message fed so far.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    pub fn finalize(self) -> Result<(), Error> {
+        let hash_point = self.message.finalize()?;
+
+        core::core_verify(self.public_key, hash_point, self.signature)
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, D: Digest<OutputSize = U32> + Clone> std::io::Write for StreamingSigner<'a, D> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "std")]
+impl<'a, D: Digest<OutputSize = U32> + Clone> std::io::Write for StreamingVerifier<'a, D> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+impl<D: Digest<OutputSize = U32> + Clone> Bn256<D> {
+    /// Function to start signing a message fed in chunks with `StreamingSigner::update`.
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_key` - The secret key bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `StreamingSigner`; otherwise `Error::UnsupportedScheme` if the engine
+    ///   hashes messages with a domain separation tag, or `Error` if the secret key is not valid
+    pub fn streaming_signer(&self, secret_key: &[u8]) -> Result<StreamingSigner<'_, D>, Error> {
+        self.check_streaming()?;
+        let private_key =
+            PrivateKey::from_slice(secret_key).map_err(|error| error.input("secret key"))?;
+        let mut message = MessageDigest::new(self);
+        // The public key is prepended to the message if required by the scheme
+        if self.binds_public_key() {
+            let public_key = PublicKey(core::sk_to_pk(private_key.0)).to_compressed_array()?;
+            message.digest.input(&public_key[..]);
+        }
+
+        Ok(StreamingSigner {
+            message,
+            private_key,
+        })
+    }
+
+    /// Function to start verifying the signature of a message fed in chunks with
+    /// `StreamingVerifier::update`.
+    ///
+    /// # Arguments
+    ///
+    /// * `signature`   - The signature bytes
+    /// * `public_key`  - The public key bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `StreamingVerifier`; otherwise `Error::UnsupportedScheme` if the engine
+    ///   hashes messages with a domain separation tag, or `Error` if the signature or the public
+    ///   key are not valid
+    pub fn streaming_verifier(
+        &self,
+        signature: &[u8],
+        public_key: &[u8],
+    ) -> Result<StreamingVerifier<'_, D>, Error> {
+        self.check_streaming()?;
+        let public_key_point = self.public_key_point(public_key)?;
+        let signature_point = self.signature_point(signature)?;
+        let mut message = MessageDigest::new(self);
+        // Serialize again so that the same encoding is hashed by signers and verifiers
+        if self.binds_public_key() {
+            let public_key = PublicKey(public_key_point).to_compressed_array()?;
+            message.digest.input(&public_key[..]);
+        }
+
+        Ok(StreamingVerifier {
+            message,
+            public_key: public_key_point,
+            signature: signature_point,
+        })
+    }
+
+    /// Function to check that messages are hashed with try and increment, as hashing to G1 with
+    /// a domain separation tag (see `Bn256Builder::dst`) is not supported in chunks.
+    fn check_streaming(&self) -> Result<(), Error> {
+        if self.dst.is_some() {
+            return Err(Error::UnsupportedScheme);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::Scheme;
+    use crate::MultiSignature;
+
+    /// Test that signing and verifying in chunks matches signing and verifying at once
+    #[test]
+    fn test_streaming() {
+        let message: Vec<u8> = (0..10_000u32).map(|i| i as u8).collect();
+        for bn256 in [
+            Bn256::default(),
+            Bn256::default().with_scheme(Scheme::MessageAugmentation),
+        ]
+        .iter()
+        {
+            let secret_key = [1u8; 32];
+            let public_key = bn256.derive_public_key(&secret_key).unwrap();
+
+            let mut signer = bn256.streaming_signer(&secret_key).unwrap();
+            for chunk in message.chunks(999) {
+                signer.update(chunk);
+            }
+            let signature = signer.finalize().unwrap();
+            assert_eq!(signature, bn256.sign(&secret_key, &message).unwrap());
+
+            let mut verifier = bn256.streaming_verifier(&signature, &public_key).unwrap();
+            verifier.update(&message[..5000]).update(&message[5000..]);
+            assert!(verifier.finalize().is_ok());
+
+            let mut verifier = bn256.streaming_verifier(&signature, &public_key).unwrap();
+            verifier.update(&message[1..]);
+            assert!(verifier.finalize().is_err());
+        }
+    }
+}
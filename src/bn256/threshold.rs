@@ -0,0 +1,874 @@
+//! Threshold BLS keys based on [Shamir secret sharing](https://dl.acm.org/doi/10.1145/359168.359176).
+//!
+//! A secret key `sk` is split into `n` shares `sk_i = f(i)` of a pseudorandom polynomial
+//! `f(x) = sk + a_1 * x + ... + a_(t-1) * x^(t-1)`, so that any `t` of them can reconstruct it while
+//! fewer reveal nothing about it. The verification vector `[sk * G2, a_1 * G2, ..., a_(t-1) * G2]`
+//! commits to the polynomial as in [Feldman VSS](https://ieeexplore.ieee.org/document/4568297), so
+//! every participant can check its share with `verify_key_share` and anybody can derive the public
+//! key of a share from its index. The first entry of the vector is the group public key.
+//!
+//! Participants are indexed from `1` to `n`, as the index `0` would reveal the secret key.
+//!
+//! Every participant signs with its share using `Bn256::sign_share`, and any `t` signature shares
+//! are combined with `combine` into a signature that verifies under the group public key. Shares
+//! should be checked first with `Bn256::verify_share`, so that invalid partial signatures can be
+//! discarded and attributed to the participant that produced them.
+use ::core::fmt;
+use alloc::vec::Vec;
+use bn::{Fr, Group, G1, G2};
+use byteorder::{BigEndian, ByteOrder};
+use digest::generic_array::typenum::U32;
+use digest::Digest;
+use sha2::Sha256;
+use subtle::{Choice, ConstantTimeEq};
+use zeroize::{Zeroize, ZeroizeOnDrop, Zeroizing};
+
+use super::error::Error;
+use super::hash_to_field::hash_to_field;
+use super::{
+    core, g1_from_compressed, g1_to_compressed, g2_from_compressed, Bn256, PrivateKey, PublicKey,
+};
+
+/// Domain separation tag used for deriving the coefficients of the sharing polynomial.
+const SPLIT_DST: &[u8] = b"BLS_THRESHOLD_SPLIT_BN254_XMD:SHA-256_";
+
+/// Length of a secret key in bytes.
+pub(super) const SECRET_KEY_LENGTH: usize = 32;
+
+/// Length of a compressed signature (point in G1) in bytes.
+pub(super) const SIGNATURE_LENGTH: usize = 33;
+
+/// Length of a compressed public key (point in G2) in bytes.
+pub(super) const PUBLIC_KEY_LENGTH: usize = 65;
+
+/// Length of a serialized `KeyShare` in bytes.
+pub(super) const KEY_SHARE_LENGTH: usize = 8 + SECRET_KEY_LENGTH;
+
+/// The parameters of a threshold key: `threshold` out of `participants` shares are required to
+/// sign.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ThresholdParameters {
+    /// Number of shares required to sign
+    threshold: usize,
+    /// Number of participants holding a share
+    participants: usize,
+}
+
+impl ThresholdParameters {
+    /// Function to create checked threshold parameters.
+    ///
+    /// # Arguments
+    ///
+    /// * `threshold`    - The number of shares required to sign (between 1 and `participants`)
+    /// * `participants` - The number of participants holding a share
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `ThresholdParameters`; otherwise `Error::InvalidThreshold`
+    pub fn new(threshold: usize, participants: usize) -> Result<Self, Error> {
+        if threshold == 0 || threshold > participants {
+            return Err(Error::InvalidThreshold);
+        }
+
+        Ok(ThresholdParameters {
+            threshold,
+            participants,
+        })
+    }
+
+    /// Function to get the number of shares required to sign.
+    pub fn threshold(&self) -> usize {
+        self.threshold
+    }
+
+    /// Function to get the number of participants holding a share.
+    pub fn participants(&self) -> usize {
+        self.participants
+    }
+
+    /// Function to check that an index belongs to one of the participants.
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - The index of the participant
+    ///
+    /// # Returns
+    ///
+    /// * If the index is between 1 and `participants`, `Ok(())`; otherwise
+    ///   `Error::IndexOutOfBounds`
+    pub fn check_index(&self, index: u64) -> Result<(), Error> {
+        if index == 0 || index > self.participants as u64 {
+            return Err(Error::IndexOutOfBounds);
+        }
+
+        Ok(())
+    }
+}
+
+/// The share of a secret key held by a participant.
+#[derive(Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KeyShare {
+    /// Index of the participant (starting at `1`)
+    pub index: u64,
+    /// Secret key bytes of the share
+    pub secret_key: Vec<u8>,
+}
+
+/// A partial signature produced by a participant with its key share.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignatureShare {
+    /// Index of the participant (starting at `1`)
+    pub index: u64,
+    /// Signature bytes of the share
+    pub signature: Vec<u8>,
+}
+
+/// Formats the index of the share, redacting its secret key so that it is not written to logs.
+impl fmt::Debug for KeyShare {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter
+            .debug_struct("KeyShare")
+            .field("index", &self.index)
+            .field("secret_key", &"<redacted>")
+            .finish()
+    }
+}
+
+impl ConstantTimeEq for KeyShare {
+    fn ct_eq(&self, other: &Self) -> Choice {
+        self.index.ct_eq(&other.index) & self.secret_key[..].ct_eq(&other.secret_key[..])
+    }
+}
+
+impl PartialEq for KeyShare {
+    fn eq(&self, other: &Self) -> bool {
+        self.ct_eq(other).into()
+    }
+}
+
+impl Eq for KeyShare {}
+
+impl Zeroize for KeyShare {
+    fn zeroize(&mut self) {
+        self.secret_key.zeroize();
+    }
+}
+
+impl Drop for KeyShare {
+    fn drop(&mut self) {
+        self.zeroize();
+    }
+}
+
+impl ZeroizeOnDrop for KeyShare {}
+
+impl KeyShare {
+    /// Function to serialize the share as the big endian index (8 bytes) followed by the secret key
+    /// (32 bytes).
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of 40 bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        if self.secret_key.len() != SECRET_KEY_LENGTH {
+            return Err(Error::InvalidLength);
+        }
+
+        Ok([&self.index.to_be_bytes()[..], &self.secret_key].concat())
+    }
+
+    /// Function to deserialize a share encoded with `to_bytes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized share
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `KeyShare`; otherwise `Error`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != KEY_SHARE_LENGTH {
+            return Err(Error::InvalidLength);
+        }
+
+        Ok(KeyShare {
+            index: BigEndian::read_u64(&bytes[0..8]),
+            secret_key: bytes[8..].to_vec(),
+        })
+    }
+}
+
+impl SignatureShare {
+    /// Function to serialize the share as the big endian index (8 bytes) followed by the
+    /// compressed signature (33 bytes).
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of 41 bytes
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        if self.signature.len() != SIGNATURE_LENGTH {
+            return Err(Error::InvalidLength);
+        }
+
+        Ok([&self.index.to_be_bytes()[..], &self.signature].concat())
+    }
+
+    /// Function to deserialize a share encoded with `to_bytes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized share
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `SignatureShare`; otherwise `Error`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != 8 + SIGNATURE_LENGTH {
+            return Err(Error::InvalidLength);
+        }
+
+        Ok(SignatureShare {
+            index: BigEndian::read_u64(&bytes[0..8]),
+            signature: bytes[8..].to_vec(),
+        })
+    }
+}
+
+impl<D: Digest<OutputSize = U32>> Bn256<D> {
+    /// Function to sign a message with a key share.
+    ///
+    /// Only available if the public key is not bound into the message hashing (i.e. neither
+    /// `Scheme::MessageAugmentation` nor `with_public_key_binding`), since every share would
+    /// otherwise hash a different input.
+    ///
+    /// # Arguments
+    ///
+    /// * `share`   - The key share of the participant
+    /// * `message` - The message bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `SignatureShare` of the participant
+    pub fn sign_share<M: AsRef<[u8]>>(
+        &self,
+        share: &KeyShare,
+        message: M,
+    ) -> Result<SignatureShare, Error> {
+        let message = message.as_ref();
+        if self.binds_public_key() {
+            return Err(Error::UnsupportedScheme);
+        }
+        if share.index == 0 {
+            return Err(Error::IndexOutOfBounds);
+        }
+        let hash_point = self.hash_input(message)?;
+
+        Ok(SignatureShare {
+            index: share.index,
+            signature: self.sign_hashed(&share.secret_key, hash_point)?,
+        })
+    }
+
+    /// Function to verify the signature share of a participant against the verification vector.
+    ///
+    /// # Arguments
+    ///
+    /// * `index`               - The index of the participant
+    /// * `signature`           - The signature share bytes
+    /// * `message`             - The message bytes
+    /// * `verification_vector` - The compressed points committing to the sharing polynomial
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    pub fn verify_share<M: AsRef<[u8]>>(
+        &self,
+        index: u64,
+        signature: &[u8],
+        message: M,
+        verification_vector: &[&[u8]],
+    ) -> Result<(), Error> {
+        let message = message.as_ref();
+        if self.binds_public_key() {
+            return Err(Error::UnsupportedScheme);
+        }
+        let public_key = share_public_key_point(verification_vector, index)?;
+        let hash_point = self.hash_input(message)?;
+
+        core::core_verify(public_key, hash_point, g1_from_compressed(signature)?)
+    }
+
+    /// Function to find the signature shares that are not valid for their participants.
+    ///
+    /// # Arguments
+    ///
+    /// * `shares`              - The signature shares to be checked
+    /// * `message`             - The message bytes
+    /// * `verification_vector` - The compressed points committing to the sharing polynomial
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the indices of the participants whose shares are invalid
+    pub fn invalid_shares(
+        &self,
+        shares: &[SignatureShare],
+        message: &[u8],
+        verification_vector: &[&[u8]],
+    ) -> Result<Vec<u64>, Error> {
+        if self.binds_public_key() {
+            return Err(Error::UnsupportedScheme);
+        }
+
+        Ok(shares
+            .iter()
+            .filter(|share| {
+                self.verify_share(share.index, &share.signature, message, verification_vector)
+                    .is_err()
+            })
+            .map(|share| share.index)
+            .collect())
+    }
+
+    /// Function to combine signature shares, discarding the invalid ones.
+    ///
+    /// The shares are combined and checked under the group public key first, so that the shares
+    /// are only verified one by one if the combined signature is not valid.
+    ///
+    /// # Arguments
+    ///
+    /// * `shares`              - The signature shares to be combined
+    /// * `message`             - The message bytes
+    /// * `verification_vector` - The compressed points committing to the sharing polynomial
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the combined signature and the indices of the participants whose shares
+    ///   were discarded; `Error::InsufficientShares` if fewer than `threshold` shares are valid
+    pub fn combine_robust(
+        &self,
+        shares: &[SignatureShare],
+        message: &[u8],
+        verification_vector: &[&[u8]],
+    ) -> Result<(Vec<u8>, Vec<u64>), Error> {
+        let threshold = verification_vector.len();
+        if threshold == 0 {
+            return Err(Error::InvalidLength);
+        }
+        let group_public_key = PublicKey::from_compressed(verification_vector[0])?.0;
+        let hash_point = self.hash_input(message)?;
+        if shares.len() >= threshold {
+            if let Ok(signature) = combine(shares) {
+                let signature_point = g1_from_compressed(&signature)?;
+                if core::core_verify(group_public_key, hash_point, signature_point).is_ok() {
+                    return Ok((signature, Vec::new()));
+                }
+            }
+        }
+
+        let invalid = self.invalid_shares(shares, message, verification_vector)?;
+        let mut valid: Vec<SignatureShare> = Vec::with_capacity(shares.len());
+        for share in shares
+            .iter()
+            .filter(|share| !invalid.contains(&share.index))
+        {
+            // Keep a single share per participant
+            if valid.iter().all(|other| other.index != share.index) {
+                valid.push(share.clone());
+            }
+        }
+        if valid.len() < threshold {
+            return Err(Error::InsufficientShares);
+        }
+
+        Ok((combine(&valid[..threshold])?, invalid))
+    }
+}
+
+/// Function to combine signature shares into a signature verifiable under the group public key.
+///
+/// The shares are interpolated in the exponent with Lagrange coefficients at `0`. At least
+/// `threshold` shares of distinct participants are required, otherwise the resulting signature
+/// is not valid.
+///
+/// # Arguments
+///
+/// * `shares` - The signature shares to be combined
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the combined signature
+pub fn combine(shares: &[SignatureShare]) -> Result<Vec<u8>, Error> {
+    let indices: Vec<u64> = shares.iter().map(|share| share.index).collect();
+    let coefficients = lagrange_coefficients(&indices)?;
+    let mut signature = G1::zero();
+    for (share, coefficient) in shares.iter().zip(coefficients) {
+        signature = signature + g1_from_compressed(&share.signature)? * coefficient;
+    }
+
+    g1_to_compressed(signature)
+}
+
+/// Function to split a secret key into `participants` shares, any `threshold` of which can
+/// reconstruct it.
+///
+/// The coefficients of the polynomial are derived from the secret key and the seed with
+/// `hash_to_field`. The seed must be chosen uniformly at random for every split: splitting a key
+/// again with the same seed produces the same shares, so shares leaked from a previous split would
+/// remain valid.
+///
+/// # Arguments
+///
+/// * `secret_key`   - The secret key bytes to be shared
+/// * `seed`         - The secret random bytes from which the polynomial is derived (not empty)
+/// * `threshold`    - The number of shares required to sign (between 1 and `participants`)
+/// * `participants` - The number of shares
+///
+/// # Returns
+///
+/// * If successful, the shares of the participants `1..=participants` and the verification vector
+///   (compressed points in G2); otherwise `Error`
+pub fn split(
+    secret_key: &[u8],
+    seed: &[u8],
+    threshold: usize,
+    participants: usize,
+) -> Result<(Vec<KeyShare>, Vec<Vec<u8>>), Error> {
+    ThresholdParameters::new(threshold, participants)?;
+    if seed.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+    let PrivateKey(secret) = PrivateKey::from_slice(secret_key)?;
+    let mut coefficients = Vec::with_capacity(threshold);
+    coefficients.push(secret);
+    if threshold > 1 {
+        // `sk || seed`, so that reusing a seed for different keys does not reuse the polynomial
+        let input = Zeroizing::new([&scalar_to_bytes(secret)?[..], seed].concat());
+        coefficients.extend(hash_to_field::<Fr, Sha256>(
+            &input,
+            SPLIT_DST,
+            threshold - 1,
+        )?);
+    }
+
+    let shares = (1..=participants as u64)
+        .map(|index| {
+            let share = evaluate_polynomial(&coefficients, index_to_scalar(index));
+            Ok(KeyShare {
+                index,
+                secret_key: scalar_to_bytes(share)?,
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    let verification_vector = coefficients
+        .iter()
+        .map(|&coefficient| PublicKey(core::sk_to_pk(coefficient)).to_compressed())
+        .collect::<Result<Vec<_>, Error>>()?;
+
+    Ok((shares, verification_vector))
+}
+
+/// Function to reconstruct the secret key from at least `threshold` key shares.
+///
+/// # Warning
+///
+/// Signing never requires the secret key, so this is only intended for tests and disaster
+/// recovery. Reconstructing the key on a single machine defeats the purpose of the threshold
+/// scheme, and the result must be erased as soon as it is no longer needed. With fewer than
+/// `threshold` shares, a different (unrelated) key is returned without any error: the caller
+/// should check the public key of the result against the group public key.
+///
+/// # Arguments
+///
+/// * `shares` - The key shares of distinct participants
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the secret key
+pub fn reconstruct_secret(shares: &[KeyShare]) -> Result<Vec<u8>, Error> {
+    let indices: Vec<u64> = shares.iter().map(|share| share.index).collect();
+    let coefficients = lagrange_coefficients(&indices)?;
+    let mut secret = Fr::zero();
+    for (share, coefficient) in shares.iter().zip(coefficients) {
+        secret = secret + Fr::from_slice(&share.secret_key)? * coefficient;
+    }
+
+    scalar_to_bytes(secret)
+}
+
+/// Function to derive the public key of a share from the verification vector.
+///
+/// # Arguments
+///
+/// * `verification_vector` - The compressed points committing to the sharing polynomial
+/// * `index`               - The index of the participant
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the compressed public key of the share
+pub fn share_public_key(verification_vector: &[&[u8]], index: u64) -> Result<Vec<u8>, Error> {
+    let point = share_public_key_point(verification_vector, index)?;
+
+    PublicKey(point).to_compressed()
+}
+
+/// Function to verify a key share against the verification vector of the dealer, i.e. that
+/// `sk_i * G2 = C_0 + i * C_1 + ... + i^(t-1) * C_(t-1)`.
+///
+/// # Arguments
+///
+/// * `share`               - The key share to be verified
+/// * `verification_vector` - The compressed points committing to the sharing polynomial
+///
+/// # Returns
+///
+/// * If the share is valid, `Ok(())`; otherwise `Error`
+pub fn verify_key_share(share: &KeyShare, verification_vector: &[&[u8]]) -> Result<(), Error> {
+    let public_key = share_public_key_point(verification_vector, share.index)?;
+    let secret = Fr::from_slice(&share.secret_key)?;
+    if core::sk_to_pk(secret) != public_key {
+        return Err(Error::InvalidShare);
+    }
+
+    Ok(())
+}
+
+/// Function to evaluate the polynomial committed by the verification vector at `index` in G2.
+pub(super) fn share_public_key_point(
+    verification_vector: &[&[u8]],
+    index: u64,
+) -> Result<G2, Error> {
+    if verification_vector.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+    if index == 0 {
+        return Err(Error::IndexOutOfBounds);
+    }
+    let x = index_to_scalar(index);
+    let mut result = G2::zero();
+    // Horner's rule, from the highest degree coefficient
+    for &commitment in verification_vector.iter().rev() {
+        result = result * x + g2_from_compressed(commitment)?;
+    }
+    core::key_validate(result)?;
+
+    Ok(result)
+}
+
+/// Function to compute the Lagrange coefficients at `0` of a set of distinct participant indices.
+///
+/// # Arguments
+///
+/// * `indices` - The indices of the participants
+///
+/// # Returns
+///
+/// * If successful, the coefficient `l_i = x_1 * ... * x_k / ((x_1 - x_i) * ... * (x_k - x_i))`
+///   (skipping `x_i`) of every index
+pub(super) fn lagrange_coefficients(indices: &[u64]) -> Result<Vec<Fr>, Error> {
+    if indices.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+    if indices.contains(&0) {
+        return Err(Error::IndexOutOfBounds);
+    }
+    let mut sorted = indices.to_vec();
+    sorted.sort_unstable();
+    if sorted.windows(2).any(|pair| pair[0] == pair[1]) {
+        return Err(Error::DuplicateShareIndex);
+    }
+
+    indices
+        .iter()
+        .map(|&index| {
+            let x_i = index_to_scalar(index);
+            let mut numerator = Fr::one();
+            let mut denominator = Fr::one();
+            for &other in indices.iter().filter(|&&other| other != index) {
+                let x_j = index_to_scalar(other);
+                numerator = numerator * x_j;
+                denominator = denominator * (x_j - x_i);
+            }
+            // The denominator is not zero as the indices are distinct and smaller than the order
+            let inverse = denominator.inverse().ok_or(Error::NotMemberError)?;
+            Ok(numerator * inverse)
+        })
+        .collect()
+}
+
+/// Function to evaluate a polynomial with the given coefficients (lowest degree first) at `x`.
+pub(super) fn evaluate_polynomial(coefficients: &[Fr], x: Fr) -> Fr {
+    // Horner's rule, from the highest degree coefficient
+    coefficients
+        .iter()
+        .rev()
+        .fold(Fr::zero(), |acc, &coefficient| acc * x + coefficient)
+}
+
+/// Function to convert the index of a participant into a scalar.
+pub(super) fn index_to_scalar(index: u64) -> Fr {
+    let mut bytes = [0u8; 32];
+    bytes[24..].copy_from_slice(&index.to_be_bytes());
+
+    Fr::from_slice(&bytes).expect("a 64-bit integer is always smaller than the group order")
+}
+
+/// Function to serialize a scalar as 32 big endian bytes.
+pub(super) fn scalar_to_bytes(scalar: Fr) -> Result<Vec<u8>, Error> {
+    let mut bytes = [0u8; 32];
+    scalar.into_u256().to_big_endian(&mut bytes)?;
+
+    Ok(bytes.to_vec())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::Bn256;
+    use crate::MultiSignature;
+
+    /// Test splitting a secret key and checking the shares against the verification vector
+    #[test]
+    fn test_split() {
+        let secret_key =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let (shares, verification_vector) = split(&secret_key, b"seed", 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+        assert_eq!(verification_vector.len(), 3);
+        assert_eq!(
+            format!("{:?}", shares[0]),
+            "KeyShare { index: 1, secret_key: \"<redacted>\" }"
+        );
+        assert_eq!(
+            verification_vector[0],
+            bn256.derive_public_key(&secret_key).unwrap()
+        );
+
+        let verification_vector: Vec<&[u8]> = verification_vector.iter().map(|v| &v[..]).collect();
+        for share in &shares {
+            assert!(verify_key_share(share, &verification_vector).is_ok());
+            assert_eq!(
+                share_public_key(&verification_vector, share.index).unwrap(),
+                bn256.derive_public_key(&share.secret_key).unwrap()
+            );
+        }
+
+        // Any 3 shares interpolate the secret key at 0
+        let points = [&shares[0], &shares[2], &shares[4]];
+        let indices: Vec<u64> = points.iter().map(|share| share.index).collect();
+        let coefficients = lagrange_coefficients(&indices).unwrap();
+        let mut secret = Fr::zero();
+        for (share, coefficient) in points.iter().zip(coefficients) {
+            secret = secret + Fr::from_slice(&share.secret_key).unwrap() * coefficient;
+        }
+        assert!(secret == Fr::from_slice(&secret_key).unwrap());
+
+        // A share of a different polynomial is rejected
+        let (other_shares, _) = split(&shares[0].secret_key, b"seed", 3, 5).unwrap();
+        match verify_key_share(&other_shares[1], &verification_vector) {
+            Err(Error::InvalidShare) => {}
+            _ => panic!("Expected InvalidShare error"),
+        }
+
+        assert!(split(&secret_key, b"seed", 0, 5).is_err());
+        assert!(split(&secret_key, b"seed", 6, 5).is_err());
+        assert!(split(&secret_key, b"", 3, 5).is_err());
+    }
+
+    /// Test that splitting a key with different seeds gives unrelated shares of the same key
+    #[test]
+    fn test_split_fresh_shares() {
+        let secret_key =
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap();
+        let (first, first_vector) = split(&secret_key, b"first seed", 2, 3).unwrap();
+        let (second, second_vector) = split(&secret_key, b"second seed", 2, 3).unwrap();
+        assert_eq!(first_vector[0], second_vector[0]);
+        assert_ne!(first_vector[1], second_vector[1]);
+        for (first_share, second_share) in first.iter().zip(&second) {
+            assert_ne!(first_share.secret_key, second_share.secret_key);
+        }
+
+        assert_eq!(reconstruct_secret(&first[..2]).unwrap(), secret_key);
+        assert_eq!(reconstruct_secret(&second[1..]).unwrap(), secret_key);
+        // A share leaked from the first split does not combine with the second one
+        assert_ne!(
+            reconstruct_secret(&[first[0].clone(), second[1].clone()]).unwrap(),
+            secret_key
+        );
+    }
+
+    /// Test combining signature shares of a 3-of-5 key
+    #[test]
+    fn test_sign_share_combine() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let msg: &[u8] = b"sample";
+        let bn256 = Bn256::default();
+        let (shares, verification_vector) = split(&secret_key, b"seed", 3, 5).unwrap();
+        let signature_shares: Vec<SignatureShare> = shares
+            .iter()
+            .map(|share| bn256.sign_share(share, msg).unwrap())
+            .collect();
+
+        let signature = combine(&signature_shares[1..4]).unwrap();
+        assert!(bn256
+            .verify(&signature, msg, &verification_vector[0])
+            .is_ok());
+        // Any subset of 3 shares produces the signature of the secret key
+        assert_eq!(signature, bn256.sign(&secret_key, msg).unwrap());
+        assert_eq!(
+            combine(&[
+                signature_shares[4].clone(),
+                signature_shares[0].clone(),
+                signature_shares[2].clone()
+            ])
+            .unwrap(),
+            signature
+        );
+
+        // Fewer than 3 shares do not produce a valid signature
+        let signature = combine(&signature_shares[0..2]).unwrap();
+        assert!(bn256
+            .verify(&signature, msg, &verification_vector[0])
+            .is_err());
+        match combine(&[signature_shares[0].clone(), signature_shares[0].clone()]) {
+            Err(Error::DuplicateShareIndex) => {}
+            _ => panic!("Expected DuplicateShareIndex error"),
+        }
+    }
+
+    /// Test attributing an invalid signature share to its participant
+    #[test]
+    fn test_verify_share() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let msg: &[u8] = b"sample";
+        let bn256 = Bn256::default();
+        let (shares, verification_vector) = split(&secret_key, b"seed", 2, 3).unwrap();
+        let verification_vector: Vec<&[u8]> = verification_vector.iter().map(|v| &v[..]).collect();
+        let mut signature_shares: Vec<SignatureShare> = shares
+            .iter()
+            .map(|share| bn256.sign_share(share, msg).unwrap())
+            .collect();
+        // Participant 2 signs a different message
+        signature_shares[1] = bn256.sign_share(&shares[1], b"other").unwrap();
+
+        let invalid: Vec<u64> = signature_shares
+            .iter()
+            .filter(|share| {
+                bn256
+                    .verify_share(share.index, &share.signature, msg, &verification_vector)
+                    .is_err()
+            })
+            .map(|share| share.index)
+            .collect();
+        assert_eq!(invalid, [2]);
+        assert!(bn256
+            .verify_share(0, &signature_shares[0].signature, msg, &verification_vector)
+            .is_err());
+    }
+
+    /// Test combining signature shares with invalid contributions
+    #[test]
+    fn test_combine_robust() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let msg: &[u8] = b"sample";
+        let bn256 = Bn256::default();
+        let (shares, verification_vector) = split(&secret_key, b"seed", 2, 4).unwrap();
+        let verification_vector: Vec<&[u8]> = verification_vector.iter().map(|v| &v[..]).collect();
+        let mut signature_shares: Vec<SignatureShare> = shares
+            .iter()
+            .map(|share| bn256.sign_share(share, msg).unwrap())
+            .collect();
+
+        // All shares valid
+        let (signature, invalid) = bn256
+            .combine_robust(&signature_shares, msg, &verification_vector)
+            .unwrap();
+        assert!(invalid.is_empty());
+        assert!(bn256
+            .verify(&signature, msg, verification_vector[0])
+            .is_ok());
+
+        // Participants 1 and 3 send invalid shares
+        signature_shares[0] = bn256.sign_share(&shares[0], b"other").unwrap();
+        signature_shares[2].signature = signature_shares[3].signature.clone();
+        assert!(combine(&signature_shares[..2]).is_ok());
+        assert_eq!(
+            bn256
+                .invalid_shares(&signature_shares, msg, &verification_vector)
+                .unwrap(),
+            [1, 3]
+        );
+        let (signature, invalid) = bn256
+            .combine_robust(&signature_shares, msg, &verification_vector)
+            .unwrap();
+        assert_eq!(invalid, [1, 3]);
+        assert!(bn256
+            .verify(&signature, msg, verification_vector[0])
+            .is_ok());
+
+        // Not enough valid shares
+        match bn256.combine_robust(&signature_shares[..3], msg, &verification_vector) {
+            Err(Error::InsufficientShares) => {}
+            _ => panic!("Expected InsufficientShares error"),
+        }
+    }
+
+    /// Test serializing key and signature shares
+    #[test]
+    fn test_share_serialization() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let (shares, _) = split(&secret_key, b"seed", 2, 3).unwrap();
+        let signature_share = bn256.sign_share(&shares[2], b"sample").unwrap();
+
+        let encoded = shares[2].to_bytes().unwrap();
+        assert_eq!(encoded.len(), 40);
+        assert_eq!(&encoded[0..8], &[0, 0, 0, 0, 0, 0, 0, 3]);
+        assert_eq!(KeyShare::from_bytes(&encoded).unwrap(), shares[2]);
+        let encoded = signature_share.to_bytes().unwrap();
+        assert_eq!(encoded.len(), 41);
+        assert_eq!(
+            SignatureShare::from_bytes(&encoded).unwrap(),
+            signature_share
+        );
+
+        assert!(KeyShare::from_bytes(&encoded).is_err());
+        assert!(SignatureShare::from_bytes(&encoded[1..]).is_err());
+    }
+
+    /// Test the validation of threshold parameters and the reconstruction of the secret key
+    #[test]
+    fn test_reconstruct_secret() {
+        assert!(ThresholdParameters::new(0, 3).is_err());
+        assert!(ThresholdParameters::new(4, 3).is_err());
+        let parameters = ThresholdParameters::new(2, 3).unwrap();
+        assert_eq!((parameters.threshold(), parameters.participants()), (2, 3));
+        assert!(parameters.check_index(3).is_ok());
+        assert!(parameters.check_index(0).is_err());
+        assert!(parameters.check_index(4).is_err());
+
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let (shares, _) = split(&secret_key, b"seed", 2, 3).unwrap();
+        assert_eq!(
+            reconstruct_secret(&[shares[2].clone(), shares[0].clone()]).unwrap(),
+            secret_key
+        );
+        assert_eq!(reconstruct_secret(&shares).unwrap(), secret_key);
+        assert!(reconstruct_secret(&shares[..1]).unwrap() != secret_key);
+        assert!(reconstruct_secret(&[shares[0].clone(), shares[0].clone()]).is_err());
+    }
+}
@@ -0,0 +1,150 @@
+//! Timelock encryption to a future round of a randomness beacon.
+//!
+//! Messages are encrypted with the Boneh–Franklin identity-based encryption scheme of `ibe`, where
+//! the master public key is the public key of the beacon and the identity is the message of a
+//! round in unchained mode, `SHA-256(round)` (see `drand::round_message`), hashed to G1 with the
+//! `BLS_SIG_BN254G1_XMD:SHA-256_SVDW_RO_NUL_` ciphersuite used by beacons. The private key of the
+//! identity is exactly the signature of the round, so a ciphertext can be decrypted by anybody
+//! once the beacon of the round has been published, and by nobody before (unless `t` members of
+//! the committee collude).
+use alloc::vec::Vec;
+use byteorder::{BigEndian, ByteOrder};
+
+use super::ciphersuite::BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL;
+use super::drand::round_message;
+use super::error::Error;
+use super::ibe;
+use super::PublicKey;
+
+pub use super::ibe::SIGMA_LENGTH;
+
+/// A message encrypted to a round.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ciphertext {
+    /// Round whose signature decrypts the message
+    pub round: u64,
+    /// Ciphertext of the message for the identity of the round
+    pub ciphertext: ibe::Ciphertext,
+}
+
+impl Ciphertext {
+    /// Function to serialize the ciphertext as the big endian round (8 bytes) followed by the
+    /// serialized `ibe::Ciphertext`.
+    ///
+    /// # Returns
+    ///
+    /// * A vector of bytes with the serialized ciphertext
+    pub fn to_bytes(&self) -> Vec<u8> {
+        [&self.round.to_be_bytes()[..], &self.ciphertext.to_bytes()].concat()
+    }
+
+    /// Function to deserialize a ciphertext encoded with `to_bytes`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The serialized ciphertext
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `Ciphertext`; otherwise `Error`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() < 8 {
+            return Err(Error::InvalidLength);
+        }
+
+        Ok(Ciphertext {
+            round: BigEndian::read_u64(&bytes[0..8]),
+            ciphertext: ibe::Ciphertext::from_bytes(&bytes[8..])?,
+        })
+    }
+}
+
+/// Function to encrypt a message so that it can only be decrypted with the signature of a round.
+///
+/// # Arguments
+///
+/// * `public_key` - The public key of the beacon
+/// * `round`      - The round whose signature decrypts the message
+/// * `message`    - The message bytes (up to 8160 bytes)
+/// * `sigma`      - 32 secret bytes chosen uniformly at random for every encryption
+///
+/// # Returns
+///
+/// * If successful, the `Ciphertext`; otherwise `Error`
+pub fn encrypt(
+    public_key: &[u8],
+    round: u64,
+    message: &[u8],
+    sigma: &[u8],
+) -> Result<Ciphertext, Error> {
+    let PublicKey(public_key) = PublicKey::from_compressed(public_key)?;
+    let identity =
+        BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL.hash_to_curve(&round_message(round, None))?;
+
+    Ok(Ciphertext {
+        round,
+        ciphertext: ibe::encrypt_to_point(public_key, identity, message, sigma)?,
+    })
+}
+
+/// Function to decrypt a ciphertext with the signature of its round.
+///
+/// # Arguments
+///
+/// * `signature`  - The signature of the round (the unchained beacon)
+/// * `ciphertext` - The ciphertext
+///
+/// # Returns
+///
+/// * If successful, a vector of bytes with the message; otherwise `Error::DecryptionFailed`
+pub fn decrypt(signature: &[u8], ciphertext: &Ciphertext) -> Result<Vec<u8>, Error> {
+    ibe::decrypt(signature, &ciphertext.ciphertext)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::drand;
+    use crate::bn256::Bn256;
+    use crate::MultiSignature;
+
+    /// Test encrypting to a round and decrypting with its beacon
+    #[test]
+    fn test_encrypt_decrypt() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let suite = &BLS_SIG_BN254G1_XMD_SHA_256_SVDW_RO_NUL;
+        let sigma = [7u8; SIGMA_LENGTH];
+        let message: &[u8] = b"sealed bid of 100 tokens";
+
+        let ciphertext = encrypt(&public_key, 10, message, &sigma).unwrap();
+        assert_eq!(
+            Ciphertext::from_bytes(&ciphertext.to_bytes()).unwrap(),
+            ciphertext
+        );
+        let beacon = bn256
+            .sign_with_ciphersuite(suite, &secret_key, round_message(10, None))
+            .unwrap();
+        assert!(drand::verify_beacon(&public_key, 10, None, &beacon).is_ok());
+        assert_eq!(decrypt(&beacon, &ciphertext).unwrap(), message);
+
+        // The beacon of another round does not decrypt
+        let other = bn256
+            .sign_with_ciphersuite(suite, &secret_key, round_message(11, None))
+            .unwrap();
+        match decrypt(&other, &ciphertext) {
+            Err(Error::DecryptionFailed) => {}
+            _ => panic!("Expected DecryptionFailed error"),
+        }
+        // Tampered ciphertexts are rejected
+        let mut tampered = ciphertext.clone();
+        tampered.ciphertext.w[0] ^= 1;
+        assert!(decrypt(&beacon, &tampered).is_err());
+
+        let empty = encrypt(&public_key, 10, &[], &sigma).unwrap();
+        assert!(decrypt(&beacon, &empty).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,524 @@
+//! Verifiable random function (VRF) based on BLS signatures.
+//!
+//! Similar to the constructions of [VRF-draft-05](https://tools.ietf.org/pdf/draft-irtf-cfrg-vrf-05),
+//! the proof `pi` of an input `alpha` is the BLS signature `Gamma = sk * H(alpha)` with `H` the
+//! `hash_to_curve` map, and the output is `beta = Hash(suite_string || 0x03 || Gamma || 0x00)`.
+//! As BLS signatures are unique for a valid public key, so is the output for every input, and
+//! anybody holding the public key can check the proof with `Bn256::vrf_verify`.
+//!
+//! Proofs are encoded as the compressed point `Gamma` (33 bytes, see `VrfProof`), without any
+//! auxiliary data: unlike ECVRF proofs, the challenge and response are not needed because the
+//! proof is checked with a pairing.
+//!
+//! As proofs are BLS signatures, a committee holding a threshold key (see `threshold` and `dkg`)
+//! can evaluate the VRF without any member knowing the secret key: every member creates a partial
+//! proof with `Bn256::vrf_prove_share`, and any `t` partial proofs checked with
+//! `Bn256::vrf_verify_share` are combined with `Bn256::vrf_combine` into the proof of the group.
+//! The output is the same whichever shares are combined, so it cannot be biased by the committee.
+//!
+//! Verified outputs (or the randomness of beacons) can be expanded into any number of pseudorandom
+//! bytes with `expand_output`, or into uniformly distributed scalars with `output_to_scalars`.
+use alloc::vec::Vec;
+use bn::{Fr, Group, G1};
+use digest::generic_array::typenum::U32;
+use digest::Digest;
+use sha2::Sha256;
+
+use super::error::Error;
+use super::expand_message::expand_message_xmd;
+use super::hash_to_field::hash_to_field;
+use super::threshold::{combine, share_public_key_point, KeyShare, SignatureShare};
+use super::{
+    core, g1_from_compressed, g1_to_compressed, hash_to_curve, Bn256, PrivateKey, PublicKey,
+};
+
+/// Domain separation tag used for hashing VRF inputs to G1, also used as suite string.
+pub const VRF_DST: &[u8] = b"BLS_VRF_BN254G1_XMD:SHA-256_SVDW_RO_";
+
+/// Domain separation tag used for deriving the coefficients of `vrf_batch_verify`.
+const VRF_BATCH_DST: &[u8] = b"BLS_VRF_BATCH_BN254_XMD:SHA-256_";
+
+/// Length of an encoded VRF proof in bytes.
+pub const PROOF_LENGTH: usize = 33;
+
+/// A VRF proof, i.e. the point `Gamma` in G1.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct VrfProof(pub G1);
+
+impl VrfProof {
+    /// Function to decode a proof with full validation.
+    ///
+    /// The encoding is the tag `0x02` (even Y) or `0x03` (odd Y) followed by the X coordinate as
+    /// 32 big endian bytes. Encodings with a coordinate not reduced modulo the field order, or of
+    /// a point which is not on the curve, are rejected, so every proof has a single encoding. All
+    /// points on the curve belong to G1, as its cofactor is `1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The proof bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the `VrfProof`; otherwise `Error`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        if bytes.len() != PROOF_LENGTH {
+            return Err(Error::InvalidLength);
+        }
+        if bytes[0] != 0x02 && bytes[0] != 0x03 {
+            return Err(Error::InvalidEncoding);
+        }
+        let gamma = g1_from_compressed(bytes)?;
+        // The identity has no compressed encoding, but make sure it is never accepted
+        if gamma.is_zero() {
+            return Err(Error::InvalidGroupPoint);
+        }
+
+        Ok(VrfProof(gamma))
+    }
+
+    /// Function to encode the proof.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of 33 bytes with the compressed point `Gamma`
+    pub fn to_bytes(&self) -> Result<Vec<u8>, Error> {
+        g1_to_compressed(self.0)
+    }
+}
+
+impl<D: Digest<OutputSize = U32>> Bn256<D> {
+    /// Function to compute the VRF proof of an input (`VRF_prove`).
+    ///
+    /// # Arguments
+    ///
+    /// * `secret_key`  - The secret key bytes
+    /// * `alpha`       - The input bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the proof (a compressed point in G1)
+    pub fn vrf_prove<M: AsRef<[u8]>>(&self, secret_key: &[u8], alpha: M) -> Result<Vec<u8>, Error> {
+        let alpha = alpha.as_ref();
+        let hash_point = hash_to_curve::hash_to_curve(alpha, VRF_DST)?;
+        let PrivateKey(secret_key) = PrivateKey::from_slice(secret_key)?;
+
+        VrfProof(core::core_sign(secret_key, hash_point)).to_bytes()
+    }
+
+    /// Function to compute the VRF output of a proof (`VRF_proof_to_hash`).
+    ///
+    /// The proof is not verified, so this should only be used on proofs created locally or already
+    /// checked with `vrf_verify`.
+    ///
+    /// # Arguments
+    ///
+    /// * `proof` - The proof bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of 32 bytes with the output `beta`
+    pub fn vrf_proof_to_hash(&self, proof: &[u8]) -> Result<Vec<u8>, Error> {
+        let proof = VrfProof::from_bytes(proof)?;
+
+        self.vrf_output(&proof)
+    }
+
+    /// Function to verify a VRF proof given a public key (`VRF_verify`).
+    ///
+    /// # Arguments
+    ///
+    /// * `public_key`  - The public key bytes
+    /// * `proof`       - The proof bytes
+    /// * `alpha`       - The input bytes
+    ///
+    /// # Returns
+    ///
+    /// * If the proof is valid, a vector of 32 bytes with the output `beta`; otherwise `Error`
+    pub fn vrf_verify<M: AsRef<[u8]>>(
+        &self,
+        public_key: &[u8],
+        proof: &[u8],
+        alpha: M,
+    ) -> Result<Vec<u8>, Error> {
+        let alpha = alpha.as_ref();
+        let proof = VrfProof::from_bytes(proof)?;
+        let PublicKey(public_key) = PublicKey::from_compressed(public_key)?;
+        let hash_point = hash_to_curve::hash_to_curve(alpha, VRF_DST)?;
+        core::core_verify(public_key, hash_point, proof.0)?;
+
+        self.vrf_output(&proof)
+    }
+
+    /// Function to verify many VRF proofs at once, each one with its own public key and input.
+    ///
+    /// The proofs are combined with pseudo-random coefficients derived from all the public keys,
+    /// inputs and proofs, as in `batch_verify`. The hashes of the inputs of every distinct public
+    /// key are summed, so the batch costs one pairing per distinct key plus one:
+    /// `e(r_1 * H(alpha_1) + ..., PK_1) * ... = e(r_1 * Gamma_1 + ... + r_n * Gamma_n, G2::one)`.
+    /// If the batch fails, the proofs can be checked one by one with `vrf_verify` to find the
+    /// invalid ones.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_keys` - The public key bytes of every proof
+    /// * `proofs`      - The proof bytes
+    /// * `alphas`      - The input of every proof
+    ///
+    /// # Returns
+    ///
+    /// * If all the proofs are valid, the output `beta` of every proof; otherwise `Error`
+    pub fn vrf_batch_verify(
+        &self,
+        public_keys: &[&[u8]],
+        proofs: &[&[u8]],
+        alphas: &[&[u8]],
+    ) -> Result<Vec<Vec<u8>>, Error> {
+        let n = proofs.len();
+        if n == 0 || public_keys.len() != n || alphas.len() != n {
+            return Err(Error::InvalidLength);
+        }
+
+        let mut decoded = Vec::with_capacity(n);
+        let mut hash_points = Vec::with_capacity(n);
+        let mut transcript = Vec::new();
+        for i in 0..n {
+            decoded.push(VrfProof::from_bytes(proofs[i])?);
+            hash_points.push(hash_to_curve::hash_to_curve(alphas[i], VRF_DST)?);
+
+            // `PK_i || len(alpha_i) || alpha_i || Gamma_i`
+            transcript.extend_from_slice(public_keys[i]);
+            transcript.extend_from_slice(&(alphas[i].len() as u64).to_be_bytes());
+            transcript.extend_from_slice(alphas[i]);
+            transcript.extend_from_slice(proofs[i]);
+        }
+        let coefficients = hash_to_field::<Fr, Sha256>(&transcript, VRF_BATCH_DST, n)?;
+
+        // Sum the scaled hashes of every distinct public key
+        let mut distinct_keys: Vec<&[u8]> = Vec::new();
+        let mut key_points = Vec::new();
+        let mut key_hashes: Vec<G1> = Vec::new();
+        let mut gamma = G1::zero();
+        for i in 0..n {
+            let position = match distinct_keys.iter().position(|&key| key == public_keys[i]) {
+                Some(position) => position,
+                None => {
+                    key_points.push(PublicKey::from_compressed(public_keys[i])?.0);
+                    key_hashes.push(G1::zero());
+                    distinct_keys.push(public_keys[i]);
+                    distinct_keys.len() - 1
+                }
+            };
+            key_hashes[position] = key_hashes[position] + hash_points[i] * coefficients[i];
+            gamma = gamma + decoded[i].0 * coefficients[i];
+        }
+        core::core_aggregate_verify(&key_points, &key_hashes, gamma)?;
+
+        decoded.iter().map(|proof| self.vrf_output(proof)).collect()
+    }
+
+    /// Function to compute the partial VRF proof of an input with a key share.
+    ///
+    /// # Arguments
+    ///
+    /// * `share` - The key share of the participant
+    /// * `alpha` - The input bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the partial proof as a `SignatureShare`
+    pub fn vrf_prove_share<M: AsRef<[u8]>>(
+        &self,
+        share: &KeyShare,
+        alpha: M,
+    ) -> Result<SignatureShare, Error> {
+        Ok(SignatureShare {
+            index: share.index,
+            signature: self.vrf_prove(&share.secret_key, alpha)?,
+        })
+    }
+
+    /// Function to verify a partial VRF proof against the verification vector.
+    ///
+    /// # Arguments
+    ///
+    /// * `share`               - The partial proof
+    /// * `alpha`               - The input bytes
+    /// * `verification_vector` - The compressed points committing to the sharing polynomial
+    ///
+    /// # Returns
+    ///
+    /// * If the partial proof is valid, `Ok(())`; otherwise `Error`
+    pub fn vrf_verify_share<M: AsRef<[u8]>>(
+        &self,
+        share: &SignatureShare,
+        alpha: M,
+        verification_vector: &[&[u8]],
+    ) -> Result<(), Error> {
+        let alpha = alpha.as_ref();
+        let VrfProof(gamma) = VrfProof::from_bytes(&share.signature)?;
+        let public_key = share_public_key_point(verification_vector, share.index)?;
+        let hash_point = hash_to_curve::hash_to_curve(alpha, VRF_DST)?;
+
+        core::core_verify(public_key, hash_point, gamma)
+    }
+
+    /// Function to combine partial VRF proofs into the proof of the group.
+    ///
+    /// The partial proofs should have been checked with `vrf_verify_share`. The combined proof is
+    /// verified under the group public key before returning it.
+    ///
+    /// # Arguments
+    ///
+    /// * `shares`     - At least `threshold` partial proofs of distinct participants
+    /// * `alpha`      - The input bytes
+    /// * `public_key` - The group public key bytes
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the proof of the group and its output `beta`; otherwise `Error`
+    pub fn vrf_combine<M: AsRef<[u8]>>(
+        &self,
+        shares: &[SignatureShare],
+        alpha: M,
+        public_key: &[u8],
+    ) -> Result<(Vec<u8>, Vec<u8>), Error> {
+        let proof = combine(shares)?;
+        let beta = self.vrf_verify(public_key, &proof, alpha)?;
+
+        Ok((proof, beta))
+    }
+
+    /// Function to hash a decoded proof into the VRF output.
+    fn vrf_output(&self, proof: &VrfProof) -> Result<Vec<u8>, Error> {
+        let gamma = proof.to_bytes()?;
+        let input = [VRF_DST, &[0x03], &gamma, &[0x00]].concat();
+
+        Ok(self.calculate_hash(&input).to_vec())
+    }
+}
+
+/// Function to expand a verified VRF output (or beacon randomness) into pseudorandom bytes.
+///
+/// The output is expanded with `expand_message_xmd`, using a domain separation tag specific to
+/// every use of the randomness, so that independent streams can be derived from the same output.
+///
+/// # Arguments
+///
+/// * `output` - The verified VRF output `beta`
+/// * `dst`    - The domain separation tag of the consumer
+/// * `length` - The number of bytes (up to 8160)
+///
+/// # Returns
+///
+/// * If successful, a vector with `length` pseudorandom bytes
+pub fn expand_output(output: &[u8], dst: &[u8], length: usize) -> Result<Vec<u8>, Error> {
+    if output.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+
+    expand_message_xmd::<Sha256>(output, dst, length)
+}
+
+/// Function to derive uniformly distributed scalars from a verified VRF output (or beacon
+/// randomness).
+///
+/// Every scalar is obtained by reducing 48 pseudorandom bytes modulo the group order with
+/// `hash_to_field`, so the bias is negligible, unlike reducing 32 bytes.
+///
+/// # Arguments
+///
+/// * `output` - The verified VRF output `beta`
+/// * `dst`    - The domain separation tag of the consumer
+/// * `count`  - The number of scalars
+///
+/// # Returns
+///
+/// * If successful, a vector with `count` scalars
+pub fn output_to_scalars(output: &[u8], dst: &[u8], count: usize) -> Result<Vec<Fr>, Error> {
+    if output.is_empty() {
+        return Err(Error::InvalidLength);
+    }
+
+    hash_to_field::<Fr, Sha256>(output, dst, count)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::bn256::threshold::split;
+    use crate::MultiSignature;
+
+    /// Test proving and verifying VRF outputs
+    #[test]
+    fn test_vrf_prove_verify() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let other_key =
+            hex::decode("09afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let other_public_key = bn256.derive_public_key(&other_key).unwrap();
+
+        let proof = bn256.vrf_prove(&secret_key, b"sample").unwrap();
+        assert_eq!(proof, bn256.vrf_prove(&secret_key, b"sample").unwrap());
+        let beta = bn256.vrf_verify(&public_key, &proof, b"sample").unwrap();
+        assert_eq!(beta.len(), 32);
+        assert_eq!(beta, bn256.vrf_proof_to_hash(&proof).unwrap());
+
+        // The proof is not a regular signature of the input
+        assert!(bn256.verify(&proof, b"sample", &public_key).is_err());
+        assert!(bn256.vrf_verify(&public_key, &proof, b"other").is_err());
+        assert!(bn256
+            .vrf_verify(&other_public_key, &proof, b"sample")
+            .is_err());
+
+        let other_proof = bn256.vrf_prove(&secret_key, b"other").unwrap();
+        let other_beta = bn256
+            .vrf_verify(&public_key, &other_proof, b"other")
+            .unwrap();
+        assert!(other_beta != beta);
+    }
+
+    /// Test the validation of encoded proofs
+    #[test]
+    fn test_vrf_proof_encoding() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let proof = bn256.vrf_prove(&secret_key, b"sample").unwrap();
+        assert_eq!(proof.len(), PROOF_LENGTH);
+        let decoded = VrfProof::from_bytes(&proof).unwrap();
+        assert_eq!(decoded.to_bytes().unwrap(), proof);
+
+        match VrfProof::from_bytes(&proof[1..]) {
+            Err(Error::InvalidLength) => {}
+            _ => panic!("Expected InvalidLength error"),
+        }
+        let mut tagged = proof.clone();
+        tagged[0] = 0x04;
+        match VrfProof::from_bytes(&tagged) {
+            Err(Error::InvalidEncoding) => {}
+            _ => panic!("Expected InvalidEncoding error"),
+        }
+        // X coordinate equal to the field modulus (non-canonical encoding of 0)
+        let modulus =
+            hex::decode("0230644e72e131a029b85045b68181585d97816a916871ca8d3c208c16d87cfd47")
+                .unwrap();
+        assert!(VrfProof::from_bytes(&modulus).is_err());
+        // X coordinate of a point which is not on the curve (x^3 + 3 is not a square for x = 4)
+        let mut off_curve = vec![0x02; PROOF_LENGTH];
+        off_curve[1..].copy_from_slice(&[0u8; 32]);
+        off_curve[32] = 4;
+        assert!(VrfProof::from_bytes(&off_curve).is_err());
+        assert!(bn256.vrf_proof_to_hash(&off_curve).is_err());
+    }
+
+    /// Test evaluating the VRF with a 2-of-3 threshold key
+    #[test]
+    fn test_threshold_vrf() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let (shares, verification_vector) = split(&secret_key, b"seed", 2, 3).unwrap();
+        let verification_vector: Vec<&[u8]> = verification_vector.iter().map(|v| &v[..]).collect();
+        let bn256 = Bn256::default();
+
+        let partial_proofs: Vec<SignatureShare> = shares
+            .iter()
+            .map(|share| bn256.vrf_prove_share(share, b"sample").unwrap())
+            .collect();
+        for partial_proof in &partial_proofs {
+            assert!(bn256
+                .vrf_verify_share(partial_proof, b"sample", &verification_vector)
+                .is_ok());
+            assert!(bn256
+                .vrf_verify_share(partial_proof, b"other", &verification_vector)
+                .is_err());
+        }
+
+        // Any two partial proofs give the output of the group key
+        let (proof, beta) = bn256
+            .vrf_combine(&partial_proofs[..2], b"sample", verification_vector[0])
+            .unwrap();
+        let (other_proof, other_beta) = bn256
+            .vrf_combine(&partial_proofs[1..], b"sample", verification_vector[0])
+            .unwrap();
+        assert_eq!(proof, other_proof);
+        assert_eq!(beta, other_beta);
+        assert_eq!(proof, bn256.vrf_prove(&secret_key, b"sample").unwrap());
+        assert!(bn256
+            .vrf_combine(&partial_proofs[..1], b"sample", verification_vector[0])
+            .is_err());
+    }
+
+    /// Test expanding VRF outputs into bytes and scalars
+    #[test]
+    fn test_output_expansion() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let bn256 = Bn256::default();
+        let public_key = bn256.derive_public_key(&secret_key).unwrap();
+        let proof = bn256.vrf_prove(&secret_key, b"sample").unwrap();
+        let beta = bn256.vrf_verify(&public_key, &proof, b"sample").unwrap();
+
+        let stream = expand_output(&beta, b"LOTTERY", 100).unwrap();
+        assert_eq!(stream.len(), 100);
+        assert_eq!(stream, expand_output(&beta, b"LOTTERY", 100).unwrap());
+        assert!(stream != expand_output(&beta, b"SHUFFLE", 100).unwrap());
+        assert!(expand_output(&[], b"LOTTERY", 32).is_err());
+
+        let scalars = output_to_scalars(&beta, b"LOTTERY", 3).unwrap();
+        assert_eq!(scalars.len(), 3);
+        assert!(scalars[0] != scalars[1]);
+        assert_eq!(scalars, output_to_scalars(&beta, b"LOTTERY", 3).unwrap());
+    }
+
+    /// Test verifying batches of VRF proofs with repeated and distinct keys
+    #[test]
+    fn test_vrf_batch_verify() {
+        let secret_keys = [
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap(),
+            hex::decode("09afa9d845ba75166b5c215767b1d6934e50c3db36e89b127b8a622b120f6721")
+                .unwrap(),
+        ];
+        let bn256 = Bn256::default();
+        let keys: Vec<Vec<u8>> = secret_keys
+            .iter()
+            .map(|secret_key| bn256.derive_public_key(secret_key).unwrap())
+            .collect();
+        let alphas: [&[u8]; 3] = [b"slot 1", b"slot 2", b"slot 3"];
+        let signers = [0, 1, 0];
+        let proofs: Vec<Vec<u8>> = signers
+            .iter()
+            .zip(&alphas)
+            .map(|(&signer, alpha)| bn256.vrf_prove(&secret_keys[signer], alpha).unwrap())
+            .collect();
+        let public_keys: Vec<&[u8]> = signers.iter().map(|&signer| &keys[signer][..]).collect();
+        let proof_refs: Vec<&[u8]> = proofs.iter().map(|p| &p[..]).collect();
+
+        let outputs = bn256
+            .vrf_batch_verify(&public_keys, &proof_refs, &alphas)
+            .unwrap();
+        for ((public_key, proof), (alpha, output)) in public_keys
+            .iter()
+            .zip(&proof_refs)
+            .zip(alphas.iter().zip(&outputs))
+        {
+            assert_eq!(*output, bn256.vrf_verify(public_key, proof, alpha).unwrap());
+        }
+
+        // Swapping two proofs of the same key breaks the batch
+        let swapped = [proof_refs[2], proof_refs[1], proof_refs[0]];
+        assert!(bn256
+            .vrf_batch_verify(&public_keys, &swapped, &alphas)
+            .is_err());
+        assert!(bn256
+            .vrf_batch_verify(&public_keys[..2], &proof_refs, &alphas)
+            .is_err());
+    }
+}
@@ -0,0 +1,428 @@
+//! C foreign function interface for the bn256 BLS signatures.
+//!
+//! The functions operate over an opaque engine handle created with `bls_bn256_new` and released
+//! with `bls_bn256_free`. Inputs are passed as pointer and length pairs, while outputs are written
+//! into caller-allocated buffers whose capacity is checked before writing:
+//!
+//! - public keys are serialized in compressed form (`BLS_BN256_PUBLIC_KEY_LENGTH` bytes)
+//! - signatures are serialized in compressed form (`BLS_BN256_SIGNATURE_LENGTH` bytes)
+//!
+//! Functions aggregating several items receive them concatenated into a single buffer together
+//! with the number of items, while messages are passed as arrays of pointers and lengths. Every
+//! function returns a `BlsStatus` code.
+//!
+//! The engine behind a handle is only read by the functions, so a handle can be shared between
+//! threads until it is released.
+//!
+//! The matching C declarations can be found in `include/bls_signatures.h`.
+use alloc::boxed::Box;
+use alloc::vec::Vec;
+use core::slice;
+use std::panic::{self, UnwindSafe};
+
+use crate::bn256::error::Error;
+use crate::bn256::Bn256;
+use crate::MultiSignature;
+
+/// Length of a compressed public key (point in G2).
+pub const BLS_BN256_PUBLIC_KEY_LENGTH: usize = 65;
+
+/// Length of a compressed signature (point in G1).
+pub const BLS_BN256_SIGNATURE_LENGTH: usize = 33;
+
+/// Status codes returned by the FFI functions.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BlsStatus {
+    /// The operation was successful.
+    Ok = 0,
+    /// A required pointer argument was null.
+    NullPointer = 1,
+    /// The output buffer is too small for the result.
+    BufferTooSmall = 2,
+    /// An input could not be decoded or had an invalid length.
+    InvalidInput = 3,
+    /// The signature verification failed.
+    VerificationFailed = 4,
+    /// An unexpected internal error occurred.
+    InternalError = 5,
+}
+
+impl From<Error> for BlsStatus {
+    fn from(error: Error) -> Self {
+        match error.kind() {
+            Error::VerificationFailed => BlsStatus::VerificationFailed,
+            Error::HashToPointError
+            | Error::TryAndIncrementExhausted
+            | Error::PointInJacobian
+            | Error::IndexOutOfBounds => BlsStatus::InternalError,
+            _ => BlsStatus::InvalidInput,
+        }
+    }
+}
+
+/// Function to run an FFI operation catching any panic, so it never unwinds into foreign code.
+fn guard<F: FnOnce() -> Result<(), BlsStatus> + UnwindSafe>(operation: F) -> BlsStatus {
+    match panic::catch_unwind(operation) {
+        Ok(Ok(())) => BlsStatus::Ok,
+        Ok(Err(status)) => status,
+        Err(_) => BlsStatus::InternalError,
+    }
+}
+
+/// Function to build a slice from a pointer and a length, checking the pointer is not null.
+unsafe fn input<'a>(data: *const u8, length: usize) -> Result<&'a [u8], BlsStatus> {
+    if data.is_null() {
+        Err(BlsStatus::NullPointer)
+    } else {
+        Ok(slice::from_raw_parts(data, length))
+    }
+}
+
+/// Function to copy `value` into the output buffer after checking its capacity.
+unsafe fn output(value: &[u8], out: *mut u8, out_length: usize) -> Result<(), BlsStatus> {
+    if out.is_null() {
+        return Err(BlsStatus::NullPointer);
+    }
+    if out_length < value.len() {
+        return Err(BlsStatus::BufferTooSmall);
+    }
+    slice::from_raw_parts_mut(out, value.len()).copy_from_slice(value);
+
+    Ok(())
+}
+
+/// Function to get a shared reference to the engine behind a handle.
+unsafe fn engine<'a>(handle: *const Bn256) -> Result<&'a Bn256, BlsStatus> {
+    handle.as_ref().ok_or(BlsStatus::NullPointer)
+}
+
+/// Function to create a new bn256 engine handle.
+///
+/// # Returns
+///
+/// * A handle to be released with `bls_bn256_free`
+#[no_mangle]
+pub extern "C" fn bls_bn256_new() -> *mut Bn256 {
+    Box::into_raw(Box::new(Bn256::default()))
+}
+
+/// Function to release an engine handle created with `bls_bn256_new`.
+///
+/// # Safety
+///
+/// `handle` must have been returned by `bls_bn256_new` and not freed before. Null is ignored.
+#[no_mangle]
+pub unsafe extern "C" fn bls_bn256_free(handle: *mut Bn256) {
+    if !handle.is_null() {
+        drop(Box::from_raw(handle));
+    }
+}
+
+/// Function to derive the compressed public key given a secret key.
+///
+/// # Safety
+///
+/// `handle` must be a valid engine handle, `secret_key` must point to `secret_key_length`
+/// readable bytes and `out` must point to `out_length` writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bls_bn256_derive_public_key(
+    handle: *mut Bn256,
+    secret_key: *const u8,
+    secret_key_length: usize,
+    out: *mut u8,
+    out_length: usize,
+) -> BlsStatus {
+    guard(|| {
+        let engine = engine(handle)?;
+        let secret_key = input(secret_key, secret_key_length)?;
+        let public_key = engine.derive_public_key(secret_key)?;
+
+        output(&public_key, out, out_length)
+    })
+}
+
+/// Function to sign a message given a secret key.
+///
+/// # Safety
+///
+/// `handle` must be a valid engine handle, `secret_key` and `message` must point to
+/// `secret_key_length` and `message_length` readable bytes and `out` must point to `out_length`
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bls_bn256_sign(
+    handle: *mut Bn256,
+    secret_key: *const u8,
+    secret_key_length: usize,
+    message: *const u8,
+    message_length: usize,
+    out: *mut u8,
+    out_length: usize,
+) -> BlsStatus {
+    guard(|| {
+        let engine = engine(handle)?;
+        let secret_key = input(secret_key, secret_key_length)?;
+        let message = input(message, message_length)?;
+        let signature = engine.sign(secret_key, message)?;
+
+        output(&signature, out, out_length)
+    })
+}
+
+/// Function to verify a signature given a public key.
+///
+/// # Safety
+///
+/// `handle` must be a valid engine handle and `signature`, `message` and `public_key` must point
+/// to `signature_length`, `message_length` and `public_key_length` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bls_bn256_verify(
+    handle: *mut Bn256,
+    signature: *const u8,
+    signature_length: usize,
+    message: *const u8,
+    message_length: usize,
+    public_key: *const u8,
+    public_key_length: usize,
+) -> BlsStatus {
+    guard(|| {
+        let engine = engine(handle)?;
+        let signature = input(signature, signature_length)?;
+        let message = input(message, message_length)?;
+        let public_key = input(public_key, public_key_length)?;
+
+        engine
+            .verify(signature, message, public_key)
+            .map_err(BlsStatus::from)
+    })
+}
+
+/// Function to aggregate `count` compressed public keys concatenated in `public_keys`.
+///
+/// # Safety
+///
+/// `handle` must be a valid engine handle, `public_keys` must point to
+/// `count * BLS_BN256_PUBLIC_KEY_LENGTH` readable bytes and `out` must point to `out_length`
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bls_bn256_aggregate_public_keys(
+    handle: *mut Bn256,
+    public_keys: *const u8,
+    count: usize,
+    out: *mut u8,
+    out_length: usize,
+) -> BlsStatus {
+    guard(|| {
+        let engine = engine(handle)?;
+        let length = count
+            .checked_mul(BLS_BN256_PUBLIC_KEY_LENGTH)
+            .ok_or(BlsStatus::InvalidInput)?;
+        let public_keys: Vec<&[u8]> = input(public_keys, length)?
+            .chunks(BLS_BN256_PUBLIC_KEY_LENGTH)
+            .collect();
+        let aggregated = engine.aggregate_public_keys(&public_keys)?;
+
+        output(&aggregated, out, out_length)
+    })
+}
+
+/// Function to aggregate `count` compressed signatures concatenated in `signatures`.
+///
+/// # Safety
+///
+/// `handle` must be a valid engine handle, `signatures` must point to
+/// `count * BLS_BN256_SIGNATURE_LENGTH` readable bytes and `out` must point to `out_length`
+/// writable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bls_bn256_aggregate_signatures(
+    handle: *mut Bn256,
+    signatures: *const u8,
+    count: usize,
+    out: *mut u8,
+    out_length: usize,
+) -> BlsStatus {
+    guard(|| {
+        let engine = engine(handle)?;
+        let length = count
+            .checked_mul(BLS_BN256_SIGNATURE_LENGTH)
+            .ok_or(BlsStatus::InvalidInput)?;
+        let signatures: Vec<&[u8]> = input(signatures, length)?
+            .chunks(BLS_BN256_SIGNATURE_LENGTH)
+            .collect();
+        let aggregated = engine.aggregate_signatures(&signatures)?;
+
+        output(&aggregated, out, out_length)
+    })
+}
+
+/// Function to verify an aggregated signature of `count` messages by different signers.
+///
+/// # Safety
+///
+/// `handle` must be a valid engine handle, `public_keys` must point to
+/// `count * BLS_BN256_PUBLIC_KEY_LENGTH` readable bytes, `messages` and `message_lengths` must
+/// point to `count` message pointers and lengths, every message must point to its length in
+/// readable bytes and `signature` must point to `signature_length` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn bls_bn256_aggregate_verify(
+    handle: *mut Bn256,
+    public_keys: *const u8,
+    messages: *const *const u8,
+    message_lengths: *const usize,
+    count: usize,
+    signature: *const u8,
+    signature_length: usize,
+) -> BlsStatus {
+    guard(|| {
+        let engine = engine(handle)?;
+        let length = count
+            .checked_mul(BLS_BN256_PUBLIC_KEY_LENGTH)
+            .ok_or(BlsStatus::InvalidInput)?;
+        let public_keys: Vec<&[u8]> = input(public_keys, length)?
+            .chunks(BLS_BN256_PUBLIC_KEY_LENGTH)
+            .collect();
+        if messages.is_null() || message_lengths.is_null() {
+            return Err(BlsStatus::NullPointer);
+        }
+        let messages = slice::from_raw_parts(messages, count)
+            .iter()
+            .zip(slice::from_raw_parts(message_lengths, count))
+            .map(|(&message, &message_length)| input(message, message_length))
+            .collect::<Result<Vec<&[u8]>, BlsStatus>>()?;
+        let signature = input(signature, signature_length)?;
+
+        engine
+            .aggregate_verify(&public_keys, &messages, signature)
+            .map_err(BlsStatus::from)
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Test signing and verifying through the FFI functions
+    #[test]
+    fn test_ffi_sign_verify() {
+        let secret_key =
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap();
+        let message = b"sample";
+        let mut public_key = [0u8; BLS_BN256_PUBLIC_KEY_LENGTH];
+        let mut signature = [0u8; BLS_BN256_SIGNATURE_LENGTH];
+
+        unsafe {
+            let handle = bls_bn256_new();
+            let status = bls_bn256_derive_public_key(
+                handle,
+                secret_key.as_ptr(),
+                secret_key.len(),
+                public_key.as_mut_ptr(),
+                public_key.len(),
+            );
+            assert_eq!(status, BlsStatus::Ok);
+            let status = bls_bn256_sign(
+                handle,
+                secret_key.as_ptr(),
+                secret_key.len(),
+                message.as_ptr(),
+                message.len(),
+                signature.as_mut_ptr(),
+                signature.len(),
+            );
+            assert_eq!(status, BlsStatus::Ok);
+            let status = bls_bn256_verify(
+                handle,
+                signature.as_ptr(),
+                signature.len(),
+                message.as_ptr(),
+                message.len(),
+                public_key.as_ptr(),
+                public_key.len(),
+            );
+            assert_eq!(status, BlsStatus::Ok);
+            bls_bn256_free(handle);
+        }
+
+        assert_eq!(
+            hex::encode(&signature[..]),
+            "031a2752fd966c0f24ccaa684aa0c303f430e56cf9e0917d8d2841b2a83488cbba"
+        );
+    }
+
+    /// Test aggregate verification of different messages through the FFI functions
+    #[test]
+    fn test_ffi_aggregate_verify() {
+        let secret_keys = [
+            hex::decode("1ab1126ff2e37c6e6eddea943ccb3a48f83b380b856424ee552e113595525565")
+                .unwrap(),
+            hex::decode("2009da7287c158b126123c113d1c85241b6e3294dd75c643588630a8bc0f934c")
+                .unwrap(),
+        ];
+        let messages: [&[u8]; 2] = [b"sample", b"test"];
+        let bn256 = Bn256::default();
+        let mut public_keys = Vec::new();
+        let mut signatures = Vec::new();
+        for (secret_key, message) in secret_keys.iter().zip(&messages) {
+            public_keys.extend(bn256.derive_public_key(secret_key).unwrap());
+            signatures.push(bn256.sign(secret_key, message).unwrap());
+        }
+        let signatures: Vec<&[u8]> = signatures.iter().map(|s| &s[..]).collect();
+        let signature = bn256.aggregate_signatures(&signatures).unwrap();
+        let pointers: Vec<*const u8> = messages.iter().map(|m| m.as_ptr()).collect();
+        let lengths: Vec<usize> = messages.iter().map(|m| m.len()).collect();
+
+        unsafe {
+            let handle = bls_bn256_new();
+            let status = bls_bn256_aggregate_verify(
+                handle,
+                public_keys.as_ptr(),
+                pointers.as_ptr(),
+                lengths.as_ptr(),
+                2,
+                signature.as_ptr(),
+                signature.len(),
+            );
+            assert_eq!(status, BlsStatus::Ok);
+            let status = bls_bn256_aggregate_verify(
+                handle,
+                public_keys.as_ptr(),
+                pointers.as_ptr(),
+                lengths.as_ptr(),
+                1,
+                signature.as_ptr(),
+                signature.len(),
+            );
+            assert_ne!(status, BlsStatus::Ok);
+            bls_bn256_free(handle);
+        }
+    }
+
+    /// Test FFI errors for null pointers, small buffers and short inputs
+    #[test]
+    fn test_ffi_errors() {
+        let secret_key = [0x01u8; 16];
+        let mut out = [0u8; 1];
+
+        unsafe {
+            let handle = bls_bn256_new();
+            let status = bls_bn256_derive_public_key(
+                core::ptr::null_mut(),
+                secret_key.as_ptr(),
+                secret_key.len(),
+                out.as_mut_ptr(),
+                out.len(),
+            );
+            assert_eq!(status, BlsStatus::NullPointer);
+            let status = bls_bn256_derive_public_key(
+                handle,
+                secret_key.as_ptr(),
+                secret_key.len(),
+                out.as_mut_ptr(),
+                out.len(),
+            );
+            assert_ne!(status, BlsStatus::Ok);
+            bls_bn256_free(handle);
+        }
+    }
+}
@@ -1,9 +1,209 @@
 //! This crate provides:
 //!
-//! - `MultiSignature` trait for specifying curves with multi signature support.
-//! - `bn256` module implementing the aforementioned trait for the BLS curve bn256.
+//! - `Bls` trait for specifying curves with BLS signatures over typed keys and signatures, which
+//!   are created from and serialized to bytes with the `ByteEncoding` trait.
+//! - `MultiSignature` trait for specifying curves with multi signature support over bytes, as
+//!   used by the `wasm` and `ffi` bindings.
+//! - `bn256` module implementing the aforementioned traits for the BLS curve bn256, whose engine
+//!   `Bn256` is re-exported from the crate root.
+//! - `prelude` module re-exporting the engine, the key and signature types and the `Bls` trait.
+//! - `wasm` module exporting the bn256 functionalities through `wasm-bindgen` (`wasm` feature).
+//! - `ffi` module exporting the bn256 functionalities as C functions (`ffi` feature).
+//!
+//! The crate is `no_std` compatible (it only requires `alloc`). The `std` feature is enabled by
+//! default and can be disabled with `default-features = false`.
+//!
+//! The `rand` feature enables `bn256::PrivateKey::random` for sampling secret keys from a
+//! cryptographically secure random number generator, and the `serde` feature implements
+//! `Serialize` and `Deserialize` for `bn256::PrivateKey`, `PublicKey` and `Signature` and for the
+//! messages of the threshold protocols. The `keystore`
+//! feature enables `bn256::keystore` for importing and exporting EIP-2335 keystores, and the
+//! `mnemonic` feature enables `bn256::mnemonic` for backing up secret keys as BIP-39 mnemonics.
+//! The `keyfile` feature enables `bn256::PrivateKey::save_encrypted` and `load_encrypted` for
+//! persisting secret keys in password-protected files. The `pem` feature enables `bn256::pem`
+//! for encoding keys and signatures in DER and PEM, and the `base64` feature enables the base64
+//! encodings of public keys and signatures in `bn256::encoding`. The `ssz` feature enables
+//! `bn256::ssz` for the SSZ encoding and hash tree roots of public keys and signatures, and the
+//! `cbor` feature enables `bn256::cbor` for their deterministic CBOR encoding. The `parallel`
+//! feature decompresses and adds the points of `aggregate_signatures` and `aggregate_public_keys`
+//! in parallel with `rayon`.
+#![no_std]
+
+#[cfg(feature = "std")]
+extern crate std;
+
+#[macro_use]
+extern crate alloc;
+
+use alloc::vec::Vec;
+use core::convert::TryFrom;
 
 pub mod bn256;
+pub mod prelude;
+
+pub use bn256::Bn256;
+
+#[cfg(feature = "wasm")]
+pub mod wasm;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
+
+/// The `ByteEncoding` trait specifies how keys and signatures are created from and serialized to
+/// bytes, validating them on creation.
+pub trait ByteEncoding: Sized {
+    type Error;
+
+    /// Function to create a value from its byte encoding, validating it.
+    ///
+    /// # Arguments
+    ///
+    /// * `bytes` - The encoded value
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the value; otherwise `Error`
+    fn decode(bytes: &[u8]) -> Result<Self, Self::Error>;
+
+    /// Function to serialize a value to bytes.
+    ///
+    /// # Returns
+    ///
+    /// * If successful, a vector of bytes with the encoded value; otherwise `Error`
+    fn encode(&self) -> Result<Vec<u8>, Self::Error>;
+}
+
+/// The `Bls` trait specifies an interface for BLS signatures over typed keys and signatures.
+///
+/// Unlike `MultiSignature`, whose functions take and return bytes, inputs are decoded and
+/// validated once when the `PrivateKey`, `PublicKey` and `Signature` types are created (see
+/// `ByteEncoding`), so invalid lengths or points are caught at construction.
+///
+/// Messages are taken as `AsRef<[u8]>`, so `&str`, `Vec<u8>` and byte arrays are signed and
+/// verified without slicing them first.
+///
+/// The lengths of the encodings and the identifier of the ciphersuite are associated constants,
+/// and the scalars and points behind the keys and signatures are associated types, so generic code
+/// over several curves can size buffers and validate lengths without curve-specific knowledge.
+pub trait Bls {
+    type PrivateKey: ByteEncoding<Error = Self::Error> + TryFrom<Self::Scalar, Error = Self::Error>;
+    type PublicKey: ByteEncoding<Error = Self::Error> + Into<Self::PublicKeyPoint>;
+    type Signature: ByteEncoding<Error = Self::Error> + Into<Self::SignaturePoint>;
+    /// Scalar field of the private keys
+    type Scalar;
+    /// Group of the public keys
+    type PublicKeyPoint;
+    /// Group of the signatures
+    type SignaturePoint;
+    type Error;
+
+    /// Length of the encoding of a private key in bytes.
+    const PRIVATE_KEY_LENGTH: usize;
+
+    /// Length of the encoding of a public key in bytes.
+    const PUBLIC_KEY_LENGTH: usize;
+
+    /// Length of the encoding of a signature in bytes.
+    const SIGNATURE_LENGTH: usize;
+
+    /// Identifier of the ciphersuite used by `sign` and `verify`.
+    const CIPHERSUITE_ID: &'static [u8];
+
+    /// Function to derive the public key of a private key.
+    ///
+    /// # Arguments
+    ///
+    /// * `private_key` - The private key
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the public key
+    fn derive_public_key(
+        &self,
+        private_key: &Self::PrivateKey,
+    ) -> Result<Self::PublicKey, Self::Error>;
+
+    /// Function to sign a message given a private key.
+    ///
+    /// # Arguments
+    ///
+    /// * `private_key` - The private key for signing
+    /// * `message`     - The message to be signed
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the signature
+    fn sign<M: AsRef<[u8]>>(
+        &self,
+        private_key: &Self::PrivateKey,
+        message: M,
+    ) -> Result<Self::Signature, Self::Error>;
+
+    /// Function to verify a signature given a public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `signature`   - The signature
+    /// * `message`     - The signed message
+    /// * `public_key`  - The public key of the signer
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    fn verify<M: AsRef<[u8]>>(
+        &self,
+        signature: &Self::Signature,
+        message: M,
+        public_key: &Self::PublicKey,
+    ) -> Result<(), Self::Error>;
+
+    /// Function to verify an aggregated signature of several (possibly different) messages, each
+    /// signed by a different signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_keys` - The public key of every signer
+    /// * `messages`    - The message signed by every signer (in the same order)
+    /// * `signature`   - The aggregated signature
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    fn aggregate_verify(
+        &self,
+        public_keys: &[Self::PublicKey],
+        messages: &[&[u8]],
+        signature: &Self::Signature,
+    ) -> Result<(), Self::Error>;
+
+    /// Function to aggregate public keys.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_keys` - The public keys to be aggregated
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the aggregated public key
+    fn aggregate_public_keys(
+        &self,
+        public_keys: &[Self::PublicKey],
+    ) -> Result<Self::PublicKey, Self::Error>;
+
+    /// Function to aggregate signatures.
+    ///
+    /// # Arguments
+    ///
+    /// * `signatures` - The signatures to be aggregated
+    ///
+    /// # Returns
+    ///
+    /// * If successful, the aggregated signature
+    fn aggregate_signatures(
+        &self,
+        signatures: &[Self::Signature],
+    ) -> Result<Self::Signature, Self::Error>;
+}
 
 /// The `MultiSignature` trait specifies an interface common for curves with multi signature support.
 ///
@@ -20,7 +220,7 @@ pub trait MultiSignature<PublicKey, SecretKey, Signature> {
     /// # Returns
     ///
     /// * If successful, a vector of bytes with the public key
-    fn derive_public_key(&mut self, secret_key: SecretKey) -> Result<Vec<u8>, Self::Error>;
+    fn derive_public_key(&self, secret_key: SecretKey) -> Result<Vec<u8>, Self::Error>;
 
     /// Function to sign a message given a private key.
     ///
@@ -32,7 +232,11 @@ pub trait MultiSignature<PublicKey, SecretKey, Signature> {
     /// # Returns
     ///
     /// * If successful, a vector of bytes with the signature
-    fn sign(&mut self, secret_key: SecretKey, message: &[u8]) -> Result<Vec<u8>, Self::Error>;
+    fn sign<M: AsRef<[u8]>>(
+        &self,
+        secret_key: SecretKey,
+        message: M,
+    ) -> Result<Vec<u8>, Self::Error>;
 
     /// Function to verify a signature given a public key.
     ///
@@ -45,13 +249,32 @@ pub trait MultiSignature<PublicKey, SecretKey, Signature> {
     /// # Returns
     ///
     /// * If successful, `Ok(())`; otherwise `Error`
-    fn verify(
-        &mut self,
+    fn verify<M: AsRef<[u8]>>(
+        &self,
         signature: Signature,
-        message: &[u8],
+        message: M,
         public_key: PublicKey,
     ) -> Result<(), Self::Error>;
 
+    /// Function to verify an aggregated signature of several (possibly different) messages, each
+    /// signed by a different signer.
+    ///
+    /// # Arguments
+    ///
+    /// * `public_keys` - An array with the public key of every signer
+    /// * `messages`    - An array with the message signed by every signer (in the same order)
+    /// * `signature`   - The aggregated signature
+    ///
+    /// # Returns
+    ///
+    /// * If successful, `Ok(())`; otherwise `Error`
+    fn aggregate_verify(
+        &self,
+        public_keys: &[PublicKey],
+        messages: &[&[u8]],
+        signature: Signature,
+    ) -> Result<(), Self::Error>;
+
     /// Function to aggregate public keys in their corresponding group.
     ///
     /// # Arguments
@@ -61,7 +284,7 @@ pub trait MultiSignature<PublicKey, SecretKey, Signature> {
     /// # Returns
     ///
     /// * If successful, a vector of bytes with the aggregated public key
-    fn aggregate_public_keys(&mut self, public_keys: &[PublicKey]) -> Result<Vec<u8>, Self::Error>;
+    fn aggregate_public_keys(&self, public_keys: &[PublicKey]) -> Result<Vec<u8>, Self::Error>;
 
     /// Function to aggregate signatures in their corresponding group.
     ///
@@ -72,5 +295,5 @@ pub trait MultiSignature<PublicKey, SecretKey, Signature> {
     /// # Returns
     ///
     /// * If successful, a vector of bytes with the aggregated signature
-    fn aggregate_signatures(&mut self, signatures: &[Signature]) -> Result<Vec<u8>, Self::Error>;
+    fn aggregate_signatures(&self, signatures: &[Signature]) -> Result<Vec<u8>, Self::Error>;
 }
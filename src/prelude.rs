@@ -0,0 +1,28 @@
+//! Convenience re-exports for signing and verifying with typed keys and signatures.
+//!
+//! Importing `bls_signatures_rs::prelude::*` brings the `Bn256` engine (created with
+//! `Bn256::default()` for SHA-256, or `Bn256::<D>::new()` for another digest), its `PrivateKey`,
+//! `PublicKey` and `Signature` types, the `Bls` and `ByteEncoding` traits, and
+//! `subtle::ConstantTimeEq` for comparing keys with `ct_eq` into scope.
+//! `MultiSignature` is not re-exported, as its functions over bytes have the same names as the
+//! ones of `Bls`.
+pub use crate::bn256::{Bn256, PrivateKey, PublicKey, Signature};
+pub use crate::{Bls, ByteEncoding};
+pub use subtle::ConstantTimeEq;
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    /// Test signing and verifying with the re-exported types
+    #[test]
+    fn test_prelude() {
+        let bn256 = Bn256::default();
+        let private_key = PrivateKey::decode(&[1u8; 32]).unwrap();
+        let public_key = bn256.derive_public_key(&private_key).unwrap();
+        let signature = bn256.sign(&private_key, b"sample").unwrap();
+        let signature = Signature::decode(&signature.encode().unwrap()).unwrap();
+
+        assert!(bn256.verify(&signature, b"sample", &public_key).is_ok());
+    }
+}
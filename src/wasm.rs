@@ -0,0 +1,169 @@
+//! WebAssembly bindings for the bn256 BLS signatures.
+//!
+//! Every function takes and returns `Uint8Array` friendly byte buffers. Functions aggregating
+//! several items receive them concatenated into a single buffer:
+//!
+//! - public keys are concatenated in their compressed form (`65` bytes each)
+//! - signatures are concatenated in their compressed form (`33` bytes each)
+//! - messages are concatenated together with an array holding the length of every message
+//!
+//! Errors are returned to JavaScript as exceptions carrying the error message.
+use alloc::string::ToString;
+use alloc::vec::Vec;
+use wasm_bindgen::prelude::*;
+
+use crate::bn256::Bn256;
+use crate::MultiSignature;
+
+/// Length of a compressed public key (point in G2).
+const PUBLIC_KEY_LENGTH: usize = 65;
+
+/// Length of a compressed signature (point in G1).
+const SIGNATURE_LENGTH: usize = 33;
+
+/// Function to convert a crate error into a JavaScript error value.
+fn to_js_error<E: core::fmt::Display>(error: E) -> JsValue {
+    JsValue::from_str(&error.to_string())
+}
+
+/// Function to split a buffer of concatenated items into slices of `length` bytes.
+fn split_concatenated(bytes: &[u8], length: usize) -> Result<Vec<&[u8]>, JsValue> {
+    let chunks = bytes.chunks_exact(length);
+    if !chunks.remainder().is_empty() {
+        return Err(JsValue::from_str(
+            "Input length is not a multiple of the item length",
+        ));
+    }
+
+    Ok(chunks.collect())
+}
+
+/// Function to derive the compressed public key given a secret key.
+///
+/// # Arguments
+///
+/// * `secret_key` - The secret key bytes
+///
+/// # Returns
+///
+/// * If successful, the compressed public key bytes
+#[wasm_bindgen]
+pub fn derive_public_key(secret_key: &[u8]) -> Result<Vec<u8>, JsValue> {
+    Bn256::default()
+        .derive_public_key(secret_key)
+        .map_err(to_js_error)
+}
+
+/// Function to sign a message given a secret key.
+///
+/// # Arguments
+///
+/// * `secret_key`  - The secret key bytes
+/// * `message`     - The message bytes
+///
+/// # Returns
+///
+/// * If successful, the compressed signature bytes
+#[wasm_bindgen]
+pub fn sign(secret_key: &[u8], message: &[u8]) -> Result<Vec<u8>, JsValue> {
+    Bn256::default()
+        .sign(secret_key, message)
+        .map_err(to_js_error)
+}
+
+/// Function to verify a signature given a public key.
+///
+/// # Arguments
+///
+/// * `signature`   - The compressed signature bytes
+/// * `message`     - The message bytes
+/// * `public_key`  - The compressed public key bytes
+///
+/// # Returns
+///
+/// * `true` if the signature is valid, `false` otherwise
+#[wasm_bindgen]
+pub fn verify(signature: &[u8], message: &[u8], public_key: &[u8]) -> bool {
+    Bn256::default()
+        .verify(signature, message, public_key)
+        .is_ok()
+}
+
+/// Function to verify an aggregated signature of several messages by different signers.
+///
+/// # Arguments
+///
+/// * `public_keys`     - The compressed public keys concatenated in a single buffer
+/// * `messages`        - The messages concatenated in a single buffer (in the same order)
+/// * `message_lengths` - The length of every message
+/// * `signature`       - The compressed aggregated signature bytes
+///
+/// # Returns
+///
+/// * `true` if the signature is valid, `false` otherwise
+#[wasm_bindgen]
+pub fn aggregate_verify(
+    public_keys: &[u8],
+    messages: &[u8],
+    message_lengths: &[u32],
+    signature: &[u8],
+) -> Result<bool, JsValue> {
+    let public_keys = split_concatenated(public_keys, PUBLIC_KEY_LENGTH)?;
+    let mut split = Vec::with_capacity(message_lengths.len());
+    let mut rest = messages;
+    for &length in message_lengths {
+        if rest.len() < length as usize {
+            return Err(JsValue::from_str(
+                "Message lengths exceed the messages buffer",
+            ));
+        }
+        let (message, remaining) = rest.split_at(length as usize);
+        split.push(message);
+        rest = remaining;
+    }
+    if !rest.is_empty() {
+        return Err(JsValue::from_str(
+            "Message lengths do not cover the messages buffer",
+        ));
+    }
+
+    Ok(Bn256::default()
+        .aggregate_verify(&public_keys, &split, signature)
+        .is_ok())
+}
+
+/// Function to aggregate public keys.
+///
+/// # Arguments
+///
+/// * `public_keys` - The compressed public keys concatenated in a single buffer
+///
+/// # Returns
+///
+/// * If successful, the compressed aggregated public key bytes
+#[wasm_bindgen]
+pub fn aggregate_public_keys(public_keys: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let public_keys = split_concatenated(public_keys, PUBLIC_KEY_LENGTH)?;
+
+    Bn256::default()
+        .aggregate_public_keys(&public_keys)
+        .map_err(to_js_error)
+}
+
+/// Function to aggregate signatures.
+///
+/// # Arguments
+///
+/// * `signatures` - The compressed signatures concatenated in a single buffer
+///
+/// # Returns
+///
+/// * If successful, the compressed aggregated signature bytes
+#[wasm_bindgen]
+pub fn aggregate_signatures(signatures: &[u8]) -> Result<Vec<u8>, JsValue> {
+    let signatures = split_concatenated(signatures, SIGNATURE_LENGTH)?;
+
+    Bn256::default()
+        .aggregate_signatures(&signatures)
+        .map_err(to_js_error)
+}